@@ -1,4 +1,4 @@
-#[derive(Debug, Clone, PartialEq, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Default)]
 pub struct Position {
     pub line: usize,
     pub column: usize,
@@ -42,6 +42,30 @@ impl AttributeValue {
             })
             .collect()
     }
+
+    /// The whitespace separated words of the value, like class names. Parts that are joined with
+    /// an expression that outputs text, like `btn-` in `btn-{{ variant }}`, are only fragments of
+    /// a word and are skipped, while `large` in `{{#if big}}large{{/if}}` is a word.
+    pub fn words(&self) -> impl Iterator<Item = &StringArea> {
+        self.parts.iter().enumerate().filter_map(|(index, part)| {
+            let Either::Left(word) = part else {
+                return None;
+            };
+            let is_joined = |neighbour: Option<&Either<StringArea, TemplateExpression>>| {
+                matches!(neighbour, Some(Either::Right(expression))
+                    if matches!(expression.kind, Construct::Expression | Construct::Statement)
+                        && (expression.area.end == word.area.start
+                            || expression.area.start == word.area.end))
+            };
+            let previous = index.checked_sub(1).and_then(|index| self.parts.get(index));
+            let joined = is_joined(previous) || is_joined(self.parts.get(index + 1));
+            (!joined).then_some(word)
+        })
+    }
+
+    pub fn has_template_expressions(&self) -> bool {
+        self.parts.iter().any(Either::is_right)
+    }
 }
 
 // TODO: Replace this with AttributeName and AttributeValue
@@ -86,12 +110,52 @@ pub struct OpenTag {
     pub index: usize,
 }
 
+impl OpenTag {
+    /// Whether the tag is never closed, as it's self-closed, like `<div />`, or a void element,
+    /// like `<br>`
+    pub fn is_void_or_self_closed(&self) -> bool {
+        self.self_closed || crate::parser::is_void_element(&self.name)
+    }
+
+    /// The areas of the element, its open tag and its close tag, if any
+    pub fn element_areas(&self, close_tag: Option<&CloseTag>) -> Vec<Area> {
+        let mut areas = vec![self.area.clone()];
+        if let Some(close_tag) = close_tag {
+            areas.push(close_tag.area.clone());
+        }
+        areas
+    }
+
+    /// The values of the attributes with the name, ignoring case. Attributes whose names are
+    /// template expressions are skipped.
+    pub fn attribute_values<'a>(
+        &'a self,
+        name: &'a str,
+    ) -> impl Iterator<Item = &'a AttributeValue> {
+        self.attributes
+            .iter()
+            .filter_map(move |attribute| match &attribute.name {
+                Either::Left(attribute_name)
+                    if attribute_name.content.eq_ignore_ascii_case(name) =>
+                {
+                    attribute.value.as_ref()
+                }
+                _ => None,
+            })
+    }
+}
+
 #[derive(Debug, PartialEq, Default, Clone)]
 pub struct CloseTag {
     pub name: String,
     pub area: Area,
     pub is_missing_end_bracket: bool, // Validated with missing_end_bracket_disallowed
     pub open_tag_index: Option<usize>, // TODO: Make rule
+    /// The close tag is omitted in the source, and implied by the next tag or the end of the
+    /// parent, like the `</li>` before another `<li>`. Its area is empty.
+    pub is_implied: bool,
+    /// The close tag may be omitted, since the next tag or the end of the parent implies it
+    pub is_optional: bool,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -131,6 +195,44 @@ pub struct Comment {
     pub content: String,
     pub area: Area,
     pub is_missing_end_bracket: bool, // Validated with missing_end_bracket_disallowed
+    pub kind: CommentKind,
+}
+
+/// The kinds of markup that are parsed like comments. HTML treats them as comments, except CDATA
+/// sections, whose content is text.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub enum CommentKind {
+    /// `<!-- content -->`
+    #[default]
+    Comment,
+    /// `<![CDATA[ content ]]>`, only in SVG and MathML
+    Cdata,
+    /// `<?xml content ?>`, which ends at the first `>` like in browsers
+    ProcessingInstruction,
+    /// `<! content >`, like a CDATA section outside of SVG and MathML
+    Bogus,
+}
+
+impl CommentKind {
+    /// The start and end delimiters around the content
+    pub fn delimiters(&self) -> (&'static str, &'static str) {
+        match self {
+            CommentKind::Comment => ("<!--", "-->"),
+            CommentKind::Cdata => ("<![CDATA[", "]]>"),
+            CommentKind::ProcessingInstruction => ("<?", ">"),
+            CommentKind::Bogus => ("<!", ">"),
+        }
+    }
+
+    /// The name of the kind in messages, like "CDATA section"
+    pub fn name(&self) -> &'static str {
+        match self {
+            CommentKind::Comment => "Comment",
+            CommentKind::Cdata => "CDATA section",
+            CommentKind::ProcessingInstruction => "Processing instruction",
+            CommentKind::Bogus => "Bogus comment",
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -148,3 +250,16 @@ pub enum Node {
     Comment(Comment),
     TemplateExpression(TemplateExpression),
 }
+
+impl Node {
+    pub fn area(&self) -> &Area {
+        match self {
+            Node::Doctype(doctype) => &doctype.area,
+            Node::OpenTag(open_tag) => &open_tag.area,
+            Node::CloseTag(close_tag) => &close_tag.area,
+            Node::Text(text) => &text.area,
+            Node::Comment(comment) => &comment.area,
+            Node::TemplateExpression(template_expression) => &template_expression.area,
+        }
+    }
+}
@@ -3,14 +3,18 @@ use crate::rule_trait::RuleTrait;
 use crate::serde_expander::expand_serde;
 use crate::template_language::TemplateLanguage;
 use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Range;
 
 #[derive(Debug)]
 pub struct Config {
     pub rules: Vec<Box<dyn RuleTrait>>, // Placeholder for rule names or identifiers
+    pub rule_values: Vec<toml::Value>,  // The config of each rule, in the same order as `rules`
     pub options: Options,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 pub struct Options {
     #[serde(default = "default_indent_size")]
     pub indent_size: usize,
@@ -18,6 +22,12 @@ pub struct Options {
     pub max_line_length: usize,
     #[serde(default)]
     pub template_language: TemplateLanguage,
+    /// Maps file globs to template languages, e.g. `"*.njk" = "jinja2"`
+    #[serde(default)]
+    pub template_languages: HashMap<String, TemplateLanguage>,
+    /// Detect the template language from a modeline comment or markers in the content
+    #[serde(default)]
+    pub sniff_template_language: bool,
 }
 
 fn default_indent_size() -> usize {
@@ -28,48 +38,180 @@ fn default_max_line_length() -> usize {
     80
 }
 
-// TODO: Change panic to Result
+/// Why a config couldn't be loaded
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigError {
+    pub message: String,
+    /// The byte range in the TOML that the error is about, if known
+    pub span: Option<Range<usize>>,
+    /// The index of the rule that couldn't be built, if the error is about a rule
+    pub rule_index: Option<usize>,
+}
+
+impl ConfigError {
+    fn new(message: String) -> Self {
+        Self {
+            message,
+            span: None,
+            rule_index: None,
+        }
+    }
+}
+
+/// The spans of the rules in the TOML, to point errors at the rule that couldn't be built
+#[derive(Deserialize, Default)]
+struct RuleSpans {
+    #[serde(default)]
+    rules: Vec<toml::Spanned<toml::Value>>,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
 impl Config {
     pub fn from_file(file_path: &str, registry: &Registry) -> Self {
+        Self::try_from_file(file_path, registry).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    pub fn try_from_file(file_path: &str, registry: &Registry) -> Result<Self, ConfigError> {
         match std::fs::read_to_string(file_path) {
-            Ok(content) => Self::from_toml(content.as_str(), registry),
-            Err(e) => {
+            Ok(content) => Self::try_from_toml(content.as_str(), registry),
+            Err(e) => Err(ConfigError::new(
                 if e.kind() == std::io::ErrorKind::NotFound {
-                    panic!("Configuration file not found: '{}' - Please check the file path and try again", file_path);
+                    format!("Configuration file not found: '{}' - Please check the file path and try again", file_path)
                 } else {
-                    panic!("Failed to load rules from '{}': {}", file_path, e);
-                }
-            }
+                    format!("Failed to load rules from '{}': {}", file_path, e)
+                },
+            )),
         }
     }
 
     pub fn from_toml(toml_str: &str, registry: &Registry) -> Self {
-        let mut value: toml::Value = toml::from_str(toml_str).expect("Failed to parse input TOML");
+        Self::try_from_toml(toml_str, registry).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    pub fn try_from_toml(toml_str: &str, registry: &Registry) -> Result<Self, ConfigError> {
+        let mut value: toml::Value =
+            toml::from_str(toml_str).map_err(|e: toml::de::Error| ConfigError {
+                span: e.span(),
+                ..ConfigError::new(format!("Failed to parse input TOML: {}", e.message()))
+            })?;
         expand_serde(&mut value);
 
-        let rules_array = value
+        let rule_values = value
             .as_table()
             .and_then(|table| table.get("rules"))
-            .and_then(|rules_value| rules_value.as_array());
+            .and_then(|rules_value| rules_value.as_array())
+            .cloned()
+            .unwrap_or_default();
 
-        let mut rules = Vec::new();
-        if let Some(rules_array) = rules_array {
-            for rule in rules_array {
-                let result = registry.build_rule_instance(rule.clone());
+        let options = Options::deserialize(value).map_err(|e| {
+            ConfigError::new(format!("Failed to deserialize options from TOML: {e}"))
+        })?;
 
-                if let Ok(rule_instance) = result {
-                    rules.push(rule_instance);
-                } else {
-                    panic!(
-                        "Failed to build rule instance from: {:?} {:?}",
-                        rule, result
-                    );
+        Self::try_from_rule_values(rule_values, options, registry).map_err(|error| {
+            let rule_spans: RuleSpans = toml::from_str(toml_str).unwrap_or_default();
+            let span = error
+                .rule_index
+                .and_then(|index| rule_spans.rules.get(index))
+                .map(|rule| rule.span());
+            ConfigError { span, ..error }
+        })
+    }
+
+    /// Builds a config from the (expanded) config of each rule, e.g. the `rule_values` of another
+    /// config with some settings changed
+    pub fn from_rule_values(
+        rule_values: Vec<toml::Value>,
+        options: Options,
+        registry: &Registry,
+    ) -> Self {
+        Self::try_from_rule_values(rule_values, options, registry)
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    pub fn try_from_rule_values(
+        rule_values: Vec<toml::Value>,
+        options: Options,
+        registry: &Registry,
+    ) -> Result<Self, ConfigError> {
+        let mut rules = Vec::new();
+        for (index, rule) in rule_values.iter().enumerate() {
+            match registry.build_rule_instance(rule.clone()) {
+                Ok(rule_instance) => rules.push(rule_instance),
+                Err(error) => {
+                    return Err(ConfigError {
+                        rule_index: Some(index),
+                        ..ConfigError::new(format!(
+                            "Invalid rule {} `{}`: {}",
+                            index + 1,
+                            rule.get("kind")
+                                .and_then(|kind| kind.as_str())
+                                .unwrap_or(""),
+                            error
+                        ))
+                    })
                 }
             }
         }
 
-        let options = Options::deserialize(value).expect("Failed to deserialize options from TOML");
+        Ok(Config {
+            options,
+            rules,
+            rule_values,
+        })
+    }
+
+    /// The name of the rule at `index`, which is its `name` or else its `kind`, like in warnings
+    pub fn rule_name(&self, index: usize) -> String {
+        let value = &self.rule_values[index];
+        let field = |key| value.get(key).and_then(|v| v.as_str()).unwrap_or("");
+
+        if field("name").is_empty() {
+            field("kind").to_string()
+        } else {
+            field("name").to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_configs_are_errors() {
+        let registry = Registry::new();
+
+        let error = Config::try_from_toml("indent_size = ", &registry).unwrap_err();
+        assert!(error.message.starts_with("Failed to parse input TOML"));
+        assert!(error.span.is_some());
+
+        let toml = "# [[rules]] in a comment\n[[rules]]\nkind = \"unknown\"";
+        let error = Config::try_from_toml(toml, &registry).unwrap_err();
+        assert_eq!(
+            error.message,
+            "Invalid rule 1 `unknown`: Unknown Rule of kind: unknown"
+        );
+        assert_eq!(error.rule_index, Some(0));
+        assert_eq!(
+            error.span.map(|span| &toml[span]),
+            Some("[[rules]]\nkind = \"unknown\"")
+        );
+
+        let toml = "rules = [{ kind = \"unknown\" }]";
+        let error = Config::try_from_toml(toml, &registry).unwrap_err();
+        assert_eq!(
+            error.span.map(|span| &toml[span]),
+            Some("{ kind = \"unknown\" }")
+        );
 
-        Config { options, rules }
+        let error = Config::try_from_file("/nonexistent/.htmlsnob.toml", &registry).unwrap_err();
+        assert!(error.message.starts_with("Configuration file not found"));
     }
 }
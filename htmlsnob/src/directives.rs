@@ -0,0 +1,213 @@
+use crate::ast::{Area, Comment, CommentKind, Position};
+use std::collections::HashMap;
+
+/// Disables rules from the comment until `htmlsnob-enable` or the end of the file
+pub const DISABLE: &str = "htmlsnob-disable";
+/// Enables rules disabled by `htmlsnob-disable` again
+pub const ENABLE: &str = "htmlsnob-enable";
+/// Disables rules on the line after the comment
+pub const DISABLE_NEXT_LINE: &str = "htmlsnob-disable-next-line";
+
+/// Tracks the directive comments seen so far, e.g. `<!-- htmlsnob-disable-next-line class_order -->`.
+/// A directive without rule names applies to all rules. The `ignore below` and `ignore above`
+/// comments disable and enable all rules. Warnings are looked up by where they start, since some
+/// are only reported when an element is closed or at the end of the document.
+#[derive(Debug, Default)]
+pub struct Directives {
+    /// The regions where rules are disabled
+    disabled_ranges: Vec<DisabledRange>,
+    /// Rules disabled on a single line, an empty list means all rules
+    disabled_lines: HashMap<usize, Vec<String>>,
+}
+
+/// A region from a `htmlsnob-disable` to a `htmlsnob-enable`, or to the end of the file
+#[derive(Debug)]
+struct DisabledRange {
+    /// The disabled rule, `None` for all rules
+    rule: Option<String>,
+    start: Position,
+    end: Option<Position>,
+}
+
+impl DisabledRange {
+    fn contains(&self, rule_name: &str, position: &Position) -> bool {
+        self.rule.as_ref().is_none_or(|rule| rule == rule_name)
+            && self.start <= *position
+            && self.end.as_ref().is_none_or(|end| position < end)
+    }
+}
+
+impl Directives {
+    pub fn track_comment(&mut self, comment: &Comment) {
+        if comment.kind != CommentKind::Comment {
+            return;
+        }
+
+        let content = comment.content.trim();
+        if content.starts_with("ignore below") {
+            self.disable(None, comment);
+            return;
+        }
+        if content.starts_with("ignore above") {
+            self.enable(|rule| rule.is_none(), comment);
+            return;
+        }
+
+        let mut words = content.split(|c: char| c == ',' || c.is_whitespace());
+        let directive = words.next().unwrap_or("");
+        let rules: Vec<String> = words
+            .filter(|word| !word.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        match directive {
+            DISABLE_NEXT_LINE => {
+                let line = comment.area.end.line + 1;
+                self.disabled_lines.entry(line).or_default().extend(rules);
+            }
+            DISABLE if rules.is_empty() => self.disable(None, comment),
+            DISABLE => {
+                for rule in rules {
+                    self.disable(Some(rule), comment);
+                }
+            }
+            ENABLE if rules.is_empty() => self.enable(|_| true, comment),
+            ENABLE => self.enable(
+                |rule| rule.is_some_and(|rule| rules.contains(rule)),
+                comment,
+            ),
+            _ => {}
+        }
+    }
+
+    /// Starts a disabled region after the comment, unless the rule is disabled already
+    fn disable(&mut self, rule: Option<String>, comment: &Comment) {
+        let is_open = self
+            .disabled_ranges
+            .iter()
+            .any(|range| range.end.is_none() && range.rule == rule);
+        if !is_open {
+            self.disabled_ranges.push(DisabledRange {
+                rule,
+                start: comment.area.end.clone(),
+                end: None,
+            });
+        }
+    }
+
+    /// Ends the disabled regions of the matching rules before the comment
+    fn enable(&mut self, matches: impl Fn(Option<&String>) -> bool, comment: &Comment) {
+        for range in &mut self.disabled_ranges {
+            if range.end.is_none() && matches(range.rule.as_ref()) {
+                range.end = Some(comment.area.start.clone());
+            }
+        }
+    }
+
+    /// Extends a `htmlsnob-disable-next-line` to all lines of the node that starts on that line,
+    /// e.g. a tag with its attributes on separate lines
+    pub fn track_node(&mut self, area: &Area) {
+        if let Some(rules) = self.disabled_lines.get(&area.start.line).cloned() {
+            for line in area.start.line + 1..=area.end.line {
+                self.disabled_lines
+                    .entry(line)
+                    .or_insert_with(|| rules.clone());
+            }
+        }
+    }
+
+    /// Whether the rule with the given name (or kind, if it has no name) is disabled at the
+    /// position
+    pub fn is_disabled(&self, rule_name: &str, position: &Position) -> bool {
+        self.disabled_ranges
+            .iter()
+            .any(|range| range.contains(rule_name, position))
+            || self
+                .disabled_lines
+                .get(&position.line)
+                .is_some_and(|rules| rules.is_empty() || rules.iter().any(|rule| rule == rule_name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::Position;
+
+    fn comment(content: &str, line: usize) -> Comment {
+        Comment {
+            content: content.to_string(),
+            area: Area {
+                start: Position { line, column: 0 },
+                end: Position { line, column: 0 },
+            },
+            is_missing_end_bracket: false,
+            kind: CommentKind::Comment,
+        }
+    }
+
+    fn at(line: usize) -> Position {
+        Position { line, column: 0 }
+    }
+
+    #[test]
+    fn disable_next_line() {
+        let mut directives = Directives::default();
+        directives.track_comment(&comment(" htmlsnob-disable-next-line a, b ", 3));
+
+        assert!(directives.is_disabled("a", &at(4)));
+        assert!(directives.is_disabled("b", &at(4)));
+        assert!(!directives.is_disabled("c", &at(4)));
+        assert!(!directives.is_disabled("a", &at(5)));
+    }
+
+    #[test]
+    fn disable_next_line_spans_the_next_node() {
+        let mut directives = Directives::default();
+        directives.track_comment(&comment(" htmlsnob-disable-next-line ", 0));
+        directives.track_node(&Area {
+            start: Position { line: 1, column: 2 },
+            end: Position { line: 3, column: 4 },
+        });
+
+        assert!(directives.is_disabled("a", &at(3)));
+        assert!(!directives.is_disabled("a", &at(4)));
+    }
+
+    #[test]
+    fn disable_and_enable() {
+        let mut directives = Directives::default();
+        directives.track_comment(&comment(" htmlsnob-disable a ", 2));
+        directives.track_comment(&comment(" htmlsnob-disable ", 4));
+        directives.track_comment(&comment(" htmlsnob-enable ", 6));
+
+        assert!(!directives.is_disabled("a", &at(1)));
+        assert!(directives.is_disabled("a", &at(3)));
+        assert!(!directives.is_disabled("b", &at(3)));
+        assert!(directives.is_disabled("a", &at(5)));
+        assert!(directives.is_disabled("b", &at(5)));
+        assert!(!directives.is_disabled("a", &at(7)));
+        assert!(!directives.is_disabled("b", &at(7)));
+    }
+
+    #[test]
+    fn enable_single_rule() {
+        let mut directives = Directives::default();
+        directives.track_comment(&comment(" htmlsnob-disable a, b ", 0));
+        directives.track_comment(&comment(" htmlsnob-enable a ", 2));
+
+        assert!(directives.is_disabled("a", &at(1)));
+        assert!(!directives.is_disabled("a", &at(3)));
+        assert!(directives.is_disabled("b", &at(3)));
+    }
+
+    #[test]
+    fn ignore_below_and_above() {
+        let mut directives = Directives::default();
+        directives.track_comment(&comment(" ignore below ", 0));
+        directives.track_comment(&comment(" ignore above ", 2));
+
+        assert!(directives.is_disabled("a", &at(1)));
+        assert!(!directives.is_disabled("a", &at(3)));
+    }
+}
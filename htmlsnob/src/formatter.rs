@@ -91,7 +91,7 @@ impl<'a> FormatRunner<'a> {
                         open_tag.close_tag_index.unwrap()
                     ),
                 };
-                let end_tag_string = format!("</{}>", &close_tag.name);
+                let end_tag_string = print_node(&Node::CloseTag(close_tag.clone()));
 
                 if child_count == 0 {
                     // Special-case 2: If there are no children, format inline.
@@ -137,16 +137,13 @@ impl<'a> FormatRunner<'a> {
                 if close_tag.open_tag_index.is_some() {
                     self.indent_level = self.indent_level.saturating_sub(1);
                 }
-                self.indent(format!("</{}>\n", &close_tag.name));
-            }
-            Node::Text(text) => self.indent(format!("{}\n", text.content)),
-            Node::Comment(comment) => {
-                if comment.is_missing_end_bracket {
-                    self.indent(format!("<!--{}\n", comment.content))
-                } else {
-                    self.indent(format!("<!--{}-->\n", comment.content))
+                // Implied close tags are omitted in the source, and stay omitted
+                if !close_tag.is_implied {
+                    self.indent(format!("</{}>\n", &close_tag.name));
                 }
             }
+            Node::Text(text) => self.indent(format!("{}\n", text.content)),
+            Node::Comment(_) => self.indent(format!("{}\n", print_node(node))),
             Node::Doctype(doctype) => {
                 if doctype.is_missing_end_bracket {
                     self.indent(format!("<!{}\n", doctype.content))
@@ -313,3 +310,77 @@ impl<'a> FormatRunner<'a> {
         " ".repeat(self.indent_level * self.config.options.indent_size)
     }
 }
+
+/// Prints a single node on one line, without indentation. Used to replace just the nodes that an
+/// autofix changed, instead of formatting the whole document.
+pub fn print_node(node: &Node) -> String {
+    match node {
+        Node::OpenTag(open_tag) => {
+            let mut result = format!("<{}", open_tag.name);
+            for attribute in &open_tag.attributes {
+                result.push(' ');
+                result.push_str(&print_attribute(attribute));
+            }
+
+            if open_tag.self_closed {
+                result.push_str(" />");
+            } else if !open_tag.is_missing_end_bracket {
+                result.push('>');
+            }
+            result
+        }
+        Node::CloseTag(close_tag) if close_tag.is_implied => String::new(),
+        Node::CloseTag(close_tag) => {
+            let end_bracket = if close_tag.is_missing_end_bracket {
+                ""
+            } else {
+                ">"
+            };
+            format!("</{}{}", close_tag.name, end_bracket)
+        }
+        Node::Text(text) => text.content.clone(),
+        Node::Comment(comment) => {
+            let (start, end) = comment.kind.delimiters();
+            let end_bracket = if comment.is_missing_end_bracket {
+                ""
+            } else {
+                end
+            };
+            format!("{}{}{}", start, comment.content, end_bracket)
+        }
+        Node::Doctype(doctype) => {
+            let end_bracket = if doctype.is_missing_end_bracket {
+                ""
+            } else {
+                ">"
+            };
+            format!("<!{}{}", doctype.content, end_bracket)
+        }
+        Node::TemplateExpression(template_expression) => template_expression.content.clone(),
+    }
+}
+
+/// Prints an attribute on one line, with its value parts separated by single spaces
+pub fn print_attribute(attribute: &Attribute) -> String {
+    let mut result = match &attribute.name {
+        Either::Left(string_area) => string_area.content.clone(),
+        Either::Right(template_expression) => template_expression.content.clone(),
+    };
+
+    if let Some(value) = &attribute.value {
+        result.push('=');
+        result.extend(value.start_quote);
+        let parts: Vec<&str> = value
+            .parts
+            .iter()
+            .map(|part| match part {
+                Either::Left(string_area) => string_area.content.as_str(),
+                Either::Right(template_expression) => template_expression.content.as_str(),
+            })
+            .collect();
+        result.push_str(&parts.join(" "));
+        result.extend(value.end_quote);
+    }
+
+    result
+}
@@ -1,8 +1,10 @@
 pub mod ast;
 pub mod case_converter;
 pub mod config;
+pub mod directives;
 pub mod dynamic_format;
 pub mod formatter;
+pub mod namespace;
 pub mod parser;
 pub mod registry;
 pub mod rule_trait;
@@ -12,10 +14,13 @@ pub mod test_utils;
 pub mod warning;
 
 use ast::Node;
+mod foreign_names;
 mod serde_expander;
 mod validator;
+mod void_elements;
 use formatter::Formatter;
 use parser::Parser;
+use std::time::Duration;
 
 use validator::Validator;
 // RePublish the warning::Warning struct
@@ -23,11 +28,35 @@ pub use warning::Warning;
 pub use warning::WarningSeverity;
 
 pub fn lint(input: &str, config: &mut config::Config) -> (Vec<Node>, Vec<Warning>) {
-    let mut parser = Parser::new(input, config);
     let mut validator = Validator::new(config);
+    run(input, &mut validator)
+}
+
+/// Parses the input without running the rules
+pub fn parse(input: &str, config: &config::Config) -> Vec<Node> {
+    let mut parser = Parser::new(input, config);
+    while let Some(node) = parser.next_node() {
+        parser.add_node(node);
+    }
+    parser.state.ast
+}
+
+/// Lints like `lint`, and also returns the time spent in the hooks of each rule, in the same order
+/// as `config.rules`
+pub fn lint_with_profiling(
+    input: &str,
+    config: &mut config::Config,
+) -> (Vec<Node>, Vec<Warning>, Vec<Duration>) {
+    let mut validator = Validator::with_profiling(config);
+    let (ast, warnings) = run(input, &mut validator);
+    (ast, warnings, validator.durations())
+}
+
+fn run(input: &str, validator: &mut Validator) -> (Vec<Node>, Vec<Warning>) {
+    let mut parser = Parser::new(input, validator.config());
     let mut warnings = Vec::new();
     while let Some(mut node) = parser.next_node() {
-        warnings.extend(validator.validate(&mut node, &mut parser.state));
+        warnings.extend(validator.validate(&mut node, &parser.state));
         parser.add_node(node);
     }
     warnings.extend(validator.finalize(&parser.state));
@@ -0,0 +1,128 @@
+use crate::foreign_names::{MATHML_ATTRIBUTES, MATHML_ELEMENTS, SVG_ATTRIBUTES, SVG_ELEMENTS};
+use std::fmt;
+
+/// SVG elements whose content is HTML
+const SVG_HTML_INTEGRATION_POINTS: [&str; 3] = ["desc", "foreignObject", "title"];
+
+/// MathML elements whose content is HTML
+const MATHML_TEXT_INTEGRATION_POINTS: [&str; 5] = ["mi", "mn", "mo", "ms", "mtext"];
+
+/// The namespace of an element. Elements are HTML, except within `<svg>` and `<math>`, where
+/// names are case-sensitive, like `foreignObject` and `viewBox`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Namespace {
+    #[default]
+    Html,
+    Svg,
+    MathMl,
+}
+
+impl Namespace {
+    pub fn is_foreign(self) -> bool {
+        self != Namespace::Html
+    }
+
+    /// The namespace of an element with the name, in content of this namespace
+    pub fn of_element(self, name: &str) -> Namespace {
+        match self {
+            Namespace::Html if name.eq_ignore_ascii_case("svg") => Namespace::Svg,
+            Namespace::Html if name.eq_ignore_ascii_case("math") => Namespace::MathMl,
+            namespace => namespace,
+        }
+    }
+
+    /// The namespace of the content of an element with the name, in content of this namespace.
+    /// The content of integration points, like `<foreignObject>`, is HTML.
+    pub fn of_content(self, name: &str) -> Namespace {
+        let is_integration_point = |names: &[&str]| {
+            names
+                .iter()
+                .any(|integration_point| integration_point.eq_ignore_ascii_case(name))
+        };
+
+        match self.of_element(name) {
+            Namespace::Svg if is_integration_point(&SVG_HTML_INTEGRATION_POINTS) => Namespace::Html,
+            Namespace::MathMl if is_integration_point(&MATHML_TEXT_INTEGRATION_POINTS) => {
+                Namespace::Html
+            }
+            namespace => namespace,
+        }
+    }
+
+    /// The case-sensitive name of an element in the namespace, like `foreignObject` for
+    /// `foreignobject`. `None` for HTML and unknown elements.
+    pub fn element_name(self, name: &str) -> Option<&'static str> {
+        match self {
+            Namespace::Html => None,
+            Namespace::Svg => find_name(&SVG_ELEMENTS, name),
+            Namespace::MathMl => find_name(&MATHML_ELEMENTS, name),
+        }
+    }
+
+    /// The case-sensitive name of an attribute in the namespace, like `viewBox` for `viewbox`.
+    /// `None` for HTML and unknown attributes.
+    pub fn attribute_name(self, name: &str) -> Option<&'static str> {
+        match self {
+            Namespace::Html => None,
+            Namespace::Svg => find_name(&SVG_ATTRIBUTES, name),
+            Namespace::MathMl => find_name(&MATHML_ATTRIBUTES, name),
+        }
+    }
+}
+
+fn find_name(names: &[&'static str], name: &str) -> Option<&'static str> {
+    names
+        .iter()
+        .find(|known_name| known_name.eq_ignore_ascii_case(name))
+        .copied()
+}
+
+impl fmt::Display for Namespace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Namespace::Html => write!(f, "HTML"),
+            Namespace::Svg => write!(f, "SVG"),
+            Namespace::MathMl => write!(f, "MathML"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn content_namespaces() {
+        let namespace = |names: &[&str]| {
+            names.iter().fold(Namespace::Html, |namespace, name| {
+                namespace.of_content(name)
+            })
+        };
+
+        assert_eq!(namespace(&["div"]), Namespace::Html);
+        assert_eq!(namespace(&["div", "svg", "g"]), Namespace::Svg);
+        assert_eq!(namespace(&["svg", "foreignObject"]), Namespace::Html);
+        assert_eq!(
+            namespace(&["svg", "foreignObject", "math"]),
+            Namespace::MathMl
+        );
+        assert_eq!(namespace(&["math", "mi"]), Namespace::Html);
+        // `<math>` in SVG is an SVG element
+        assert_eq!(namespace(&["svg", "math"]), Namespace::Svg);
+    }
+
+    #[test]
+    fn case_sensitive_names() {
+        assert_eq!(
+            Namespace::Svg.element_name("FOREIGNOBJECT"),
+            Some("foreignObject")
+        );
+        assert_eq!(Namespace::Svg.attribute_name("viewbox"), Some("viewBox"));
+        assert_eq!(
+            Namespace::MathMl.attribute_name("definitionurl"),
+            Some("definitionURL")
+        );
+        assert_eq!(Namespace::Svg.element_name("my-element"), None);
+        assert_eq!(Namespace::Html.element_name("svg"), None);
+    }
+}
@@ -3,6 +3,7 @@ use crate::ast::Attribute;
 use crate::ast::AttributeValue;
 use crate::ast::CloseTag;
 use crate::ast::Comment;
+use crate::ast::CommentKind;
 use crate::ast::Construct;
 use crate::ast::Doctype;
 use crate::ast::Either;
@@ -13,25 +14,144 @@ use crate::ast::StringArea;
 use crate::ast::TemplateExpression;
 use crate::ast::Text;
 use crate::config::Config;
+use crate::namespace::Namespace;
 use crate::template_language::TemplateLanguage;
 use crate::warning::Warning;
+use regex::Regex;
+
+/// Elements whose content is text up to their close tag
+const RAW_TEXT_ELEMENTS: [&str; 6] = ["iframe", "noembed", "noframes", "script", "style", "xmp"];
+
+/// Elements whose content is text with character references up to their close tag
+const RCDATA_ELEMENTS: [&str; 2] = ["textarea", "title"];
+
+/// Elements without content or close tags, generated from
+/// `htmlsnob_scripts/data/void_elements.json`
+pub use crate::void_elements::VOID_ELEMENTS;
+
+/// Elements whose close tags may be omitted, when the next tag or the end of the parent implies
+/// them
+const OPTIONAL_END_TAG_ELEMENTS: [&str; 19] = [
+    "body", "caption", "colgroup", "dd", "dt", "head", "html", "li", "optgroup", "option", "p",
+    "rp", "rt", "tbody", "td", "tfoot", "th", "thead", "tr",
+];
+
+/// Elements whose open tags close an open `p`
+const P_CLOSING_ELEMENTS: [&str; 41] = [
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "center",
+    "details",
+    "dialog",
+    "dir",
+    "div",
+    "dl",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hgroup",
+    "hr",
+    "listing",
+    "main",
+    "menu",
+    "nav",
+    "ol",
+    "p",
+    "plaintext",
+    "pre",
+    "search",
+    "section",
+    "summary",
+    "table",
+    "ul",
+    "xmp",
+    "li",
+    "dd",
+    "dt",
+];
+
+/// Parents of `p` that don't imply its close tag, since `p` may be part of their transparent
+/// content
+const P_CLOSE_TAG_REQUIRING_PARENTS: [&str; 7] =
+    ["a", "audio", "del", "ins", "map", "noscript", "video"];
+
+/// Whether the element is a void element, like `br`, that never has content or a close tag
+pub fn is_void_element(name: &str) -> bool {
+    VOID_ELEMENTS.contains(&name.to_ascii_lowercase().as_str())
+}
+
+/// Whether the close tag of the element may be omitted, like the one of `li` or `p`
+pub fn has_optional_end_tag(name: &str) -> bool {
+    OPTIONAL_END_TAG_ELEMENTS.contains(&name.to_ascii_lowercase().as_str())
+}
+
+/// Whether the close tag of the element may be omitted at the end of its parent, which `p` may
+/// not within parents like `a` or autonomous custom elements, like `my-element`
+fn may_omit_close_tag_at_end_of(name: &str, parent: Option<&OpenTag>) -> bool {
+    !name.eq_ignore_ascii_case("p")
+        || parent.is_none_or(|parent| {
+            !P_CLOSE_TAG_REQUIRING_PARENTS.contains(&parent.name.to_ascii_lowercase().as_str())
+                && !parent.name.contains('-')
+        })
+}
 
-const RAW_TEXT_ELEMENTS: [&str; 3] = ["script", "style", "textrange"];
+/// The elements that the open tag of an element implicitly closes, per the implied end tags of
+/// the HTML specification
+fn implicitly_closed_elements(name: &str) -> &'static [&'static str] {
+    match name.to_ascii_lowercase().as_str() {
+        "li" => &["li", "p"],
+        "dt" | "dd" => &["dt", "dd", "p"],
+        "option" => &["option"],
+        "optgroup" => &["option", "optgroup"],
+        "rt" | "rp" => &["rt", "rp"],
+        "caption" | "colgroup" => &["caption", "colgroup"],
+        "thead" | "tbody" | "tfoot" => &["caption", "colgroup", "thead", "tbody", "tfoot"],
+        "tr" => &["caption", "colgroup", "tr"],
+        "td" | "th" => &["caption", "colgroup", "td", "th"],
+        "body" => &["head"],
+        name if P_CLOSING_ELEMENTS.contains(&name) => &["p"],
+        _ => &[],
+    }
+}
 
 pub struct Parser {
     template_language: TemplateLanguage,
+    // The constructs of the template language, compiled once per document
+    constructs: Vec<(Regex, Regex, Regex, Construct)>,
     input: Vec<char>,
+    // The input as a string, with the byte offset of each character, to match regexes from the
+    // cursor without copying the rest of the input
+    source: String,
+    byte_offsets: Vec<usize>,
     line_number: usize,
     line_start_cursor: usize,
     cursor: usize,
-    ignore_rules: bool,
     pub state: ParseState,
     pub warnings: Vec<Warning>,
+    // The end of the last character that isn't whitespace, where implied close tags are placed
+    content_end: Position,
+    implied_close_tag_position: Position,
+    // A tag that implied the close tag of the innermost open element, returned after it
+    pending_node: Option<Node>,
 }
 
 pub struct ParseState {
     pub ast: Vec<Node>,
     pub open_tag_indexes: Vec<usize>,
+    /// The open tags taken off the stack by the current close tag, outermost first: the element it
+    /// closes, followed by the elements inside of it that were left unclosed
+    pub popped_tag_indexes: Vec<usize>,
     pub raw_text_mode_end_tag_name: Option<String>, // End tag in raw mode, e.g. "script"
 }
 
@@ -40,10 +160,48 @@ impl ParseState {
         ParseState {
             ast: Vec::new(),
             open_tag_indexes: Vec::new(),
+            popped_tag_indexes: Vec::new(),
             raw_text_mode_end_tag_name: None,
         }
     }
 
+    fn open_tag(&self, index: usize) -> &OpenTag {
+        match &self.ast[index] {
+            Node::OpenTag(open_tag) => open_tag,
+            _ => panic!("Expected an OpenTag"),
+        }
+    }
+
+    fn open_tag_mut(&mut self, index: usize) -> &mut OpenTag {
+        match &mut self.ast[index] {
+            Node::OpenTag(open_tag) => open_tag,
+            _ => panic!("Expected an OpenTag"),
+        }
+    }
+
+    /// Whether an element with the name is open, ignoring case like HTML does
+    fn is_open(&self, name: &str) -> bool {
+        self.open_tag_indexes
+            .iter()
+            .any(|&index| self.open_tag(index).name.eq_ignore_ascii_case(name))
+    }
+
+    /// The namespace of the content at the current position, SVG or MathML within `<svg>` or
+    /// `<math>`, unless an integration point like `<foreignObject>` switches back to HTML
+    pub fn namespace(&self) -> Namespace {
+        self.open_tag_indexes
+            .iter()
+            .fold(Namespace::Html, |namespace, &index| {
+                namespace.of_content(&self.open_tag(index).name)
+            })
+    }
+
+    /// The namespace of an element with the name that is opened or closed at the current
+    /// position, so `<svg>` itself is an SVG element
+    pub fn element_namespace(&self, name: &str) -> Namespace {
+        self.namespace().of_element(name)
+    }
+
     pub fn open_tag_names(&self) -> Vec<String> {
         self.open_tag_indexes
             .iter()
@@ -53,6 +211,54 @@ impl ParseState {
             })
             .collect()
     }
+
+    /// The open tags at the current position, innermost first
+    pub fn open_tags(&self) -> impl Iterator<Item = &OpenTag> {
+        self.open_tag_indexes
+            .iter()
+            .rev()
+            .map(|&index| self.open_tag(index))
+    }
+
+    /// The elements left unclosed by the current close tag, e.g. `div` in `<ul><div></ul>`
+    pub fn unclosed_open_tags(&self) -> impl Iterator<Item = &OpenTag> {
+        self.popped_tag_indexes
+            .iter()
+            .skip(1)
+            .map(|&index| self.open_tag(index))
+    }
+
+    /// The open tags around the tag, innermost first. Rules call this while the tag is opened or
+    /// closed, when it isn't on the stack, or at the end of the document, when it is still open.
+    /// Template expressions aren't on the stack, so a tag inside `{% if %}` has the same
+    /// ancestors as outside of it.
+    pub fn ancestors(&self, open_tag: &OpenTag) -> impl Iterator<Item = &OpenTag> {
+        // The elements popped by a close tag are still around the elements left unclosed in them
+        let (open_tag_indexes, popped_tag_indexes) = match self
+            .popped_tag_indexes
+            .iter()
+            .position(|index| *index == open_tag.index)
+        {
+            Some(end) => (&self.open_tag_indexes[..], &self.popped_tag_indexes[..end]),
+            None => {
+                let end = self
+                    .open_tag_indexes
+                    .iter()
+                    .rposition(|index| *index == open_tag.index)
+                    .unwrap_or(self.open_tag_indexes.len());
+                (&self.open_tag_indexes[..end], &[][..])
+            }
+        };
+
+        popped_tag_indexes
+            .iter()
+            .rev()
+            .chain(open_tag_indexes.iter().rev())
+            .map(|&index| match &self.ast[index] {
+                Node::OpenTag(open_tag) => open_tag,
+                _ => panic!("Expected an OpenTag"),
+            })
+    }
 }
 
 impl Parser {
@@ -60,51 +266,175 @@ impl Parser {
         Parser {
             state: ParseState::new(),
             template_language: config.options.template_language.clone(),
+            constructs: TemplateLanguage::constructs(&config.options.template_language),
             input: input.chars().collect(),
+            source: input.to_string(),
+            byte_offsets: input
+                .char_indices()
+                .map(|(offset, _)| offset)
+                .chain([input.len()])
+                .collect(),
             line_number: 0,
             line_start_cursor: 0,
             cursor: 0,
-            ignore_rules: false,
             warnings: Vec::new(),
+            content_end: Position::default(),
+            implied_close_tag_position: Position::default(),
+            pending_node: None,
         }
     }
 
     pub fn next_node(&mut self) -> Option<Node> {
+        self.state.popped_tag_indexes.clear();
+        if let Some(node) = self.pending_node.take() {
+            return Some(self.imply_close_tag_or(node));
+        }
+
+        self.implied_close_tag_position = self.content_end.clone();
         self.skip_whitespace();
         if self.cursor >= self.input.len() {
-            return None;
+            // Elements with optional close tags are closed by the end of the document
+            return self
+                .innermost_open_tag()
+                .is_some_and(|open_tag| has_optional_end_tag(&open_tag.name))
+                .then(|| Node::CloseTag(self.implied_close_tag(true)));
+        }
+
+        // Raw text ends at the close tag, and may contain template expressions
+        if let Some(end_tag_name) = self.state.raw_text_mode_end_tag_name.clone() {
+            if self.peek_end_tag(&end_tag_name) {
+                self.state.raw_text_mode_end_tag_name = None;
+            } else if self.is_template_expression() {
+                return Some(Node::TemplateExpression(self.parse_template_expression()));
+            } else {
+                return Some(Node::Text(self.parse_raw_text(&end_tag_name)));
+            }
         }
 
-        Some(if self.state.raw_text_mode_end_tag_name.is_some() {
-            let raw_text = self.parse_raw_text();
-            self.state.raw_text_mode_end_tag_name = None;
-            Node::Text(raw_text)
-        } else if self.peek_str("</") {
-            Node::CloseTag(self.parse_close_tag())
+        Some(if self.peek_str("</") {
+            let close_tag = self.parse_close_tag();
+            self.imply_close_tag_or(Node::CloseTag(close_tag))
         } else if self.peek_str("<!--") {
-            Node::Comment(self.parse_comment())
+            Node::Comment(self.parse_comment(CommentKind::Comment))
+        } else if self.peek_str("<![CDATA[") && self.state.namespace().is_foreign() {
+            Node::Comment(self.parse_comment(CommentKind::Cdata))
         } else if self.peek_str("<!DOCTYPE") || self.peek_str("<!doctype") {
             Node::Doctype(self.parse_doctype())
+        } else if self.peek_str("<!") {
+            Node::Comment(self.parse_comment(CommentKind::Bogus))
+        } else if self.peek_str("<?") {
+            Node::Comment(self.parse_comment(CommentKind::ProcessingInstruction))
         } else if self.is_template_expression() {
             Node::TemplateExpression(self.parse_template_expression())
         } else if self.peek_char('<') {
             let open_tag = self.parse_open_tag();
-
-            // Enter raw text mode if applicable
-            if RAW_TEXT_ELEMENTS.contains(&open_tag.name.as_str()) && !open_tag.self_closed {
-                self.state.raw_text_mode_end_tag_name = Some(open_tag.name.clone());
-            }
-
-            Node::OpenTag(open_tag)
+            self.imply_close_tag_or(Node::OpenTag(open_tag))
         } else {
             Node::Text(self.parse_text())
         })
     }
 
+    /// Returns an implied close tag of the innermost open element if the tag implies it, and
+    /// keeps the tag to return it next, as it may imply more close tags. Otherwise returns the
+    /// tag, matched with the open elements.
+    fn imply_close_tag_or(&mut self, node: Node) -> Node {
+        let implies_close_tag = match &node {
+            Node::OpenTag(open_tag) => self
+                .implicitly_closed_position(&self.state.open_tag_indexes, &open_tag.name)
+                .is_some(),
+            // A close tag implies the close tags of the elements with optional close tags in it
+            Node::CloseTag(close_tag) => self.innermost_open_tag().is_some_and(|open_tag| {
+                !open_tag.name.eq_ignore_ascii_case(&close_tag.name)
+                    && has_optional_end_tag(&open_tag.name)
+                    && self.state.is_open(&close_tag.name)
+            }),
+            _ => false,
+        };
+
+        if implies_close_tag {
+            let ends_parent = matches!(node, Node::CloseTag(_));
+            self.pending_node = Some(node);
+            return Node::CloseTag(self.implied_close_tag(ends_parent));
+        }
+
+        match node {
+            Node::OpenTag(mut open_tag) => {
+                open_tag.index = self.state.ast.len();
+
+                // Enter raw text mode if applicable, tags like `<title>` in SVG contain markup
+                let name = open_tag.name.to_ascii_lowercase();
+                if (RAW_TEXT_ELEMENTS.contains(&name.as_str())
+                    || RCDATA_ELEMENTS.contains(&name.as_str()))
+                    && !open_tag.self_closed
+                    && !self.state.element_namespace(&open_tag.name).is_foreign()
+                {
+                    self.state.raw_text_mode_end_tag_name = Some(open_tag.name.clone());
+                }
+
+                Node::OpenTag(open_tag)
+            }
+            Node::CloseTag(close_tag) => Node::CloseTag(self.match_close_tag(close_tag)),
+            node => node,
+        }
+    }
+
+    /// The position in the stack of the open element that the open tag implicitly closes. Only
+    /// elements with optional close tags are closed, so the search stops at other elements.
+    fn implicitly_closed_position(&self, open_tag_indexes: &[usize], name: &str) -> Option<usize> {
+        let closed_elements = implicitly_closed_elements(name);
+
+        for (position, &index) in open_tag_indexes.iter().enumerate().rev() {
+            let open_tag_name = self.state.open_tag(index).name.to_ascii_lowercase();
+            if closed_elements.contains(&open_tag_name.as_str()) {
+                return Some(position);
+            }
+            if !has_optional_end_tag(&open_tag_name) {
+                return None;
+            }
+        }
+        None
+    }
+
+    fn innermost_open_tag(&self) -> Option<&OpenTag> {
+        let index = *self.state.open_tag_indexes.last()?;
+        Some(self.state.open_tag(index))
+    }
+
+    /// Closes the innermost open element with a close tag that is omitted in the source, placed
+    /// after the last content before the tag that implies it. When the end of the parent implies
+    /// it, the close tag is only optional if the parent allows omitting it.
+    fn implied_close_tag(&mut self, ends_parent: bool) -> CloseTag {
+        let open_tag_index = self
+            .state
+            .open_tag_indexes
+            .pop()
+            .expect("Expected an open element");
+        let is_optional = !ends_parent
+            || may_omit_close_tag_at_end_of(
+                &self.state.open_tag(open_tag_index).name,
+                self.innermost_open_tag(),
+            );
+        let index = self.state.ast.len();
+        let open_tag = self.state.open_tag_mut(open_tag_index);
+        open_tag.close_tag_index = Some(index);
+
+        CloseTag {
+            name: open_tag.name.clone(),
+            area: Area {
+                start: self.implied_close_tag_position.clone(),
+                end: self.implied_close_tag_position.clone(),
+            },
+            is_missing_end_bracket: false,
+            open_tag_index: Some(open_tag_index),
+            is_implied: true,
+            is_optional,
+        }
+    }
+
     pub fn add_node(&mut self, node: Node) {
         // Track open tags
         if let Node::OpenTag(open_tag) = &node {
-            if !open_tag.self_closed {
+            if !open_tag.is_void_or_self_closed() {
                 self.state.open_tag_indexes.push(open_tag.index);
             }
         }
@@ -112,22 +442,16 @@ impl Parser {
         self.state.ast.push(node);
     }
 
-    fn parse_comment(&mut self) -> Comment {
+    fn parse_comment(&mut self, kind: CommentKind) -> Comment {
+        let (start_delimiter, end_delimiter) = kind.delimiters();
         let start_position = self.position();
-        self.consume_str("<!--");
+        self.consume_str(start_delimiter);
 
         let start = self.cursor;
 
         // Find the end of the comment
         while self.cursor < self.input.len() {
-            if self.peek_str("ignore above") {
-                self.ignore_rules = false;
-            }
-            if self.peek_str("ignore below") {
-                self.ignore_rules = true;
-            }
-
-            if self.peek_str("-->") {
+            if self.peek_str(end_delimiter) {
                 break;
             }
             self.advance();
@@ -136,8 +460,8 @@ impl Parser {
         let content: String = self.input[start..self.cursor].iter().collect();
 
         let mut is_missing_end_bracket = false;
-        if self.peek_str("-->") {
-            self.consume_str("-->");
+        if self.peek_str(end_delimiter) {
+            self.consume_str(end_delimiter);
         } else {
             is_missing_end_bracket = true;
         }
@@ -149,6 +473,7 @@ impl Parser {
                 end: self.position(),
             },
             is_missing_end_bracket,
+            kind,
         }
     }
 
@@ -167,6 +492,7 @@ impl Parser {
 
         let (name, is_missing_end_bracket) = self.parse_tag_name();
         let attributes = self.parse_attributes();
+        let is_missing_end_bracket = is_missing_end_bracket || self.cursor == self.input.len();
 
         // Self-closing tag
         if self.peek_str("/>") {
@@ -210,77 +536,98 @@ impl Parser {
         self.consume('<');
         self.consume('/');
         self.skip_whitespace();
-        let (name, is_missing_end_bracket) = self.parse_tag_name();
+        let (name, mut is_missing_end_bracket) = self.parse_tag_name();
         if !is_missing_end_bracket {
-            self.consume('>');
+            // A close tag being typed, like `</di` followed by a newline, has no end bracket
+            let next_char = self.input[self.cursor..]
+                .iter()
+                .find(|c| !self.is_whitespace(**c));
+            if next_char == Some(&'>') {
+                self.skip_whitespace();
+                self.consume('>');
+            } else {
+                is_missing_end_bracket = true;
+            }
         }
         let end_position = self.position();
 
-        // Check if a matching opening tag exists
-        let matched = self.state.open_tag_indexes.iter().any(|&open_tag_index| {
-            let open_tag = match &self.state.ast[open_tag_index] {
-                Node::OpenTag(open_tag) => open_tag,
-                _ => panic!("Expected an OpenElement"),
-            };
-            open_tag.name == name
-        });
+        CloseTag {
+            name,
+            area: Area {
+                start: start_position,
+                end: end_position,
+            },
+            is_missing_end_bracket,
+            open_tag_index: None, // Set when the close tag is matched with an open tag
+            is_implied: false,
+            is_optional: false,
+        }
+    }
 
+    fn match_close_tag(&mut self, mut close_tag: CloseTag) -> CloseTag {
         // Orphan closing tag
-        if !matched {
-            return CloseTag {
-                name,
-                area: Area {
-                    start: start_position,
-                    end: end_position,
-                },
-                is_missing_end_bracket,
-                open_tag_index: None,
-            };
+        if !self.state.is_open(&close_tag.name) {
+            return close_tag;
         }
 
         // Since a match exists:
         // Traverse the stack to find the matching opening tag, marking passed elements as unclosed
         let index = self.state.ast.len();
         while let Some(open_tag_index) = self.state.open_tag_indexes.pop() {
-            let open_tag = match &self.state.ast[open_tag_index] {
-                Node::OpenTag(element) => element,
-                _ => panic!("Expected an OpenTag"),
-            };
-
-            if open_tag.name == name {
-                // Update OpenTag
-                self.state.ast[open_tag_index] = Node::OpenTag(OpenTag {
-                    name: open_tag.name.clone(),
-                    attributes: open_tag.attributes.clone(),
-                    area: open_tag.area.clone(),
-                    self_closed: open_tag.self_closed,
-                    is_missing_end_bracket: open_tag.is_missing_end_bracket,
-                    close_tag_index: Some(index),
-                    index: open_tag.index,
-                });
-
-                // Return CloseTag
-                return CloseTag {
-                    name,
-                    area: Area {
-                        start: start_position,
-                        end: end_position,
-                    },
-                    is_missing_end_bracket,
-                    open_tag_index: Some(open_tag_index),
-                };
+            self.state.popped_tag_indexes.insert(0, open_tag_index);
+            let open_tag = self.state.open_tag_mut(open_tag_index);
+            if open_tag.name.eq_ignore_ascii_case(&close_tag.name) {
+                open_tag.close_tag_index = Some(index);
+                close_tag.open_tag_index = Some(open_tag_index);
+                close_tag.is_optional = self.is_close_tag_optional(open_tag_index);
+                break;
             }
         }
 
-        // No matching opening tag found
-        CloseTag {
-            name,
-            area: Area {
-                start: start_position,
-                end: end_position,
-            },
-            is_missing_end_bracket,
-            open_tag_index: None,
+        close_tag
+    }
+
+    /// Whether the close tag of the element, which was just closed, could be omitted, since the
+    /// tag after it or the end of the parent would imply it
+    fn is_close_tag_optional(&self, open_tag_index: usize) -> bool {
+        let name = self
+            .state
+            .open_tag(open_tag_index)
+            .name
+            .to_ascii_lowercase();
+        if !has_optional_end_tag(&name) {
+            return false;
+        }
+
+        let rest: String = self.input[self.cursor..]
+            .iter()
+            .skip_while(|c| self.is_whitespace(**c))
+            .take_while(|c| **c != '>')
+            .collect();
+        let parent = self.innermost_open_tag();
+        let ends_parent = || may_omit_close_tag_at_end_of(&name, parent);
+        let tag_name = |tag: &str| {
+            tag.split(|c: char| self.is_whitespace(c) || c == '/')
+                .next()
+                .unwrap_or_default()
+                .to_string()
+        };
+
+        if rest.is_empty() {
+            ends_parent()
+        } else if let Some(close_tag) = rest.strip_prefix("</") {
+            parent.is_some_and(|parent| parent.name.eq_ignore_ascii_case(&tag_name(close_tag)))
+                && ends_parent()
+        } else if let Some(open_tag) = rest.strip_prefix('<') {
+            let name = tag_name(open_tag);
+            let mut open_tag_indexes = self.state.open_tag_indexes.clone();
+            open_tag_indexes.push(open_tag_index);
+            name.starts_with(|c: char| c.is_ascii_alphabetic())
+                && self
+                    .implicitly_closed_position(&open_tag_indexes, &name)
+                    .is_some()
+        } else {
+            false
         }
     }
 
@@ -288,15 +635,15 @@ impl Parser {
         self.cursor - self.line_start_cursor
     }
 
-    fn parse_raw_text(&mut self) -> Text {
+    fn parse_raw_text(&mut self, end_tag_name: &str) -> Text {
         let start_position = self.position();
         let start = self.cursor;
-        let end_tag = format!(
-            "</{}",
-            self.state.raw_text_mode_end_tag_name.as_ref().unwrap()
-        );
 
-        while self.cursor < self.input.len() && !self.peek_str(&end_tag) {
+        // Template expressions start with `{` or `<`, so the constructs are only matched there
+        while self.cursor < self.input.len()
+            && !self.peek_end_tag(end_tag_name)
+            && !(matches!(self.current_char(), '{' | '<') && self.is_template_expression())
+        {
             self.advance();
         }
         let content = self.input[start..self.cursor]
@@ -474,8 +821,20 @@ impl Parser {
                 break;
             }
 
+            // The value is unterminated at the end of the input, e.g. while typing
             if self.cursor >= self.input.len() {
-                panic!("Unexpected end of input");
+                if class_start < self.cursor {
+                    classes.push(Either::Left(StringArea {
+                        content: self.input[class_start..self.cursor]
+                            .iter()
+                            .collect::<String>(),
+                        area: Area {
+                            start: class_start_position,
+                            end: self.position(),
+                        },
+                    }));
+                }
+                break;
             }
 
             self.advance();
@@ -562,8 +921,8 @@ impl Parser {
             return false;
         }
 
-        TemplateLanguage::constructs(&self.template_language)
-            .into_iter()
+        self.constructs
+            .iter()
             .any(|construct| self.peek_regex(&construct.0))
     }
 
@@ -571,9 +930,11 @@ impl Parser {
         let start_position = self.position();
         let content_start = self.cursor;
 
-        let construct = TemplateLanguage::constructs(&self.template_language)
-            .into_iter()
+        let construct = self
+            .constructs
+            .iter()
             .find(|construct| self.peek_regex(&construct.0))
+            .cloned()
             .unwrap_or_else(|| panic!("No matching construct found for input"));
 
         let mut quote = None;
@@ -657,20 +1018,37 @@ impl Parser {
         true
     }
 
-    fn peek_regex(&self, regex: &regex::Regex) -> bool {
-        let remaining_input: String = self.input[self.cursor..].iter().collect();
-        regex.is_match(&remaining_input)
+    /// Whether the close tag of the element is next, ignoring case, like `</SCRIPT>`
+    fn peek_end_tag(&self, name: &str) -> bool {
+        let end = self.cursor + 2 + name.chars().count();
+        if end > self.input.len() || !self.peek_str("</") {
+            return false;
+        }
+
+        let tag_name: String = self.input[self.cursor + 2..end].iter().collect();
+        tag_name.eq_ignore_ascii_case(name)
+            && (end == self.input.len()
+                || self.is_whitespace(self.input[end])
+                || matches!(self.input[end], '/' | '>'))
+    }
+
+    /// The input from the cursor on, which the anchored regexes of the constructs match
+    fn remaining_source(&self) -> &str {
+        &self.source[self.byte_offsets[self.cursor.min(self.input.len())]..]
     }
 
-    fn consume_regex(&mut self, regex: &regex::Regex) {
-        let remaining_input: String = self.input[self.cursor..].iter().collect();
-        if let Some(regex_match) = regex.find(&remaining_input) {
+    fn peek_regex(&self, regex: &Regex) -> bool {
+        regex.is_match(self.remaining_source())
+    }
+
+    fn consume_regex(&mut self, regex: &Regex) {
+        if let Some(regex_match) = regex.find(self.remaining_source()) {
             assert_eq!(
                 regex_match.start(),
                 0,
                 "Regex did not match at the current position"
             );
-            for _ in 0..regex_match.end() {
+            for _ in 0..regex_match.as_str().chars().count() {
                 self.advance();
             }
         } else {
@@ -688,11 +1066,15 @@ impl Parser {
     }
 
     fn advance(&mut self) {
+        let is_content = !self.is_whitespace(self.current_char());
         if self.current_char() == '\n' {
             self.line_number += 1;
             self.line_start_cursor = self.cursor + 1;
         }
         self.cursor += 1;
+        if is_content {
+            self.content_end = self.position();
+        }
     }
 
     fn consume(&mut self, expected: char) {
@@ -725,8 +1107,10 @@ impl Parser {
 
 #[cfg(test)]
 mod tests {
-    use crate::ast::Node;
+    use crate::ast::{CommentKind, Node};
     use crate::config::Config;
+    use crate::formatter::print_node;
+    use crate::namespace::Namespace;
     use crate::parser::Parser;
     use crate::registry::Registry;
 
@@ -765,4 +1149,286 @@ mod tests {
 
         //panic!("TEST END");
     }
+
+    fn parse(input: &str) -> Vec<Node> {
+        parse_with_config(input, "")
+    }
+
+    fn parse_with_config(input: &str, config: &str) -> Vec<Node> {
+        let registry = Registry::new();
+        let config = Config::from_toml(config, &registry);
+        let mut parser = Parser::new(input, &config);
+        while let Some(node) = parser.next_node() {
+            parser.add_node(node);
+        }
+        parser.state.ast
+    }
+
+    /// The tags and text of the input, with implied close tags in parentheses
+    fn tags(input: &str) -> String {
+        print_tags(&parse(input))
+    }
+
+    fn print_tags(ast: &[Node]) -> String {
+        ast.iter()
+            .map(|node| match node {
+                Node::OpenTag(open_tag) => format!("<{}>", open_tag.name),
+                Node::CloseTag(close_tag) if close_tag.is_implied => {
+                    format!("(/{})", close_tag.name)
+                }
+                Node::CloseTag(close_tag) => format!("</{}>", close_tag.name),
+                Node::Text(text) => text.content.clone(),
+                node => print_node(node),
+            })
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    #[test]
+    fn implied_close_tags() {
+        assert_eq!(
+            tags("<ul><li>One<li>Two</ul>"),
+            "<ul> <li> One (/li) <li> Two (/li) </ul>"
+        );
+        assert_eq!(
+            tags("<li><p>One<li>Two"),
+            "<li> <p> One (/p) (/li) <li> Two (/li)"
+        );
+        assert_eq!(
+            tags("<div><p>One<div></div></div>"),
+            "<div> <p> One (/p) <div> </div> </div>"
+        );
+        assert_eq!(
+            tags("<dl><dt>One<dd>Two<DT>Three</dl>"),
+            "<dl> <dt> One (/dt) <dd> Two (/dd) <DT> Three (/DT) </dl>"
+        );
+        assert_eq!(
+            tags("<table><tr><td>One<th>Two<tr><td>Three</table>"),
+            "<table> <tr> <td> One (/td) <th> Two (/th) (/tr) <tr> <td> Three (/td) (/tr) </table>"
+        );
+        assert_eq!(
+            tags("<select><optgroup><option>One<optgroup><option>Two</select>"),
+            "<select> <optgroup> <option> One (/option) (/optgroup) <optgroup> <option> Two (/option) (/optgroup) </select>"
+        );
+        assert_eq!(
+            tags("<html><head><title>Title</title><body><p>Text"),
+            "<html> <head> <title> Title </title> (/head) <body> <p> Text (/p) (/body) (/html)"
+        );
+    }
+
+    #[test]
+    fn implied_close_tags_stop_at_elements_with_required_close_tags() {
+        assert_eq!(
+            tags("<li><span><li>One</span></li>"),
+            "<li> <span> <li> One (/li) </span> </li>"
+        );
+        assert_eq!(tags("<div><p>One"), "<div> <p> One (/p)");
+    }
+
+    #[test]
+    fn implied_close_tags_are_placed_after_the_content() {
+        let ast = parse("<li>One\n  <li>Two");
+        let Node::CloseTag(close_tag) = &ast[2] else {
+            panic!("Expected an implied close tag");
+        };
+        assert!(close_tag.is_implied);
+        assert_eq!(close_tag.area.start, close_tag.area.end);
+        assert_eq!((close_tag.area.end.line, close_tag.area.end.column), (0, 7));
+    }
+
+    #[test]
+    fn optional_close_tags() {
+        let optional_close_tags = |input: &str| -> Vec<String> {
+            parse(input)
+                .iter()
+                .filter_map(|node| match node {
+                    Node::CloseTag(close_tag) if close_tag.is_optional => {
+                        Some(close_tag.name.clone())
+                    }
+                    _ => None,
+                })
+                .collect()
+        };
+
+        assert_eq!(
+            optional_close_tags("<ul><li>One</li> <li>Two</li>\n</ul>"),
+            ["li", "li"]
+        );
+        assert_eq!(
+            optional_close_tags("<div><p>One</p>Two<p>Three</p><!-- Four --></div>"),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            optional_close_tags("<div><p>One</p><div></div><a><p>Two</p></a></div><p>Three</p>"),
+            ["p", "p"]
+        );
+        assert_eq!(
+            optional_close_tags("<table><tr><td>One</td></tr><tr><td>Two</td></tr></table>"),
+            ["td", "tr", "td", "tr"]
+        );
+        // Implied by the end of `a`, which requires `</p>`
+        assert_eq!(optional_close_tags("<div><p>One</div><a><p>Two</a>"), ["p"]);
+        // Custom elements require `</p>` too
+        assert_eq!(
+            optional_close_tags("<div><p>One</div><my-element><p>Two</my-element>"),
+            ["p"]
+        );
+    }
+
+    #[test]
+    fn void_elements_are_never_open() {
+        assert_eq!(
+            tags("<div>One<br>Two<IMG src=\"a.png\"></div>"),
+            "<div> One <br> Two <IMG> </div>"
+        );
+        assert_eq!(tags("<p>One<hr>Two"), "<p> One (/p) <hr> Two");
+
+        let ast = parse("<div><input></input></div>");
+        let Node::CloseTag(close_tag) = &ast[2] else {
+            panic!("Expected a close tag");
+        };
+        assert_eq!(close_tag.open_tag_index, None);
+        let Node::OpenTag(open_tag) = &ast[0] else {
+            panic!("Expected an open tag");
+        };
+        assert_eq!(open_tag.close_tag_index, Some(3));
+    }
+
+    #[test]
+    fn raw_text_and_rcdata() {
+        assert_eq!(
+            tags("<textarea><b></textarea><TITLE>A <i>title</Title>"),
+            "<textarea> <b> </textarea> <TITLE> A <i>title </Title>"
+        );
+        assert_eq!(
+            tags("<script>a = '</scripts>';</SCRIPT ><style></style>"),
+            "<script> a = '</scripts>'; </SCRIPT> <style> </style>"
+        );
+    }
+
+    #[test]
+    fn template_expressions_in_raw_text() {
+        let ast = parse_with_config(
+            "<script>let a = {{ value }};</script><title>{{ title }}</title>",
+            "template_language = \"handlebars\"",
+        );
+        assert_eq!(
+            print_tags(&ast),
+            "<script> let a = {{ value }} ; </script> <title> {{ title }} </title>"
+        );
+    }
+
+    #[test]
+    fn long_raw_text() {
+        // Each `{` and `<` in a script is checked for a template expression, which took minutes
+        // for a script this long when every check copied the rest of the input
+        let line = "if (a < b) { c({{ d }}, { e: [1, 2, 3] }); } // A comment of some length\n";
+        let input = format!("<script>{}</script>", line.repeat(4_000));
+        let start = std::time::Instant::now();
+        let ast = parse_with_config(&input, "template_language = \"jinja2\"");
+        assert_eq!(ast.len(), 2 + 4_000 * 2 + 1);
+        assert!(start.elapsed() < std::time::Duration::from_secs(10));
+    }
+
+    #[test]
+    fn foreign_content() {
+        assert_eq!(
+            tags("<svg><title><b>A</b></title><![CDATA[ a < b ]]></svg><![CDATA[ c ]]>"),
+            "<svg> <title> <b> A </b> </title> <![CDATA[ a < b ]]> </svg> <![CDATA[ c ]]>"
+        );
+
+        let kinds: Vec<CommentKind> = parse("<svg><![CDATA[ a ]]></svg><![CDATA[ b ]]>")
+            .into_iter()
+            .filter_map(|node| match node {
+                Node::Comment(comment) => Some(comment.kind),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(kinds, [CommentKind::Cdata, CommentKind::Bogus]);
+
+        // The content of `<foreignObject>` is HTML again
+        assert_eq!(
+            tags("<svg><foreignObject><title><b></title></foreignObject></svg>"),
+            "<svg> <foreignObject> <title> <b> </title> </foreignObject> </svg>"
+        );
+    }
+
+    #[test]
+    fn namespaces() {
+        let config = Config::from_toml("", &Registry::new());
+        let mut parser = Parser::new(
+            "<div><svg><g><foreignObject><p></p></foreignObject></g></svg><math><mi>",
+            &config,
+        );
+        let mut namespaces = Vec::new();
+        while let Some(node) = parser.next_node() {
+            if let Node::OpenTag(open_tag) = &node {
+                namespaces.push((
+                    open_tag.name.clone(),
+                    parser.state.element_namespace(&open_tag.name),
+                    parser.state.namespace(),
+                ));
+            }
+            parser.add_node(node);
+        }
+        assert_eq!(
+            namespaces,
+            [
+                ("div".to_string(), Namespace::Html, Namespace::Html),
+                ("svg".to_string(), Namespace::Svg, Namespace::Html),
+                ("g".to_string(), Namespace::Svg, Namespace::Svg),
+                ("foreignObject".to_string(), Namespace::Svg, Namespace::Svg),
+                ("p".to_string(), Namespace::Html, Namespace::Html),
+                ("math".to_string(), Namespace::MathMl, Namespace::Html),
+                ("mi".to_string(), Namespace::MathMl, Namespace::MathMl),
+            ]
+        );
+        assert_eq!(parser.state.namespace(), Namespace::Html);
+    }
+
+    #[test]
+    fn processing_instructions_and_bogus_comments() {
+        let ast = parse("<?xml version=\"1.0\"?><!ELEMENT br EMPTY><!-- Comment -->");
+        let comments: Vec<(CommentKind, String)> = ast
+            .into_iter()
+            .filter_map(|node| match node {
+                Node::Comment(comment) => Some((comment.kind, comment.content)),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(
+            comments,
+            [
+                (
+                    CommentKind::ProcessingInstruction,
+                    "xml version=\"1.0\"?".to_string()
+                ),
+                (CommentKind::Bogus, "ELEMENT br EMPTY".to_string()),
+                (CommentKind::Comment, " Comment ".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn unterminated_attribute_value_at_end_of_input() {
+        let registry = Registry::new();
+        let config = Config::from_toml("", &registry);
+
+        for input in ["<input type=\"te", "<input type='", "<input type=te"] {
+            let mut parser = Parser::new(input, &config);
+            while let Some(node) = parser.next_node() {
+                parser.add_node(node);
+            }
+
+            let Some(Node::OpenTag(open_tag)) = parser.state.ast.first() else {
+                panic!("Expected an open tag for {input}");
+            };
+            assert!(open_tag.is_missing_end_bracket);
+            assert_eq!(
+                open_tag.attributes[0].value.as_ref().unwrap().end_quote,
+                None
+            );
+        }
+    }
 }
@@ -9,6 +9,12 @@ pub struct Registry {
     factories: HashMap<String, Factory>,
 }
 
+impl Default for Registry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 // A registery holds factories for creating rules
 impl Registry {
     pub fn new() -> Self {
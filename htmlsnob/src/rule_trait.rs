@@ -21,6 +21,18 @@ pub trait RuleTrait: Debug + Send + Sync {
     ) -> Vec<Warning> {
         vec![]
     }
+    /// Checks each element once, with its ancestors in the parse state. Self-closed tags and void
+    /// elements are checked when they are opened, since they are never closed, other elements
+    /// when they are closed, without a close tag when the close tag of an outer element leaves
+    /// them unclosed, or at the end of the document.
+    fn apply_element(
+        &self,
+        _open_tag: &OpenTag,
+        _close_tag: Option<&CloseTag>,
+        _parse_state: &ParseState,
+    ) -> Option<Warning> {
+        None
+    }
     fn apply_open_tag(
         &self,
         _open_tag: &mut OpenTag,
@@ -10,14 +10,56 @@ where
     Regex::new(&s).map_err(serde::de::Error::custom)
 }
 
-#[derive(Debug, Clone, Deserialize)]
-#[serde(transparent)]
-pub struct DeserializableRegex(#[serde(deserialize_with = "deserialize_regex")] Regex);
+#[derive(Debug, Clone)]
+pub struct DeserializableRegex {
+    regex: Regex,
+    /// The regex anchored at both ends, for matching whole names
+    whole: Regex,
+}
+
+impl DeserializableRegex {
+    /// Whether the regex matches all of the text, so `btn` matches `btn` but not `btn-primary`
+    pub fn is_whole_match(&self, text: &str) -> bool {
+        self.whole.is_match(text)
+    }
+}
+
+impl<'de> Deserialize<'de> for DeserializableRegex {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let regex = deserialize_regex(deserializer)?;
+        let whole =
+            Regex::new(&format!("^(?:{})$", regex.as_str())).map_err(serde::de::Error::custom)?;
+        Ok(Self { regex, whole })
+    }
+}
 
 impl Deref for DeserializableRegex {
     type Target = Regex;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.regex
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Config {
+        regex: DeserializableRegex,
+    }
+
+    #[test]
+    fn whole_matches() {
+        let Config { regex } = toml::from_str(r#"regex = "btn|btn-.*""#).unwrap();
+
+        assert!(regex.is_whole_match("btn"));
+        assert!(regex.is_whole_match("btn-primary"));
+        assert!(!regex.is_whole_match("my-btn"));
+        assert!(regex.is_match("my-btn"));
     }
 }
@@ -1,7 +1,35 @@
 use regex::Regex;
 use serde::Deserialize;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::OnceLock;
 
 use crate::ast::Construct;
+use crate::config::Options;
+
+// Markers that give away the template language of a file with a generic extension (e.g. `.html`).
+// More specific markers must come first, as the first marker found wins.
+const CONTENT_MARKERS: [(&str, TemplateLanguage); 16] = [
+    ("{%- liquid", TemplateLanguage::Liquid),
+    ("{% assign", TemplateLanguage::Liquid),
+    ("{%- assign", TemplateLanguage::Liquid),
+    ("{% capture", TemplateLanguage::Liquid),
+    ("{% extends", TemplateLanguage::Jinja2),
+    ("{%- extends", TemplateLanguage::Jinja2),
+    ("{% block", TemplateLanguage::Jinja2),
+    ("{% endblock", TemplateLanguage::Jinja2),
+    ("<%=", TemplateLanguage::Erb),
+    ("{{#each", TemplateLanguage::Handlebars),
+    ("{{#if", TemplateLanguage::Handlebars),
+    ("{{/if", TemplateLanguage::Handlebars),
+    ("{{range", TemplateLanguage::Go),
+    ("{{ range", TemplateLanguage::Go),
+    ("{{define", TemplateLanguage::Go),
+    ("{{ define", TemplateLanguage::Go),
+];
+
+// Number of lines at the start and end of a file that are searched for a modeline
+const MODELINE_LINES: usize = 5;
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
@@ -29,16 +57,96 @@ impl TemplateLanguage {
                 | TemplateLanguage::Twig
         )
     }
+
+    /// Detects the template language of a file, returns None if nothing points to a specific one.
+    /// Precedence: modeline, `template_languages` globs, file extension, content markers.
+    /// Modelines and content markers are only used when `sniff_template_language` is enabled.
+    pub fn detect(path: &str, content: &str, options: &Options) -> Option<Self> {
+        if options.sniff_template_language {
+            if let Some(template_language) = Self::from_modeline(content) {
+                return Some(template_language);
+            }
+        }
+
+        if let Some(template_language) = Self::from_glob_map(path, &options.template_languages) {
+            return Some(template_language);
+        }
+
+        let template_language = Self::from_filename(path);
+        if template_language != TemplateLanguage::None {
+            return Some(template_language);
+        }
+
+        if options.sniff_template_language {
+            return Self::from_content(content);
+        }
+
+        None
+    }
+
+    /// Finds the template language of the most specific (longest) glob matching the path.
+    pub fn from_glob_map(path: &str, globs: &HashMap<String, TemplateLanguage>) -> Option<Self> {
+        let mut globs: Vec<_> = globs.iter().collect();
+        globs.sort_by(|a, b| b.0.len().cmp(&a.0.len()).then(a.0.cmp(b.0)));
+
+        globs
+            .into_iter()
+            .find(|(glob, _)| {
+                // Also match the glob against the end of absolute paths, e.g. from the LSP
+                [glob.to_string(), format!("**/{}", glob)]
+                    .iter()
+                    .filter_map(|pattern| glob::Pattern::new(pattern).ok())
+                    .any(|pattern| pattern.matches(path))
+            })
+            .map(|(_, template_language)| template_language.clone())
+    }
+
+    /// Reads a modeline such as `<!-- htmlsnob: template_language=jinja2 -->` from the first or
+    /// last lines of the content.
+    pub fn from_modeline(content: &str) -> Option<Self> {
+        static MODELINE: OnceLock<Regex> = OnceLock::new();
+        let modeline = MODELINE.get_or_init(|| {
+            Regex::new(r"htmlsnob:\s*template_language\s*=\s*([A-Za-z0-9]+)").unwrap()
+        });
+        let lines: Vec<&str> = content.lines().collect();
+        let tail_start = lines
+            .len()
+            .saturating_sub(MODELINE_LINES)
+            .max(MODELINE_LINES);
+
+        lines
+            .iter()
+            .take(MODELINE_LINES)
+            .chain(lines.iter().skip(tail_start))
+            .find_map(|line| modeline.captures(line))
+            .and_then(|captures| captures[1].parse().ok())
+    }
+
+    /// Guesses the template language from markers in the content, such as `{% extends`.
+    pub fn from_content(content: &str) -> Option<Self> {
+        CONTENT_MARKERS
+            .iter()
+            .find(|(marker, _)| content.contains(marker))
+            .map(|(_, template_language)| template_language.clone())
+    }
+
     pub fn from_filename(filename: &str) -> Self {
         if filename.ends_with(".eex") {
             TemplateLanguage::Eex
         } else if filename.ends_with(".erb") {
             TemplateLanguage::Erb
-        } else if filename.ends_with(".gohtml") || filename.ends_with(".go") {
+        } else if filename.ends_with(".gohtml")
+            || filename.ends_with(".gotmpl")
+            || filename.ends_with(".tmpl")
+            || filename.ends_with(".go")
+        {
             TemplateLanguage::Go
         } else if filename.ends_with(".hbs") || filename.ends_with(".handlebars") {
             TemplateLanguage::Handlebars
-        } else if filename.ends_with(".jinja2") || filename.ends_with(".jinja") {
+        } else if filename.ends_with(".jinja2")
+            || filename.ends_with(".jinja")
+            || filename.ends_with(".njk")
+        {
             TemplateLanguage::Jinja2
         } else if filename.ends_with(".liquid") {
             TemplateLanguage::Liquid
@@ -254,6 +362,25 @@ impl TemplateLanguage {
     }
 }
 
+impl FromStr for TemplateLanguage {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "none" => Ok(TemplateLanguage::None),
+            "eex" => Ok(TemplateLanguage::Eex),
+            "handlebars" => Ok(TemplateLanguage::Handlebars),
+            "jinja2" => Ok(TemplateLanguage::Jinja2),
+            "liquid" => Ok(TemplateLanguage::Liquid),
+            "mustache" => Ok(TemplateLanguage::Mustache),
+            "erb" => Ok(TemplateLanguage::Erb),
+            "go" => Ok(TemplateLanguage::Go),
+            "twig" => Ok(TemplateLanguage::Twig),
+            _ => Err(format!("Unknown template language: {}", s)),
+        }
+    }
+}
+
 fn group(s1: &str, s2: &str, s3: &str, construct: Construct) -> (Regex, Regex, Regex, Construct) {
     (
         Regex::new(&format!(r"^{}", compile_bre_style(s1))).unwrap(),
@@ -273,3 +400,82 @@ fn compile_bre_style(pattern: &str) -> Regex {
 
     Regex::new(&custom_pattern).unwrap()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::registry::Registry;
+
+    const CONFIG: &str = r#"
+        sniff_template_language = true
+        [template_languages]
+        "*.tmpl" = "go"
+        "templates/**/*.html" = "jinja2"
+        "templates/emails/*.html" = "liquid"
+    "#;
+
+    #[test]
+    fn detect_from_glob_map() {
+        let config = Config::from_toml(CONFIG, &Registry::new());
+        let detect = |path| TemplateLanguage::detect(path, "", &config.options);
+
+        assert_eq!(detect("layout.tmpl"), Some(TemplateLanguage::Go));
+        assert_eq!(
+            detect("templates/base.html"),
+            Some(TemplateLanguage::Jinja2)
+        );
+        assert_eq!(
+            detect("/project/templates/base.html"),
+            Some(TemplateLanguage::Jinja2)
+        );
+        assert_eq!(
+            detect("templates/emails/welcome.html"),
+            Some(TemplateLanguage::Liquid)
+        );
+        assert_eq!(detect("index.hbs"), Some(TemplateLanguage::Handlebars));
+        assert_eq!(detect("index.html"), None);
+    }
+
+    #[test]
+    fn detect_from_modeline() {
+        let config = Config::from_toml(CONFIG, &Registry::new());
+        let content = "<!-- htmlsnob: template_language=handlebars -->\n<p>{{name}}</p>";
+
+        assert_eq!(
+            TemplateLanguage::detect("templates/base.html", content, &config.options),
+            Some(TemplateLanguage::Handlebars)
+        );
+    }
+
+    #[test]
+    fn detect_from_content_markers() {
+        let config = Config::from_toml(CONFIG, &Registry::new());
+        let detect = |content| TemplateLanguage::detect("index.html", content, &config.options);
+
+        assert_eq!(
+            detect("{% extends 'base.html' %}"),
+            Some(TemplateLanguage::Jinja2)
+        );
+        assert_eq!(
+            detect("<p><%= @user.name %></p>"),
+            Some(TemplateLanguage::Erb)
+        );
+        assert_eq!(
+            detect("{{#each items}}{{/each}}"),
+            Some(TemplateLanguage::Handlebars)
+        );
+        assert_eq!(detect("<p>Hello</p>"), None);
+    }
+
+    #[test]
+    fn sniffing_is_optional() {
+        let config = Config::from_toml("", &Registry::new());
+        let content = "<!-- htmlsnob: template_language=handlebars -->\n{% extends 'base.html' %}";
+
+        assert_eq!(
+            TemplateLanguage::detect("index.html", content, &config.options),
+            None
+        );
+    }
+}
@@ -38,7 +38,7 @@ pub mod tests {
             .iter()
             .map(|area| (area.start.column, area.end.column))
             .collect::<Vec<_>>();
-        warning_ranges.sort_by(|a, b| a.0.cmp(&b.0));
+        warning_ranges.sort_by_key(|range| range.0);
 
         let actual_range_str = ranges_to_string(&warning_ranges);
         let expected_range_str = ranges_to_string(&expected_ranges);
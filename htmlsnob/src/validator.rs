@@ -1,17 +1,60 @@
-use crate::{ast::Node, config::Config, parser::ParseState, Warning};
+use crate::{
+    ast::{Node, Position},
+    config::Config,
+    directives::Directives,
+    parser::ParseState,
+    Warning,
+};
+use std::time::{Duration, Instant};
 
 pub struct Validator<'a> {
     config: &'a mut Config,
+    // Time spent in the hooks of each rule, indexed like `config.rules`. None when not profiling.
+    durations: Option<Vec<Duration>>,
+    // The name of each rule, indexed like `config.rules`
+    rule_names: Vec<String>,
+    directives: Directives,
 }
 
 impl<'a> Validator<'a> {
     pub fn new(config: &'a mut Config) -> Self {
-        Validator { config }
+        let rule_names = (0..config.rules.len())
+            .map(|index| config.rule_name(index))
+            .collect();
+
+        Validator {
+            config,
+            durations: None,
+            rule_names,
+            directives: Directives::default(),
+        }
+    }
+
+    pub fn with_profiling(config: &'a mut Config) -> Self {
+        let durations = Some(vec![Duration::ZERO; config.rules.len()]);
+        Validator {
+            durations,
+            ..Validator::new(config)
+        }
+    }
+
+    pub fn config(&self) -> &Config {
+        self.config
+    }
+
+    pub fn durations(&self) -> Vec<Duration> {
+        self.durations.clone().unwrap_or_default()
     }
 
     pub fn validate(&mut self, node: &mut Node, parse_state: &ParseState) -> Vec<Warning> {
-        let warnings = self.apply(node, parse_state);
+        if let Node::Comment(comment) = node {
+            self.directives.track_comment(comment);
+        }
+        self.directives.track_node(node.area());
+
+        let mut warnings = self.apply(node, parse_state);
         self.track(node, parse_state);
+        warnings.retain(|warning| !self.is_disabled(warning));
         warnings
     }
 
@@ -19,13 +62,16 @@ impl<'a> Validator<'a> {
         let mut warnings = Vec::new();
 
         for open_tag_index in parse_state.open_tag_indexes.iter() {
-            for rule in self.config.rules.iter_mut() {
+            for (index, rule) in self.config.rules.iter_mut().enumerate() {
+                let start = self.durations.as_ref().map(|_| Instant::now());
                 let open_tag = match &parse_state.ast[*open_tag_index] {
                     Node::OpenTag(open_tag) => open_tag,
                     _ => panic!("Expected OpenTag at index {}", open_tag_index),
                 };
 
                 warnings.extend(rule.apply_tag(Some(open_tag), None, parse_state));
+                warnings.extend(rule.apply_element(open_tag, None, parse_state));
+                record(&mut self.durations, index, start);
             }
         }
 
@@ -33,17 +79,40 @@ impl<'a> Validator<'a> {
             rule.reset_state();
         }
 
+        warnings.retain(|warning| !self.is_disabled(warning));
         warnings
     }
 
+    /// Whether a directive comment disables the rule of the warning where the warning starts
+    fn is_disabled(&self, warning: &Warning) -> bool {
+        let start = warning.areas.first().map(|area| &area.start);
+        self.directives
+            .is_disabled(&warning.name, start.unwrap_or(&Position::default()))
+    }
+
     fn apply(&mut self, node: &mut Node, parse_state: &ParseState) -> Vec<Warning> {
         let mut warnings = Vec::new();
+        let node_start = node.area().start.clone();
 
-        for rule in self.config.rules.iter_mut() {
+        for (index, rule) in self.config.rules.iter_mut().enumerate() {
+            // Disabled rules are skipped, so they don't autofix either. A close tag still completes
+            // its element, which may start outside of the disabled region, so the warnings of the
+            // element are filtered by where they start instead.
+            let is_disabled = self
+                .directives
+                .is_disabled(&self.rule_names[index], &node_start);
+            if is_disabled && !matches!(node, Node::CloseTag(_)) {
+                continue;
+            }
+
+            let start = self.durations.as_ref().map(|_| Instant::now());
             match node {
                 Node::OpenTag(open_tag) => {
                     warnings.extend(rule.apply_open_tag(open_tag, parse_state));
                     warnings.extend(rule.apply_open_tag_vec(open_tag, parse_state));
+                    if open_tag.is_void_or_self_closed() {
+                        warnings.extend(rule.apply_element(open_tag, None, parse_state));
+                    }
 
                     for attribute in open_tag.attributes.iter_mut() {
                         warnings.extend(rule.apply_attribute(attribute));
@@ -60,10 +129,21 @@ impl<'a> Validator<'a> {
                         }
                     }
 
-                    warnings.extend(rule.apply_close_tag(close_tag, parse_state));
-                    warnings.extend(rule.apply_close_tag_vec(close_tag, parse_state));
+                    if !is_disabled {
+                        warnings.extend(rule.apply_close_tag(close_tag, parse_state));
+                        warnings.extend(rule.apply_close_tag_vec(close_tag, parse_state));
+                    }
                     warnings.extend(rule.apply_tag(open_tag, Some(close_tag), parse_state));
                     warnings.extend(rule.apply_tag_vec(open_tag, Some(close_tag), parse_state));
+                    if let Some(open_tag) = open_tag {
+                        warnings.extend(rule.apply_element(open_tag, Some(close_tag), parse_state));
+                    }
+
+                    // Like at the end of the document, for the elements the close tag left open
+                    for open_tag in parse_state.unclosed_open_tags() {
+                        warnings.extend(rule.apply_tag(Some(open_tag), None, parse_state));
+                        warnings.extend(rule.apply_element(open_tag, None, parse_state));
+                    }
                 }
                 Node::Text(text) => warnings.extend(rule.apply_text(text, parse_state)),
                 Node::TemplateExpression(template_expression) => {
@@ -82,19 +162,28 @@ impl<'a> Validator<'a> {
                     warnings.extend(rule.apply_comment_vec(comment, parse_state));
                 }
             };
+            record(&mut self.durations, index, start);
         }
 
         warnings
     }
 
     fn track(&mut self, node: &Node, parse_state: &ParseState) {
-        for rule in self.config.rules.iter_mut() {
+        for (index, rule) in self.config.rules.iter_mut().enumerate() {
+            let start = self.durations.as_ref().map(|_| Instant::now());
             match node {
                 Node::OpenTag(open_tag) => rule.track_open_tag(open_tag, parse_state),
                 Node::CloseTag(close_tag) => rule.track_close_tag(close_tag, parse_state),
                 Node::Text(text) => rule.track_text(text, parse_state),
                 _ => {}
             }
+            record(&mut self.durations, index, start);
         }
     }
 }
+
+fn record(durations: &mut Option<Vec<Duration>>, index: usize, start: Option<Instant>) {
+    if let (Some(durations), Some(start)) = (durations, start) {
+        durations[index] += start.elapsed();
+    }
+}
@@ -0,0 +1,20 @@
+// Generated from `htmlsnob_scripts/data/void_elements.json` by
+// `cargo run --bin void_elements_from_specs`, do not edit.
+
+#[rustfmt::skip]
+pub const VOID_ELEMENTS: [&str; 14] = [
+    "area",
+    "base",
+    "br",
+    "col",
+    "embed",
+    "hr",
+    "img",
+    "input",
+    "link",
+    "meta",
+    "param",
+    "source",
+    "track",
+    "wbr",
+];
@@ -30,11 +30,17 @@ impl Warning {
     ) -> Warning {
         let name_or_kind = if name.is_empty() { kind } else { name };
 
+        // Empty areas, like the ones of implied close tags, can't be shown next to other areas
+        let mut areas = areas.to_vec();
+        if areas.iter().any(|area| area.start != area.end) {
+            areas.retain(|area| area.start != area.end);
+        }
+
         Warning {
             name: name_or_kind.to_string(),
             severity,
             message: message.to_string(),
-            areas: areas.to_vec(),
+            areas,
         }
     }
 
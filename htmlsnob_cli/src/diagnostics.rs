@@ -0,0 +1,385 @@
+use htmlsnob::ast::Area;
+use htmlsnob::{Warning, WarningSeverity};
+use std::collections::BTreeSet;
+
+/// Number of source lines shown before and after each labelled line
+const CONTEXT_LINES: usize = 2;
+
+const RESET: &str = "\x1B[0m";
+const BOLD: &str = "\x1B[1m";
+const RED: &str = "\x1B[1;31m";
+const YELLOW: &str = "\x1B[1;33m";
+const BLUE: &str = "\x1B[1;34m";
+const CYAN: &str = "\x1B[1;36m";
+
+/// A part of an area that lies on a single line, columns are counted in chars
+struct Segment {
+    line: usize,
+    start: usize,
+    end: usize,
+    primary: bool,
+    label: Option<String>,
+}
+
+struct Painter {
+    color: bool,
+}
+
+impl Painter {
+    fn paint(&self, style: &str, text: &str) -> String {
+        if self.color && !text.is_empty() {
+            format!("{}{}{}", style, text, RESET)
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+/// Renders a warning with the source lines of all its areas, e.g.:
+///
+/// ```text
+/// error[child_requirement]: Must contain `head`
+///  --> index.html:1:1
+///   |
+/// 1 | <html><body></body></html>
+///   | ^^^^^^             ------- related
+///   | |
+///   | Must contain `head`
+/// ```
+pub fn render(warning: &Warning, content: &str, file_path: &str, color: bool) -> String {
+    let painter = Painter { color };
+    let lines: Vec<&str> = content.lines().collect();
+
+    let (severity, severity_style) = match warning.severity {
+        WarningSeverity::ERROR => ("error", RED),
+        WarningSeverity::WARNING => ("warning", YELLOW),
+        WarningSeverity::INFORMATION => ("info", BLUE),
+        WarningSeverity::HINT => ("hint", CYAN),
+    };
+
+    let mut result = format!(
+        "{}{}\n",
+        painter.paint(severity_style, &format!("{}[{}]", severity, warning.name)),
+        painter.paint(BOLD, &format!(": {}", warning.message)),
+    );
+
+    let Some(primary_area) = warning.areas.first() else {
+        result.push_str(&format!("{} {}\n", painter.paint(BLUE, "-->"), file_path));
+        return result;
+    };
+
+    let segments: Vec<Segment> = warning
+        .areas
+        .iter()
+        .enumerate()
+        .flat_map(|(index, area)| {
+            let label = if index == 0 {
+                warning.message.clone()
+            } else {
+                "related".to_string()
+            };
+            segments(area, &lines, index == 0, label)
+        })
+        .collect();
+
+    let mut shown_lines = BTreeSet::new();
+    for segment in &segments {
+        let first = segment.line.saturating_sub(CONTEXT_LINES);
+        let last = (segment.line + CONTEXT_LINES).min(lines.len().saturating_sub(1));
+        shown_lines.extend(first..=last);
+    }
+
+    let gutter_width = shown_lines
+        .last()
+        .map(|line| (line + 1).to_string().len())
+        .unwrap_or(1);
+    let gutter = |text: &str| painter.paint(BLUE, &format!("{:>gutter_width$} |", text));
+
+    result.push_str(&format!(
+        "{}{} {}:{}:{}\n",
+        " ".repeat(gutter_width),
+        painter.paint(BLUE, "-->"),
+        file_path,
+        primary_area.start.line + 1,
+        primary_area.start.column + 1
+    ));
+    result.push_str(&format!("{}\n", gutter("")));
+
+    let mut previous_line = None;
+    for line in shown_lines {
+        if previous_line.is_some_and(|previous| line > previous + 1) {
+            result.push_str(&format!("{}\n", painter.paint(BLUE, "...")));
+        }
+        previous_line = Some(line);
+
+        let text = lines.get(line).copied().unwrap_or("");
+        result.push_str(&format!("{} {}\n", gutter(&(line + 1).to_string()), text));
+
+        let line_segments: Vec<&Segment> = segments.iter().filter(|s| s.line == line).collect();
+        if line_segments.is_empty() {
+            continue;
+        }
+
+        result.push_str(&underline(
+            &line_segments,
+            &gutter(""),
+            &painter,
+            severity_style,
+        ));
+    }
+
+    result
+}
+
+/// Splits an area into one segment per line it covers
+fn segments(area: &Area, lines: &[&str], primary: bool, label: String) -> Vec<Segment> {
+    let line_length = |line: usize| lines.get(line).map_or(0, |l| l.chars().count());
+
+    // An area ending at the very start of a line really ends on the line before
+    let mut end_line = area.end.line;
+    if end_line > area.start.line && area.end.column == 0 {
+        end_line -= 1;
+    }
+    let end_line = end_line.max(area.start.line);
+
+    (area.start.line..=end_line)
+        .map(|line| {
+            let start = if line == area.start.line {
+                area.start.column
+            } else {
+                // Skip the indentation of continuation lines
+                lines
+                    .get(line)
+                    .map_or(0, |l| l.chars().take_while(|c| c.is_whitespace()).count())
+            };
+            let end = if line == area.end.line {
+                area.end.column
+            } else {
+                line_length(line)
+            };
+
+            Segment {
+                line,
+                start,
+                // Always underline at least one character, e.g. for missing brackets
+                end: end.max(start + 1),
+                primary,
+                label: (line == end_line).then(|| label.clone()),
+            }
+        })
+        .collect()
+}
+
+/// Renders the markers below a line, followed by the labels of the segments ending on it
+fn underline(
+    segments: &[&Segment],
+    gutter: &str,
+    painter: &Painter,
+    primary_style: &str,
+) -> String {
+    let width = segments.iter().map(|s| s.end).max().unwrap_or(0);
+    let mut markers = vec![' '; width];
+    for segment in segments.iter().filter(|s| !s.primary) {
+        markers[segment.start..segment.end].fill('-');
+    }
+    for segment in segments.iter().filter(|s| s.primary) {
+        markers[segment.start..segment.end].fill('^');
+    }
+
+    let mut painted = String::new();
+    let mut run = String::new();
+    for (index, marker) in markers.iter().enumerate() {
+        run.push(*marker);
+        if markers.get(index + 1) != Some(marker) {
+            let style = if *marker == '^' { primary_style } else { BLUE };
+            painted.push_str(&painter.paint(style, &run));
+            run.clear();
+        }
+    }
+
+    // The rightmost label goes on the marker line, the others on lines of their own below it
+    let mut labelled: Vec<&&Segment> = segments.iter().filter(|s| s.label.is_some()).collect();
+    labelled.sort_by_key(|s| s.start);
+    let style = |segment: &Segment| if segment.primary { primary_style } else { BLUE };
+
+    let mut result = format!("{} {}", gutter, painted);
+    if let Some(last) = labelled.pop() {
+        result.push_str(&format!(
+            " {}",
+            painter.paint(style(last), last.label.as_deref().unwrap_or(""))
+        ));
+    }
+    result.push('\n');
+
+    for segment in labelled.iter().rev() {
+        let mut pipes = String::new();
+        let mut column = 0;
+        for other in labelled.iter().filter(|s| s.start < segment.start) {
+            // Labels that start in the same column share a pipe
+            if other.start < column {
+                continue;
+            }
+            pipes.push_str(&" ".repeat(other.start - column));
+            pipes.push_str(&painter.paint(style(other), "|"));
+            column = other.start + 1;
+        }
+        pipes.push_str(&" ".repeat(segment.start - column));
+        result.push_str(&format!(
+            "{} {}{}\n{} {}{}\n",
+            gutter,
+            pipes,
+            painter.paint(style(segment), "|"),
+            gutter,
+            pipes,
+            painter.paint(style(segment), segment.label.as_deref().unwrap_or(""))
+        ));
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use htmlsnob::ast::Position;
+
+    fn area(start: (usize, usize), end: (usize, usize)) -> Area {
+        Area {
+            start: Position {
+                line: start.0,
+                column: start.1,
+            },
+            end: Position {
+                line: end.0,
+                column: end.1,
+            },
+        }
+    }
+
+    #[test]
+    fn single_line() {
+        let warning = Warning::from_area(
+            "",
+            "tag_name_casing",
+            area((0, 0), (0, 3)),
+            "Tag name \"P\" should be in lowercase",
+            WarningSeverity::WARNING,
+        );
+
+        assert_eq!(
+            render(&warning, "<P>hi</p>", "index.html", false),
+            r#"warning[tag_name_casing]: Tag name "P" should be in lowercase
+ --> index.html:1:1
+  |
+1 | <P>hi</p>
+  | ^^^ Tag name "P" should be in lowercase
+"#
+        );
+    }
+
+    #[test]
+    fn multiple_spans_and_context() {
+        let warning = Warning::from_areas(
+            "",
+            "child_requirement",
+            &[area((1, 0), (1, 6)), area((4, 0), (4, 7))],
+            "Must contain `head`",
+            WarningSeverity::ERROR,
+        );
+        let content = "<!DOCTYPE html>\n<html>\n  <body>\n  </body>\n</html>\n";
+
+        assert_eq!(
+            render(&warning, content, "index.html", false),
+            r#"error[child_requirement]: Must contain `head`
+ --> index.html:2:1
+  |
+1 | <!DOCTYPE html>
+2 | <html>
+  | ^^^^^^ Must contain `head`
+3 |   <body>
+4 |   </body>
+5 | </html>
+  | ------- related
+"#
+        );
+    }
+
+    #[test]
+    fn multi_line_span_ending_before_start_column() {
+        let warning = Warning::from_area(
+            "",
+            "attribute_name_requirement",
+            area((0, 5), (1, 7)),
+            "Missing `alt`",
+            WarningSeverity::ERROR,
+        );
+        let content = "<div><img\n  src>";
+
+        assert_eq!(
+            render(&warning, content, "index.html", false),
+            r#"error[attribute_name_requirement]: Missing `alt`
+ --> index.html:1:6
+  |
+1 | <div><img
+  |      ^^^^
+2 |   src>
+  |   ^^^^^ Missing `alt`
+"#
+        );
+    }
+
+    #[test]
+    fn labels_on_the_same_line() {
+        let warning = Warning::from_areas(
+            "",
+            "child_requirement",
+            &[area((0, 0), (0, 6)), area((0, 19), (0, 26))],
+            "Must contain `head`",
+            WarningSeverity::ERROR,
+        );
+
+        assert_eq!(
+            render(&warning, "<html><body></body></html>", "index.html", false),
+            r#"error[child_requirement]: Must contain `head`
+ --> index.html:1:1
+  |
+1 | <html><body></body></html>
+  | ^^^^^^             ------- related
+  | |
+  | Must contain `head`
+"#
+        );
+    }
+
+    #[test]
+    fn labels_in_the_same_column() {
+        let warning = Warning::from_areas(
+            "",
+            "child_requirement",
+            &[
+                area((0, 0), (0, 6)),
+                area((0, 0), (0, 3)),
+                area((0, 6), (0, 12)),
+                area((0, 19), (0, 26)),
+            ],
+            "Must contain `head`",
+            WarningSeverity::ERROR,
+        );
+
+        assert_eq!(
+            render(&warning, "<html><body></body></html>", "index.html", false),
+            r#"error[child_requirement]: Must contain `head`
+ --> index.html:1:1
+  |
+1 | <html><body></body></html>
+  | ^^^^^^------       ------- related
+  | |     |
+  | |     related
+  | |
+  | related
+  | |
+  | Must contain `head`
+"#
+        );
+    }
+}
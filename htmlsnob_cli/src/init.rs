@@ -0,0 +1,485 @@
+use crate::{Runner, SimpleArgs, CONFIG_FILE_NAME};
+use htmlsnob::ast::{Either, Node};
+use htmlsnob::case_converter::CaseStyle;
+use htmlsnob::config::{Config, Options};
+use htmlsnob::lint;
+use htmlsnob::parser::{is_void_element, VOID_ELEMENTS};
+use htmlsnob::registry::Registry;
+use htmlsnob::template_language::TemplateLanguage;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+// Same list as in default_config.toml
+const BOOLEAN_ATTRIBUTES: [&str; 29] = [
+    "allowfullscreen",
+    "alpha",
+    "async",
+    "autofocus",
+    "autoplay",
+    "checked",
+    "controls",
+    "default",
+    "defer",
+    "disabled",
+    "formnovalidate",
+    "inert",
+    "ismap",
+    "itemscope",
+    "loop",
+    "multiple",
+    "muted",
+    "nomodule",
+    "novalidate",
+    "open",
+    "playsinline",
+    "readonly",
+    "required",
+    "reversed",
+    "selected",
+    "shadowrootclonable",
+    "shadowrootcustomelementregistry",
+    "shadowrootdelegatesfocus",
+    "shadowrootserializable",
+];
+
+// Case styles that can be inferred, with their names in the config
+const CASE_STYLES: [(&str, CaseStyle); 4] = [
+    ("kebab_case", CaseStyle::KebabCase),
+    ("snake_case", CaseStyle::SnakeCase),
+    ("camel_case", CaseStyle::CamelCase),
+    ("pascal_case", CaseStyle::PascalCase),
+];
+
+/// Maximum number of attributes in the inferred `attributes_order`
+const MAX_ORDERED_ATTRIBUTES: usize = 12;
+
+/// Tally of the style choices found in the scanned files
+#[derive(Default)]
+struct Observations {
+    quote_styles: BTreeMap<&'static str, usize>,
+    boolean_attribute_styles: BTreeMap<&'static str, usize>,
+    self_closing_styles: BTreeMap<&'static str, usize>,
+    attribute_name_styles: BTreeMap<&'static str, usize>,
+    class_name_styles: BTreeMap<&'static str, usize>,
+    indent_steps: BTreeMap<usize, usize>,
+    attribute_counts: HashMap<String, usize>,
+    attribute_precedence: HashMap<(String, String), usize>, // (before, after) -> count
+}
+
+/// A rule to enable, with a comment explaining the evidence for it
+struct RuleBlock {
+    kind: &'static str,
+    comment: String,
+    body: String,
+}
+
+/// Scans the files matching the paths, and writes a `.htmlsnob.toml` that enforces the
+/// conventions they already follow
+pub fn init(args: SimpleArgs, force: bool) -> (i32, String) {
+    let mut runner = Runner::default();
+
+    if Path::new(CONFIG_FILE_NAME).exists() && !force {
+        return (
+            1,
+            format!(
+                "{} already exists, use --force to overwrite it\n",
+                CONFIG_FILE_NAME
+            ),
+        );
+    }
+
+    let file_paths = runner.matched_file_paths(&args);
+    if file_paths.is_empty() {
+        return (1, runner.result);
+    }
+
+    let files: Vec<(String, String)> = file_paths
+        .iter()
+        .filter_map(|path| {
+            let content = std::fs::read_to_string(path).ok()?;
+            Some((path.display().to_string(), content))
+        })
+        .collect();
+
+    let config = infer_config(&files, &args.template_language, &args.registry);
+
+    if let Err(e) = std::fs::write(CONFIG_FILE_NAME, &config) {
+        runner.result.push_str(&format!(
+            "Failed to write to file '{}': {}\n",
+            CONFIG_FILE_NAME, e
+        ));
+        return (1, runner.result);
+    }
+
+    runner.result.push_str(&config);
+    runner.result.push_str(&format!(
+        "\nWrote {} based on {} files\n",
+        CONFIG_FILE_NAME,
+        files.len()
+    ));
+    (0, runner.result)
+}
+
+/// Infers a commented config from (path, content) pairs
+fn infer_config(
+    files: &[(String, String)],
+    template_language: &Option<TemplateLanguage>,
+    registry: &Registry,
+) -> String {
+    let mut observations = Observations::default();
+    let mut config = Config::from_toml("", &Registry::new());
+    // Template languages are detected with the default options, which are built once
+    let options = config.options.clone();
+    for (path, content) in files {
+        config.options.template_language =
+            file_template_language(path, content, template_language, &options);
+        let (ast, _) = lint(content, &mut config);
+        observations.observe_ast(&ast);
+        observations.observe_indentation(content);
+    }
+
+    let indent_size = most_common(&observations.indent_steps).unwrap_or(2);
+    let blocks = observations.rule_blocks();
+
+    // Lint the files with the inferred config, to report how many currently violate each rule
+    let mut config = Config::from_toml(&render(files.len(), indent_size, &blocks, None), registry);
+    let mut violations: HashMap<String, usize> = HashMap::new();
+    for (path, content) in files {
+        config.options.template_language =
+            file_template_language(path, content, template_language, &options);
+        let (_, warnings) = lint(content, &mut config);
+        let names: HashSet<String> = warnings.into_iter().map(|w| w.name).collect();
+        for name in names {
+            *violations.entry(name).or_default() += 1;
+        }
+    }
+
+    render(files.len(), indent_size, &blocks, Some(&violations))
+}
+
+fn file_template_language(
+    path: &str,
+    content: &str,
+    template_language: &Option<TemplateLanguage>,
+    options: &Options,
+) -> TemplateLanguage {
+    template_language
+        .clone()
+        .or_else(|| TemplateLanguage::detect(path, content, options))
+        .unwrap_or_default()
+}
+
+fn render(
+    file_count: usize,
+    indent_size: usize,
+    blocks: &[RuleBlock],
+    violations: Option<&HashMap<String, usize>>,
+) -> String {
+    let mut result = format!(
+        "# Generated by `htmlsnob init` from the conventions found in {} files.\n\
+         # See default_config.toml and htmlsnob_rules/src/lib.rs for more rules.\n\n\
+         indent_size = {}\n\
+         max_line_length = 80\n",
+        file_count, indent_size
+    );
+
+    for block in blocks {
+        result.push_str(&format!("\n# {}\n", block.comment));
+        if let Some(violations) = violations {
+            let count = violations.get(block.kind).copied().unwrap_or(0);
+            result.push_str(&format!(
+                "# {} of {} files currently violate this rule.\n",
+                count, file_count
+            ));
+        }
+        result.push_str(&format!(
+            "[[rules]]\nkind = \"{}\"\n{}",
+            block.kind, block.body
+        ));
+    }
+
+    result
+}
+
+impl Observations {
+    fn observe_ast(&mut self, ast: &[Node]) {
+        for node in ast {
+            let Node::OpenTag(open_tag) = node else {
+                continue;
+            };
+
+            if is_void_element(&open_tag.name) {
+                let style = if open_tag.self_closed {
+                    "closed"
+                } else {
+                    "open"
+                };
+                *self.self_closing_styles.entry(style).or_default() += 1;
+            }
+
+            let mut names = Vec::new();
+            for attribute in &open_tag.attributes {
+                let Either::Left(name) = &attribute.name else {
+                    continue;
+                };
+                let name = name.content.as_str();
+                names.push(name.to_string());
+                tally_case_styles(&mut self.attribute_name_styles, name);
+
+                if let Some(value) = &attribute.value {
+                    match value.start_quote {
+                        Some('"') => *self.quote_styles.entry("double").or_default() += 1,
+                        Some('\'') => *self.quote_styles.entry("single").or_default() += 1,
+                        _ => {}
+                    }
+                }
+
+                if BOOLEAN_ATTRIBUTES.contains(&name) {
+                    let style = match &attribute.value {
+                        None => "no_value",
+                        Some(value) if value.parts.is_empty() => "empty_value",
+                        Some(value) if value.string_areas().iter().any(|s| s.content == name) => {
+                            "same_value"
+                        }
+                        Some(_) => continue,
+                    };
+                    *self.boolean_attribute_styles.entry(style).or_default() += 1;
+                }
+
+                if name == "class" {
+                    for class in attribute.value.iter().flat_map(|v| v.string_areas()) {
+                        tally_case_styles(&mut self.class_name_styles, &class.content);
+                    }
+                }
+            }
+
+            for (index, before) in names.iter().enumerate() {
+                *self.attribute_counts.entry(before.clone()).or_default() += 1;
+                for after in &names[index + 1..] {
+                    *self
+                        .attribute_precedence
+                        .entry((before.clone(), after.clone()))
+                        .or_default() += 1;
+                }
+            }
+        }
+    }
+
+    /// Counts the increase in indentation between consecutive lines indented with spaces
+    fn observe_indentation(&mut self, content: &str) {
+        let mut previous_indent = 0;
+        for line in content.lines().filter(|line| !line.trim().is_empty()) {
+            if line.starts_with('\t') {
+                continue;
+            }
+            let indent = line.chars().take_while(|c| *c == ' ').count();
+            if indent > previous_indent {
+                *self
+                    .indent_steps
+                    .entry(indent - previous_indent)
+                    .or_default() += 1;
+            }
+            previous_indent = indent;
+        }
+    }
+
+    fn rule_blocks(&self) -> Vec<RuleBlock> {
+        let mut blocks = Vec::new();
+
+        if let Some(style) = most_common(&self.quote_styles) {
+            blocks.push(RuleBlock {
+                kind: "attribute_value_quote_style",
+                comment: share_comment(&self.quote_styles, style, "quoted attribute values"),
+                body: format!("style = \"{}\"\nautofix = true\n", style),
+            });
+        }
+
+        if let Some(style) = most_common(&self.boolean_attribute_styles) {
+            blocks.push(RuleBlock {
+                kind: "boolean_attribute_style",
+                comment: share_comment(&self.boolean_attribute_styles, style, "boolean attributes"),
+                body: format!(
+                    "style = \"{}\"\nautofix = true\nattributes = {}\n",
+                    style,
+                    toml_list(BOOLEAN_ATTRIBUTES.iter())
+                ),
+            });
+        }
+
+        if let Some(style) = most_common(&self.self_closing_styles) {
+            blocks.push(RuleBlock {
+                kind: "self_closing_tag_style",
+                comment: share_comment(&self.self_closing_styles, style, "void elements"),
+                body: format!(
+                    "style = \"{}\"\nautofix = true\ntags = {}\n",
+                    style,
+                    toml_list(VOID_ELEMENTS.iter())
+                ),
+            });
+        }
+
+        if let Some(style) = most_common(&self.attribute_name_styles) {
+            blocks.push(RuleBlock {
+                kind: "attribute_name_casing_style",
+                comment: share_comment(
+                    &self.attribute_name_styles,
+                    style,
+                    "multi-word attribute names",
+                ),
+                body: format!("style = \"{}\"\n", style),
+            });
+        }
+
+        if let Some(style) = most_common(&self.class_name_styles) {
+            blocks.push(RuleBlock {
+                kind: "class_name_casing_style",
+                comment: share_comment(&self.class_name_styles, style, "multi-word class names"),
+                body: format!("case_style = \"{}\"\n", style),
+            });
+        }
+
+        let order = self.attribute_order();
+        if order.len() > 1 {
+            blocks.push(RuleBlock {
+                kind: "attributes_order",
+                comment: "The order most attributes appear in, among the most common ones."
+                    .to_string(),
+                body: format!("order = {}\n", toml_list(order.iter())),
+            });
+        }
+
+        blocks
+    }
+
+    /// Orders the most common attributes by how many other attributes they usually precede
+    fn attribute_order(&self) -> Vec<String> {
+        let mut common: Vec<(&String, &usize)> = self
+            .attribute_counts
+            .iter()
+            .filter(|(name, count)| **count > 1 && !name.contains('-'))
+            .collect();
+        common.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+        common.truncate(MAX_ORDERED_ATTRIBUTES);
+
+        let precedence = |a: &String, b: &String| {
+            self.attribute_precedence
+                .get(&(a.clone(), b.clone()))
+                .copied()
+                .unwrap_or(0)
+        };
+
+        let mut scored: Vec<(usize, usize, String)> = common
+            .iter()
+            .map(|(name, count)| {
+                let wins = common
+                    .iter()
+                    .filter(|(other, _)| precedence(name, other) > precedence(other, name))
+                    .count();
+                (wins, **count, (*name).clone())
+            })
+            .collect();
+        scored.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.cmp(&a.1)).then(a.2.cmp(&b.2)));
+
+        scored.into_iter().map(|(_, _, name)| name).collect()
+    }
+}
+
+/// Counts the case style a name is written in. Names that fit several styles, like `class`, say
+/// nothing about the style and are ignored.
+fn tally_case_styles(tally: &mut BTreeMap<&'static str, usize>, name: &str) {
+    let matching: Vec<&'static str> = CASE_STYLES
+        .iter()
+        .filter(|(_, style)| style.convert(name) == name)
+        .map(|(style_name, _)| *style_name)
+        .collect();
+
+    if let [style_name] = matching[..] {
+        *tally.entry(style_name).or_default() += 1;
+    }
+}
+
+fn most_common<K: Copy + Ord>(tally: &BTreeMap<K, usize>) -> Option<K> {
+    tally
+        .iter()
+        .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(a.0)))
+        .map(|(key, _)| *key)
+}
+
+fn share_comment(tally: &BTreeMap<&'static str, usize>, style: &str, subject: &str) -> String {
+    let total: usize = tally.values().sum();
+    let count = tally.get(style).copied().unwrap_or(0);
+    format!(
+        "{}% of {} {} use the `{}` style.",
+        count * 100 / total.max(1),
+        total,
+        subject,
+        style
+    )
+}
+
+fn toml_list<T: AsRef<str>>(items: impl Iterator<Item = T>) -> String {
+    let items: Vec<String> = items.map(|item| format!("\"{}\"", item.as_ref())).collect();
+    format!("[{}]", items.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn infers_dominant_conventions() {
+        let files = vec![
+            (
+                "index.html".to_string(),
+                "<div class='main-card' id='a'>\n    <input type='checkbox' checked />\n</div>\n"
+                    .to_string(),
+            ),
+            (
+                "about.html".to_string(),
+                "<div class='side-bar' id='b'>\n    <br>\n    <img src=\"a.png\" />\n</div>\n"
+                    .to_string(),
+            ),
+        ];
+
+        let config = infer_config(&files, &None, &htmlsnob_rules::registry());
+
+        assert!(config.contains("indent_size = 4\n"), "{}", config);
+        assert!(
+            config.contains(
+                "# 83% of 6 quoted attribute values use the `single` style.\n\
+             # 1 of 2 files currently violate this rule.\n\
+             [[rules]]\n\
+             kind = \"attribute_value_quote_style\"\n\
+             style = \"single\"\n"
+            ),
+            "{}",
+            config
+        );
+        assert!(
+            config.contains(
+                "# 100% of 1 boolean attributes use the `no_value` style.\n\
+             # 0 of 2 files currently violate this rule.\n"
+            ),
+            "{}",
+            config
+        );
+        assert!(
+            config.contains(
+                "# 66% of 3 void elements use the `closed` style.\n\
+             # 1 of 2 files currently violate this rule.\n"
+            ),
+            "{}",
+            config
+        );
+        assert!(
+            config.contains("case_style = \"kebab_case\"\n"),
+            "{}",
+            config
+        );
+        assert!(
+            config.contains("order = [\"class\", \"id\"]\n"),
+            "{}",
+            config
+        );
+    }
+}
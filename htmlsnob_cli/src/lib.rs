@@ -1,32 +1,96 @@
 use glob::glob;
-use htmlsnob::ast::Area;
 use htmlsnob::config;
 use htmlsnob::lint;
+use htmlsnob::lint_with_profiling;
 use htmlsnob::registry::Registry;
-use std::path::Path;
+use htmlsnob::template_language::TemplateLanguage;
+use std::path::{Path, PathBuf};
+
+pub mod diagnostics;
+pub mod init;
+pub mod stats;
+pub mod watch;
+
+use stats::Stats;
+
+/// Config file used when no `--config` is given, if it exists in the working directory
+pub const CONFIG_FILE_NAME: &str = ".htmlsnob.toml";
 
 pub struct SimpleArgs {
     pub paths: Vec<String>,
     pub autofix: bool,
     pub config: Option<String>,
     pub ignore: Vec<String>,
+    pub template_language: Option<TemplateLanguage>,
+    /// Use colors in the output, e.g. when writing to a terminal
+    pub color: bool,
+    /// Print warning counts per rule and per file
+    pub stats: bool,
+    /// Print the time spent in each rule
+    pub profile: bool,
     pub registry: Registry,
 }
 
+#[derive(Default)]
 struct Runner {
     result: String,
+    warning_count: usize,
+    stats: Stats,
 }
 
 pub fn run(args: SimpleArgs) -> (i32, String) {
-    let mut runner = Runner {
-        result: String::new(),
-    };
+    let mut runner = Runner::default();
     let status_code = runner.run(args);
     (status_code, runner.result)
 }
 
 impl Runner {
     fn run(&mut self, args: SimpleArgs) -> i32 {
+        let matched_file_paths = self.matched_file_paths(&args);
+
+        if matched_file_paths.is_empty() {
+            return 1;
+        }
+
+        let mut success = true;
+        let mut config = load_config(&args.config, &args.registry);
+
+        // The configured template language is used for files that don't point to a specific one
+        let configured_template_language = config.options.template_language.clone();
+
+        for file_path in matched_file_paths {
+            let result = self.lint_file(
+                &file_path,
+                &mut config,
+                &configured_template_language,
+                &args,
+            );
+            match result {
+                Ok(file_success) => success &= file_success,
+                Err(e) => {
+                    self.result.push_str(&format!(
+                        "Failed to read file '{}': {}\n",
+                        file_path.display(),
+                        e
+                    ));
+                    success = false;
+                }
+            }
+        }
+
+        if args.stats || args.profile {
+            self.result.push_str(&self.stats.render());
+        }
+
+        if success {
+            self.result.push_str("Success: No issues found\n");
+            0
+        } else {
+            1
+        }
+    }
+
+    fn matched_file_paths(&mut self, args: &SimpleArgs) -> Vec<PathBuf> {
         let mut matched_file_paths = Vec::new();
 
         for file_path in &args.paths {
@@ -55,7 +119,7 @@ impl Runner {
                 "No files found matching the patterns: {:?}\n",
                 args.paths
             ));
-            return 1;
+            return matched_file_paths;
         }
 
         // Filter out ignored files
@@ -65,62 +129,74 @@ impl Runner {
 
         if matched_file_paths.is_empty() {
             self.result.push_str("All matching files were ignored\n");
-            return 1;
         }
 
+        matched_file_paths
+    }
+
+    /// Lints a single file and appends its warnings to the result, returns false on issues, or
+    /// an error if the file couldn't be read, e.g. because it was deleted
+    fn lint_file(
+        &mut self,
+        file_path: &Path,
+        config: &mut config::Config,
+        configured_template_language: &TemplateLanguage,
+        args: &SimpleArgs,
+    ) -> std::io::Result<bool> {
         let mut success = true;
+        let content = std::fs::read_to_string(file_path)?;
 
-        let mut config;
-        if let Some(config_path) = args.config {
-            config = config::Config::from_file(&config_path, &args.registry);
-        } else {
-            let config_string = include_str!("../../default_config/default_config.toml");
-            config = config::Config::from_toml(config_string, &args.registry);
-        }
+        config.options.template_language = args
+            .template_language
+            .clone()
+            .or_else(|| {
+                TemplateLanguage::detect(
+                    file_path.to_str().unwrap_or(""),
+                    &content,
+                    &config.options,
+                )
+            })
+            .unwrap_or_else(|| configured_template_language.clone());
 
-        for file_path in matched_file_paths {
-            let content = std::fs::read_to_string(&file_path)
-                .unwrap_or_else(|_| panic!("Failed to read file: {}", file_path.display()));
+        let (ast, warnings) = if args.profile {
+            let (ast, warnings, durations) = lint_with_profiling(&content, config);
+            self.stats.add_durations(config, &durations);
+            (ast, warnings)
+        } else {
+            lint(&content, config)
+        };
 
-            let template_language = htmlsnob::template_language::TemplateLanguage::from_filename(
-                file_path.file_name().and_then(|s| s.to_str()).unwrap_or(""),
-            );
+        if args.stats {
+            self.stats
+                .add_warnings(&file_path.display().to_string(), &warnings);
+        }
 
-            config.options.template_language = template_language;
-
-            let (ast, warnings) = lint(&content, &mut config);
-
-            if !warnings.is_empty() {
-                success = false;
-                self.result.push_str(&format!("{}:\n", file_path.display()));
-                for warning in warnings {
-                    // Print the line of the file with the warning
-                    let line_number = warning.areas[0].start.line;
-                    let line_number_indentation = " ".repeat(line_number.to_string().len());
-                    let line = content.lines().nth(line_number).unwrap_or("");
-                    let mut truncated_message = warning.message.clone();
-                    if truncated_message.len() > 80 {
-                        truncated_message.truncate(80);
-                        truncated_message.push_str("...");
-                    }
-                    self.result
-                        .push_str(&format!("{}: {}\n", line_number, line));
-                    self.result.push_str(&format!(
-                        "{}  {} {}\n",
-                        line_number_indentation,
-                        range_to_string(warning.areas[0].clone()),
-                        truncated_message
-                    ));
-                }
+        if !warnings.is_empty() {
+            success = false;
+            self.warning_count += warnings.len();
+            for warning in &warnings {
+                self.result.push_str(&diagnostics::render(
+                    warning,
+                    &content,
+                    &file_path.display().to_string(),
+                    args.color,
+                ));
                 self.result.push('\n');
             }
+        }
 
-            if args.autofix {
-                let output = htmlsnob::format(&ast, &config);
-                self.result.push_str(&output);
+        if args.autofix {
+            let output = htmlsnob::format(&ast, config);
+            self.result.push_str(&output);
+
+            if args.stats {
+                let (_, remaining_warnings) = lint(&output, config);
+                self.stats.add_corrections(&warnings, &remaining_warnings);
+            }
 
-                // Overwrite the file with the fixed content
-                if let Err(e) = std::fs::write(&file_path, output) {
+            // Overwrite the file with the fixed content, unless nothing changed
+            if output != content {
+                if let Err(e) = std::fs::write(file_path, output) {
                     self.result.push_str(&format!(
                         "Failed to write to file '{}': {}\n",
                         file_path.display(),
@@ -131,13 +207,30 @@ impl Runner {
             }
         }
 
-        if success {
-            self.result.push_str("Success: No issues found\n");
-            0
-        } else {
-            1
-        }
+        Ok(success)
+    }
+}
+
+/// Loads the config given with `--config`, the `.htmlsnob.toml` in the working directory, or the
+/// default config, in that order
+pub fn load_config(config_path: &Option<String>, registry: &Registry) -> config::Config {
+    try_load_config(config_path, registry).unwrap_or_else(|error| panic!("{error}"))
+}
+
+/// Loads the config like `load_config`, returns an error if it is invalid
+pub fn try_load_config(
+    config_path: &Option<String>,
+    registry: &Registry,
+) -> Result<config::Config, config::ConfigError> {
+    if let Some(config_path) = config_path {
+        return config::Config::try_from_file(config_path, registry);
+    }
+
+    if Path::new(CONFIG_FILE_NAME).is_file() {
+        return config::Config::try_from_file(CONFIG_FILE_NAME, registry);
     }
+
+    config::Config::try_from_toml(&htmlsnob_rules::default_config_toml(), registry)
 }
 
 /// Filter out files that match any of the ignore patterns
@@ -170,8 +263,3 @@ fn filter_ignored_files<P: AsRef<Path>>(files: Vec<P>, ignore_patterns: &[String
 
     result
 }
-
-fn range_to_string(range: Area) -> String {
-    let dashes = "-".repeat(range.end.column - range.start.column);
-    format!("{:>width$}", dashes, width = range.end.column)
-}
@@ -1,11 +1,18 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use htmlsnob::template_language::TemplateLanguage;
+use htmlsnob_cli::init::init;
 use htmlsnob_cli::run;
+use htmlsnob_cli::watch::watch;
 use htmlsnob_cli::SimpleArgs;
+use std::io::IsTerminal;
 use std::process;
 
 #[derive(Parser, Debug)]
-#[command(version, about)]
+#[command(version, about, args_conflicts_with_subcommands = true)]
 pub struct Args {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     /// Input paths
     #[arg(default_value = "**/*.html")]
     pub paths: Vec<String>,
@@ -21,19 +28,92 @@ pub struct Args {
     /// Files or patterns to ignore (can be specified multiple times)
     #[arg(short, long)]
     pub ignore: Vec<String>,
+
+    /// Template language of all files, overrides the config and file extension
+    #[arg(short, long)]
+    pub template_language: Option<TemplateLanguage>,
+
+    /// Re-lint files when they change
+    #[arg(short, long)]
+    pub watch: bool,
+
+    /// Print warning counts per rule and per file, and how many were autofixed
+    #[arg(long, conflicts_with = "watch")]
+    pub stats: bool,
+
+    /// Print the time spent in each rule
+    #[arg(long, conflicts_with = "watch")]
+    pub profile: bool,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Write a .htmlsnob.toml that enforces the conventions already used in the input files
+    Init {
+        /// Input paths
+        #[arg(default_value = "**/*.html")]
+        paths: Vec<String>,
+
+        /// Files or patterns to ignore (can be specified multiple times)
+        #[arg(short, long)]
+        ignore: Vec<String>,
+
+        /// Template language of all files, overrides the file extension
+        #[arg(short, long)]
+        template_language: Option<TemplateLanguage>,
+
+        /// Overwrite an existing .htmlsnob.toml
+        #[arg(short, long)]
+        force: bool,
+    },
 }
 
 fn main() {
     let args = Args::parse();
     let registry = htmlsnob_rules::registry();
 
-    let (status, message) = run(SimpleArgs {
+    if let Some(Command::Init {
+        paths,
+        ignore,
+        template_language,
+        force,
+    }) = args.command
+    {
+        let (status, message) = init(
+            SimpleArgs {
+                paths,
+                autofix: false,
+                config: None,
+                ignore,
+                template_language,
+                color: false,
+                stats: false,
+                profile: false,
+                registry,
+            },
+            force,
+        );
+        print!("{}", message);
+        process::exit(status);
+    }
+
+    let simple_args = SimpleArgs {
         paths: args.paths,
         autofix: args.autofix,
         config: args.config,
         ignore: args.ignore,
+        template_language: args.template_language,
+        color: std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none(),
+        stats: args.stats,
+        profile: args.profile,
         registry,
-    });
+    };
+
+    if args.watch {
+        process::exit(watch(simple_args));
+    }
+
+    let (status, message) = run(simple_args);
 
     print!("{}", message);
     process::exit(status);
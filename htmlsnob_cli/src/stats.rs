@@ -0,0 +1,158 @@
+use htmlsnob::config::Config;
+use htmlsnob::Warning;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Width of the name column in the report
+const NAME_WIDTH: usize = 40;
+
+/// Counts warnings per rule and per file, and optionally the time spent in each rule
+#[derive(Default)]
+pub struct Stats {
+    rule_warnings: BTreeMap<String, usize>,
+    rule_corrections: BTreeMap<String, usize>,
+    rule_durations: BTreeMap<String, Duration>,
+    file_warnings: Vec<(String, usize)>,
+}
+
+impl Stats {
+    pub fn add_warnings(&mut self, file_path: &str, warnings: &[Warning]) {
+        for (name, count) in count_by_rule(warnings) {
+            *self.rule_warnings.entry(name).or_default() += count;
+        }
+        self.file_warnings
+            .push((file_path.to_string(), warnings.len()));
+    }
+
+    /// Counts the warnings that are gone after autofixing, per rule
+    pub fn add_corrections(&mut self, warnings: &[Warning], remaining_warnings: &[Warning]) {
+        let remaining = count_by_rule(remaining_warnings);
+        for (name, count) in count_by_rule(warnings) {
+            let corrected = count.saturating_sub(remaining.get(&name).copied().unwrap_or(0));
+            if corrected > 0 {
+                *self.rule_corrections.entry(name).or_default() += corrected;
+            }
+        }
+    }
+
+    /// Adds the durations returned by `lint_with_profiling`, which are in the order of the rules
+    pub fn add_durations(&mut self, config: &Config, durations: &[Duration]) {
+        for (index, duration) in durations.iter().enumerate() {
+            *self
+                .rule_durations
+                .entry(config.rule_name(index))
+                .or_default() += *duration;
+        }
+    }
+
+    /// Renders the warnings per rule and per file if they were counted, and the time per rule if
+    /// it was profiled
+    pub fn render(&self) -> String {
+        let mut sections = Vec::new();
+
+        if !self.file_warnings.is_empty() {
+            let mut section = String::from("Warnings per rule:\n");
+            let mut rule_warnings: Vec<_> = self.rule_warnings.iter().collect();
+            rule_warnings.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+            for (name, count) in rule_warnings {
+                section.push_str(&format!("  {:<NAME_WIDTH$} {:>6}", name, count));
+                if let Some(corrected) = self.rule_corrections.get(name) {
+                    section.push_str(&format!("  ({} autofixed)", corrected));
+                }
+                section.push('\n');
+            }
+            sections.push(section);
+
+            let mut file_warnings: Vec<_> = self
+                .file_warnings
+                .iter()
+                .filter(|(_, count)| *count > 0)
+                .collect();
+            file_warnings.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+            let mut section = format!(
+                "Warnings per file ({} of {} files):\n",
+                file_warnings.len(),
+                self.file_warnings.len()
+            );
+            for (file_path, count) in file_warnings {
+                section.push_str(&format!("  {:<NAME_WIDTH$} {:>6}\n", file_path, count));
+            }
+            sections.push(section);
+        }
+
+        if !self.rule_durations.is_empty() {
+            let mut section = String::from("Time per rule:\n");
+            let mut rule_durations: Vec<_> = self.rule_durations.iter().collect();
+            rule_durations.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+            for (name, duration) in rule_durations {
+                section.push_str(&format!(
+                    "  {:<NAME_WIDTH$} {:>10.3}ms\n",
+                    name,
+                    duration.as_secs_f64() * 1000.0
+                ));
+            }
+            sections.push(section);
+        }
+
+        let mut result = sections.join("\n");
+        result.push('\n');
+        result
+    }
+}
+
+fn count_by_rule(warnings: &[Warning]) -> BTreeMap<String, usize> {
+    let mut counts = BTreeMap::new();
+    for warning in warnings {
+        *counts.entry(warning.name.clone()).or_default() += 1;
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use htmlsnob::ast::Area;
+    use htmlsnob::WarningSeverity;
+
+    fn warning(name: &str) -> Warning {
+        Warning::from_area("", name, Area::default(), "", WarningSeverity::ERROR)
+    }
+
+    #[test]
+    fn render() {
+        let mut stats = Stats::default();
+        let warnings = [
+            warning("class_order"),
+            warning("id_unique"),
+            warning("class_order"),
+        ];
+        stats.add_warnings("index.html", &warnings);
+        stats.add_warnings("about.html", &[]);
+        stats.add_corrections(&warnings, &[warning("id_unique")]);
+
+        assert_eq!(
+            stats.render(),
+            format!(
+                "Warnings per rule:\n  {:<40} {:>6}  (2 autofixed)\n  {:<40} {:>6}\n\n\
+                 Warnings per file (1 of 2 files):\n  {:<40} {:>6}\n\n",
+                "class_order", 2, "id_unique", 1, "index.html", 3
+            )
+        );
+    }
+
+    #[test]
+    fn render_profile_only() {
+        let mut stats = Stats::default();
+        stats
+            .rule_durations
+            .insert("class_order".to_string(), Duration::from_millis(2));
+
+        assert_eq!(
+            stats.render(),
+            format!(
+                "Time per rule:\n  {:<40} {:>10.3}ms\n\n",
+                "class_order", 2.0
+            )
+        );
+    }
+}
@@ -0,0 +1,355 @@
+use crate::{try_load_config, Runner, SimpleArgs, CONFIG_FILE_NAME};
+use htmlsnob::config::{Config, ConfigError};
+use htmlsnob::template_language::TemplateLanguage;
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// Time without filesystem events before a burst of writes is considered done
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+const CLEAR_SCREEN: &str = "\x1B[2J\x1B[1;1H";
+
+/// Directories whose changes are never linted, like build output, even if they match the paths
+const IGNORED_DIRECTORIES: [&str; 3] = [".git", "node_modules", "target"];
+
+struct Report {
+    result: String,
+    warning_count: usize,
+}
+
+struct WatchState {
+    args: SimpleArgs,
+    config: Config,
+    configured_template_language: TemplateLanguage,
+    config_error: Option<String>,
+    reports: BTreeMap<PathBuf, Report>,
+}
+
+/// Lints all matching files, then re-lints files as they change until interrupted
+pub fn watch(args: SimpleArgs) -> i32 {
+    let mut state = match WatchState::new(args) {
+        Ok(state) => state,
+        Err(e) => {
+            eprintln!("Failed to load config: {}", e);
+            return 1;
+        }
+    };
+
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = match notify::recommended_watcher(sender) {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            eprintln!("Failed to start watching files: {}", e);
+            return 1;
+        }
+    };
+    if let Err(e) = watcher.watch(Path::new("."), RecursiveMode::Recursive) {
+        eprintln!("Failed to watch the working directory: {}", e);
+        return 1;
+    }
+
+    state.lint_all();
+    state.redraw();
+
+    while let Ok(event) = receiver.recv() {
+        // Irrelevant changes, like build output, don't start a burst
+        let mut changed_paths = state.relevant_paths(changed_paths(event));
+        if changed_paths.is_empty() {
+            continue;
+        }
+
+        // Collect the rest of the burst, e.g. editors that write to a temporary file and rename
+        while let Ok(event) = receiver.recv_timeout(DEBOUNCE) {
+            changed_paths.extend(state.relevant_paths(self::changed_paths(event)));
+        }
+
+        if state.handle_changes(changed_paths) {
+            state.redraw();
+        }
+    }
+
+    0
+}
+
+impl WatchState {
+    fn new(args: SimpleArgs) -> Result<Self, ConfigError> {
+        let config = try_load_config(&args.config, &args.registry)?;
+        Ok(WatchState {
+            configured_template_language: config.options.template_language.clone(),
+            config,
+            config_error: None,
+            args,
+            reports: BTreeMap::new(),
+        })
+    }
+
+    fn lint_all(&mut self) {
+        let mut runner = Runner::default();
+        let file_paths = runner.matched_file_paths(&self.args);
+
+        self.reports.clear();
+        for file_path in file_paths {
+            self.lint_file(file_path);
+        }
+    }
+
+    fn lint_file(&mut self, file_path: PathBuf) {
+        let mut runner = Runner::default();
+        let result = runner.lint_file(
+            &file_path,
+            &mut self.config,
+            &self.configured_template_language,
+            &self.args,
+        );
+
+        // Files can be deleted before they are linted, in the middle of a burst
+        if result.is_err() {
+            self.reports.remove(&file_path);
+            return;
+        }
+
+        self.reports.insert(
+            file_path,
+            Report {
+                result: runner.result,
+                warning_count: runner.warning_count,
+            },
+        );
+    }
+
+    /// Re-lints the changed files, or everything if the config changed. Returns true if any of
+    /// the changes were relevant
+    fn handle_changes(&mut self, changed_paths: Vec<PathBuf>) -> bool {
+        if changed_paths.iter().any(|path| self.is_config_file(path)) {
+            self.reload_config();
+            self.lint_all();
+            return true;
+        }
+
+        let mut relevant = false;
+        for path in changed_paths {
+            if !self.is_linted_file(&path) {
+                continue;
+            }
+
+            relevant = true;
+            if path.is_file() {
+                self.lint_file(path);
+            } else {
+                self.reports.remove(&path);
+            }
+        }
+
+        relevant
+    }
+
+    /// Keeps the previous config if the new one is invalid, instead of stopping the watch
+    fn reload_config(&mut self) {
+        match try_load_config(&self.args.config, &self.args.registry) {
+            Ok(config) => {
+                self.configured_template_language = config.options.template_language.clone();
+                self.config = config;
+                self.config_error = None;
+            }
+            Err(error) => self.config_error = Some(error.to_string()),
+        }
+    }
+
+    /// The paths that are linted or configure the linting
+    fn relevant_paths(&self, paths: Vec<PathBuf>) -> Vec<PathBuf> {
+        paths
+            .into_iter()
+            .filter(|path| self.is_config_file(path) || self.is_linted_file(path))
+            .collect()
+    }
+
+    fn is_config_file(&self, path: &Path) -> bool {
+        match &self.args.config {
+            Some(config_path) => path == strip_current_dir(Path::new(config_path)),
+            None => path == Path::new(CONFIG_FILE_NAME),
+        }
+    }
+
+    fn is_linted_file(&self, path: &Path) -> bool {
+        let is_in_ignored_directory = path.components().any(|component| {
+            IGNORED_DIRECTORIES.contains(&component.as_os_str().to_str().unwrap_or(""))
+        });
+        if is_in_ignored_directory {
+            return false;
+        }
+
+        let matches = |pattern: &String| {
+            glob::Pattern::new(pattern.trim_start_matches("./"))
+                .map(|pattern| pattern.matches_path(path))
+                .unwrap_or(false)
+        };
+
+        self.args.paths.iter().any(matches) && !self.args.ignore.iter().any(matches)
+    }
+
+    fn redraw(&self) {
+        let mut output = String::from(CLEAR_SCREEN);
+
+        if let Some(config_error) = &self.config_error {
+            output.push_str(&format!(
+                "Failed to reload config, still using the previous one: {}\n\n",
+                config_error
+            ));
+        }
+
+        let mut warning_count = 0;
+        let mut files_with_warnings = 0;
+        for report in self.reports.values() {
+            output.push_str(&report.result);
+            warning_count += report.warning_count;
+            if report.warning_count > 0 {
+                files_with_warnings += 1;
+            }
+        }
+
+        if warning_count == 0 {
+            output.push_str(&format!(
+                "Success: No issues found in {} files\n",
+                self.reports.len()
+            ));
+        } else {
+            output.push_str(&format!(
+                "{} warnings in {} of {} files\n",
+                warning_count,
+                files_with_warnings,
+                self.reports.len()
+            ));
+        }
+        output.push_str("Watching for changes, press Ctrl-C to stop\n");
+
+        print!("{}", output);
+    }
+}
+
+/// Paths touched by an event, relative to the working directory like the paths from `glob`
+fn changed_paths(event: notify::Result<notify::Event>) -> Vec<PathBuf> {
+    let Ok(event) = event else {
+        return vec![];
+    };
+
+    match event.kind {
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_) => event
+            .paths
+            .iter()
+            .map(|path| strip_current_dir(path))
+            .collect(),
+        _ => vec![],
+    }
+}
+
+fn strip_current_dir(path: &Path) -> PathBuf {
+    let path = path.strip_prefix("./").unwrap_or(path);
+
+    std::env::current_dir()
+        .ok()
+        .and_then(|current_dir| path.strip_prefix(current_dir).ok())
+        .unwrap_or(path)
+        .to_path_buf()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(dir: &Path, config: Option<String>) -> SimpleArgs {
+        SimpleArgs {
+            paths: vec![format!("{}/**/*.html", dir.display())],
+            autofix: false,
+            config,
+            ignore: vec!["**/vendor/**".to_string()],
+            template_language: None,
+            color: false,
+            stats: false,
+            profile: false,
+            registry: htmlsnob_rules::registry(),
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("htmlsnob_cli_{name}_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn linted_files() {
+        let dir = temp_dir("linted_files");
+        let state = WatchState::new(args(&dir, None)).unwrap();
+
+        assert!(state.is_linted_file(&dir.join("index.html")));
+        assert!(state.is_linted_file(&dir.join("pages/about.html")));
+        assert!(!state.is_linted_file(&dir.join("style.css")));
+        assert!(!state.is_linted_file(&dir.join("vendor/library.html")));
+        assert!(!state.is_linted_file(&dir.join("target/doc/index.html")));
+        assert_eq!(
+            state.relevant_paths(vec![dir.join("index.html"), dir.join("target/index.html")]),
+            [dir.join("index.html")]
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn changed_and_deleted_files() {
+        let dir = temp_dir("changed_and_deleted_files");
+        let path = dir.join("index.html");
+        let mut state = WatchState::new(args(&dir, None)).unwrap();
+
+        std::fs::write(&path, "<div></div>").unwrap();
+        assert!(state.handle_changes(vec![path.clone()]));
+        assert!(state.reports.contains_key(&path));
+
+        // A file that is deleted before it's linted is skipped
+        std::fs::remove_file(&path).unwrap();
+        state.lint_file(path.clone());
+        assert!(!state.reports.contains_key(&path));
+
+        assert!(!state.handle_changes(vec![dir.join("style.css")]));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn invalid_config_keeps_the_previous_config() {
+        let dir = temp_dir("invalid_config");
+        let config_path = dir.join("htmlsnob.toml");
+        std::fs::write(&config_path, "").unwrap();
+        let config = Some(config_path.display().to_string());
+        let mut state = WatchState::new(args(&dir, config)).unwrap();
+
+        std::fs::write(&config_path, "[[rules]]\nkind = \"unknown\"").unwrap();
+        assert!(state.handle_changes(vec![config_path.clone()]));
+        assert!(state
+            .config_error
+            .as_ref()
+            .is_some_and(|error| error.contains("Unknown Rule of kind: unknown")));
+
+        std::fs::write(&config_path, "").unwrap();
+        state.handle_changes(vec![config_path]);
+        assert_eq!(state.config_error, None);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn invalid_config_at_startup() {
+        let dir = temp_dir("invalid_startup_config");
+        let config_path = dir.join("htmlsnob.toml");
+        std::fs::write(&config_path, "[[rules]]\nkind = \"unknown\"").unwrap();
+        let config = Some(config_path.display().to_string());
+
+        assert!(WatchState::new(args(&dir, config))
+            .is_err_and(|error| error.to_string().contains("Unknown Rule of kind: unknown")));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
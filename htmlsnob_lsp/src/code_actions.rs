@@ -0,0 +1,200 @@
+use htmlsnob::directives::{DISABLE, DISABLE_NEXT_LINE};
+use std::collections::HashMap;
+use tower_lsp::lsp_types::{
+    CodeAction, CodeActionKind, CodeActionOrCommand, CodeActionParams, Diagnostic, NumberOrString,
+    Position, Range, TextEdit, WorkspaceEdit,
+};
+
+use crate::document::Document;
+use crate::fixes::Fixer;
+use crate::rule_config::rule_index;
+
+pub const SOURCE_FIX_ALL: &str = "source.fixAll.htmlsnob";
+
+/// Quick fixes and suppressions for the htmlsnob diagnostics in the request, and a fix all action
+pub fn code_actions(document: &Document, params: &CodeActionParams) -> Vec<CodeActionOrCommand> {
+    let only = params.context.only.as_deref();
+    let mut actions = Vec::new();
+    let mut fixer = None;
+
+    // Edits of each rule by index in the config, the autofixes of all its warnings in the file
+    let mut rule_edits: HashMap<usize, Vec<TextEdit>> = HashMap::new();
+    let mut seen_rules = Vec::new();
+
+    for diagnostic in &params.context.diagnostics {
+        let Some(rule_name) = rule_name(document, diagnostic) else {
+            continue;
+        };
+        let Some(index) = rule_index(document.config(), &rule_name) else {
+            continue;
+        };
+        let edits = rule_edits.entry(index).or_insert_with(|| {
+            fixer
+                .get_or_insert_with(|| Fixer::new(document))
+                .edits(|i| i == index)
+        });
+
+        if includes(only, &CodeActionKind::QUICKFIX) {
+            let diagnostic_edits: Vec<TextEdit> = edits
+                .iter()
+                .filter(|edit| intersects(&edit.range, &diagnostic.range))
+                .cloned()
+                .collect();
+            if !diagnostic_edits.is_empty() {
+                let mut action = action(
+                    document,
+                    format!("Fix: {}", diagnostic.message),
+                    CodeActionKind::QUICKFIX,
+                    diagnostic_edits,
+                );
+                action.diagnostics = Some(vec![diagnostic.clone()]);
+                action.is_preferred = Some(true);
+                actions.push(action);
+            }
+        }
+
+        if seen_rules.contains(&rule_name) {
+            continue;
+        }
+        seen_rules.push(rule_name.clone());
+
+        if !includes(only, &CodeActionKind::QUICKFIX) {
+            continue;
+        }
+
+        let warning_count = document
+            .warnings
+            .iter()
+            .filter(|warning| warning.name == rule_name)
+            .count();
+        if warning_count > 1 && !edits.is_empty() {
+            actions.push(action(
+                document,
+                format!("Fix all `{rule_name}` problems in this file"),
+                CodeActionKind::QUICKFIX,
+                edits.clone(),
+            ));
+        }
+
+        let line = node_start_line(document, diagnostic.range.start);
+        let indentation: String = document
+            .text
+            .lines()
+            .nth(line as usize)
+            .unwrap_or("")
+            .chars()
+            .take_while(|c| c.is_whitespace())
+            .collect();
+        actions.push(action(
+            document,
+            format!("Disable `{rule_name}` for this line"),
+            CodeActionKind::QUICKFIX,
+            vec![insert(
+                Position::new(line, 0),
+                format!("{indentation}<!-- {DISABLE_NEXT_LINE} {rule_name} -->\n"),
+            )],
+        ));
+        actions.push(action(
+            document,
+            format!("Disable `{rule_name}` for this file"),
+            CodeActionKind::QUICKFIX,
+            vec![insert(
+                Position::new(0, 0),
+                format!("<!-- {DISABLE} {rule_name} -->\n"),
+            )],
+        ));
+    }
+
+    // Only when asked for explicitly, e.g. on save, as it lints the whole document twice
+    let fix_all_kind = CodeActionKind::new(SOURCE_FIX_ALL);
+    if only.is_some() && includes(only, &fix_all_kind) {
+        let edits = fixer
+            .get_or_insert_with(|| Fixer::new(document))
+            .edits(|_| true);
+        if !edits.is_empty() {
+            actions.push(action(
+                document,
+                "Fix all auto-fixable htmlsnob problems".to_string(),
+                fix_all_kind,
+                edits,
+            ));
+        }
+    }
+
+    actions
+        .into_iter()
+        .map(CodeActionOrCommand::CodeAction)
+        .collect()
+}
+
+/// The name of the rule behind one of our diagnostics, from its code, or from the warning it was
+/// made from if the client didn't send the code back
+fn rule_name(document: &Document, diagnostic: &Diagnostic) -> Option<String> {
+    if let Some(NumberOrString::String(code)) = &diagnostic.code {
+        return Some(code.clone());
+    }
+
+    document
+        .warnings
+        .iter()
+        .find(|warning| {
+            warning.message == diagnostic.message
+                && warning
+                    .areas
+                    .iter()
+                    .any(|area| document.area_to_range(area) == diagnostic.range)
+        })
+        .map(|warning| warning.name.clone())
+}
+
+/// The line of the node that contains the position, so a directive comment is not inserted
+/// between the lines of a tag
+fn node_start_line(document: &Document, position: Position) -> u32 {
+    document
+        .ast
+        .iter()
+        .map(|node| document.area_to_range(node.area()))
+        .find(|range| range.start <= position && position <= range.end)
+        .map_or(position.line, |range| range.start.line)
+}
+
+/// Whether an action of the kind was requested, `only` may contain parent kinds like `source`
+fn includes(only: Option<&[CodeActionKind]>, kind: &CodeActionKind) -> bool {
+    only.is_none_or(|only| {
+        only.iter().any(|only| {
+            kind.as_str() == only.as_str()
+                || kind.as_str().starts_with(&format!("{}.", only.as_str()))
+        })
+    })
+}
+
+fn intersects(a: &Range, b: &Range) -> bool {
+    a.start <= b.end && b.start <= a.end
+}
+
+fn insert(position: Position, new_text: String) -> TextEdit {
+    TextEdit {
+        range: Range {
+            start: position,
+            end: position,
+        },
+        new_text,
+    }
+}
+
+fn action(
+    document: &Document,
+    title: String,
+    kind: CodeActionKind,
+    edits: Vec<TextEdit>,
+) -> CodeAction {
+    CodeAction {
+        title,
+        kind: Some(kind),
+        edit: Some(WorkspaceEdit {
+            changes: Some(HashMap::from([(document.uri.clone(), edits)])),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
@@ -0,0 +1,111 @@
+use htmlsnob::format;
+use htmlsnob::WarningSeverity;
+use tower_lsp::lsp_types::TextEdit;
+
+use crate::document::Document;
+use crate::fixes::Fixer;
+use crate::hover::rule_section;
+use crate::rule_config::{rule_index, rule_kind};
+use crate::settings::ResolvedConfig;
+use crate::text_edits;
+
+/// Applies the autofixes of all rules to the open documents and the files of the workspace
+pub const FIX_ALL_IN_WORKSPACE: &str = "htmlsnob.fixAllInWorkspace";
+/// Formats the open documents and the files of the workspace
+pub const FORMAT_WORKSPACE: &str = "htmlsnob.formatWorkspace";
+/// Returns the documentation of a rule, given its name or kind, and the uri of a document to
+/// find the config of the rule in
+pub const EXPLAIN_RULE: &str = "htmlsnob.explainRule";
+
+pub const COMMANDS: [&str; 3] = [FIX_ALL_IN_WORKSPACE, FORMAT_WORKSPACE, EXPLAIN_RULE];
+
+pub fn fix_all_edits(document: &Document) -> Vec<TextEdit> {
+    Fixer::new(document).edits(|_| true)
+}
+
+pub fn format_edits(document: &Document) -> Vec<TextEdit> {
+    let formatted_text = format(&document.ast, document.config());
+    text_edits::diff(&document.text, &formatted_text)
+}
+
+/// Markdown documentation of the rule, with its config if the config has it
+pub fn explain_rule(resolved_config: &ResolvedConfig, rule: &str) -> Option<String> {
+    let config = &resolved_config.config;
+    let index = rule_index(config, rule)
+        .or_else(|| (0..config.rules.len()).find(|index| rule_kind(config, *index) == rule));
+
+    let Some(index) = index else {
+        let documentation = htmlsnob_rules::documentation(rule)?;
+        return Some(format!(
+            "**{rule}**\n\n{documentation}\n\nNot enabled in the config"
+        ));
+    };
+
+    let severity = config.rule_values[index]
+        .get("severity")
+        .and_then(|severity| severity.as_str())
+        .map_or_else(
+            || format!("{:?}", WarningSeverity::default()).to_lowercase(),
+            String::from,
+        );
+    Some(rule_section(resolved_config, index, &severity))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use htmlsnob::config::Config;
+    use htmlsnob::template_language::TemplateLanguage;
+    use tower_lsp::lsp_types::Url;
+
+    const CONFIG: &str = r#"
+        [[rules]]
+        kind = "tag_name_casing"
+        name = "lowercase_tags"
+        style = "lower"
+        severity = "warning"
+    "#;
+
+    fn resolved_config() -> ResolvedConfig {
+        ResolvedConfig {
+            config: Config::from_toml(CONFIG, &htmlsnob_rules::registry()),
+            path: None,
+            template_language: TemplateLanguage::None,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn explain_rules_by_name_or_kind() {
+        let resolved_config = resolved_config();
+
+        let by_name = explain_rule(&resolved_config, "lowercase_tags").unwrap();
+        assert!(by_name.starts_with("**lowercase_tags** (`tag_name_casing`, warning)"));
+        assert!(by_name.contains("style = \"lower\""));
+        assert_eq!(
+            explain_rule(&resolved_config, "tag_name_casing"),
+            Some(by_name)
+        );
+
+        let unconfigured = explain_rule(&resolved_config, "class_order").unwrap();
+        assert!(unconfigured.starts_with("**class_order**"));
+        assert!(unconfigured.ends_with("Not enabled in the config"));
+
+        assert_eq!(explain_rule(&resolved_config, "unknown"), None);
+    }
+
+    #[test]
+    fn fix_and_format_edits() {
+        let uri = Url::parse("file:///tmp/index.html").unwrap();
+        let mut document =
+            Document::new(uri, "<P>\n<b>x</b></P>\n".to_string(), 1, resolved_config());
+        document.lint();
+
+        let fixes: Vec<String> = fix_all_edits(&document)
+            .into_iter()
+            .map(|edit| edit.new_text)
+            .collect();
+        assert_eq!(fixes, vec!["p", "</p>"]);
+        assert!(!format_edits(&document).is_empty());
+    }
+}
@@ -0,0 +1,402 @@
+use htmlsnob::ast::{Area, Attribute, Either, Node, Position as AstPosition};
+use htmlsnob::case_converter::CaseStyle;
+use htmlsnob::config::Config;
+use htmlsnob::rule_utils::deserialize_regex::DeserializableRegex;
+use htmlsnob_rules::tags::tag_name_casing::TagNameCaseStyle;
+use std::collections::BTreeSet;
+use tower_lsp::lsp_types::{CompletionItem, CompletionItemKind, Position};
+
+use crate::document::{position_to_offset, Document};
+use crate::elements;
+use crate::rule_config::{
+    is_literal, rule_settings, AttributeNameWhitelist, AttributeValueWhitelist, Casing,
+    ClassWhitelist, Order, TagNameWhitelist,
+};
+
+pub const TRIGGER_CHARACTERS: [&str; 4] = ["<", " ", "\"", "'"];
+
+/// What is being typed at the cursor
+#[derive(Debug, PartialEq)]
+enum Context {
+    TagName,
+    AttributeName {
+        tag: String,
+        present: Vec<String>,
+    },
+    AttributeValue {
+        tag: String,
+        attribute: String,
+        present: Vec<String>,
+    },
+}
+
+/// Suggestions for the tag name, attribute name or attribute value at the position, based on
+/// what the config allows
+pub fn completions(document: &Document, position: Position) -> Vec<CompletionItem> {
+    let offset = position_to_offset(&document.text, position);
+
+    match context(&document.text[..offset], document.config()) {
+        Some(Context::TagName) => tag_names(document),
+        Some(Context::AttributeName { tag, present }) => attribute_names(document, &tag, &present),
+        Some(Context::AttributeValue {
+            tag,
+            attribute,
+            present,
+        }) if attribute == "class" => class_names(document, &tag, &present),
+        Some(Context::AttributeValue { tag, attribute, .. }) => {
+            attribute_values(document, &tag, &attribute)
+        }
+        None => Vec::new(),
+    }
+}
+
+/// Finds the context from the node that the text ends in. Only the text before the cursor is
+/// parsed, so the tag at the cursor is the last node, missing its end bracket.
+fn context(text: &str, config: &Config) -> Option<Context> {
+    let ast = htmlsnob::parse(text, config);
+    // Elements with optional close tags are closed at the end of the text
+    let node = ast
+        .iter()
+        .rev()
+        .find(|node| !matches!(node, Node::CloseTag(close_tag) if close_tag.is_implied))?;
+
+    let lines: Vec<&str> = text.split('\n').collect();
+    let cursor = AstPosition {
+        line: lines.len() - 1,
+        column: lines[lines.len() - 1].chars().count(),
+    };
+    let ends_at_cursor = |area: &Area| area.end == cursor;
+    // Whether only `<` or `</` and the name have been typed, not the whitespace after the name
+    let is_typing_name = |area: &Area, prefix_length: usize, name: &str| {
+        area.start.line == cursor.line
+            && area.start.column + prefix_length + name.chars().count() == cursor.column
+    };
+
+    let open_tag = match node {
+        Node::OpenTag(open_tag)
+            if open_tag.is_missing_end_bracket && ends_at_cursor(&open_tag.area) =>
+        {
+            open_tag
+        }
+        Node::CloseTag(close_tag)
+            if close_tag.is_missing_end_bracket
+                && is_typing_name(&close_tag.area, 2, &close_tag.name) =>
+        {
+            return Some(Context::TagName);
+        }
+        _ => return None,
+    };
+    if open_tag.attributes.is_empty() && is_typing_name(&open_tag.area, 1, &open_tag.name) {
+        return Some(Context::TagName);
+    }
+
+    let tag = open_tag.name.clone();
+    let name_area = |attribute: &Attribute| match &attribute.name {
+        Either::Left(name) => name.area.clone(),
+        Either::Right(expression) => expression.area.clone(),
+    };
+    let mut present: Vec<String> = open_tag
+        .attributes
+        .iter()
+        .filter_map(|attribute| attribute.name.left())
+        .map(|name| name.content.clone())
+        .collect();
+
+    let Some(last) = open_tag.attributes.last() else {
+        return Some(Context::AttributeName { tag, present });
+    };
+    match &last.value {
+        Some(value) if value.end_quote.is_none() && ends_at_cursor(&value.area) => {
+            // The word being typed isn't present yet
+            let present = value
+                .words()
+                .filter(|word| !ends_at_cursor(&word.area))
+                .map(|word| word.content.clone())
+                .collect();
+            Some(Context::AttributeValue {
+                tag,
+                attribute: last.name.left()?.content.clone(),
+                present,
+            })
+        }
+        None if ends_at_cursor(&name_area(last)) => {
+            present.pop();
+            Some(Context::AttributeName { tag, present })
+        }
+        _ => Some(Context::AttributeName { tag, present }),
+    }
+}
+
+fn tag_names(document: &Document) -> Vec<CompletionItem> {
+    let config = document.config();
+    let casing: Option<Casing<TagNameCaseStyle>> = rule_settings(config, "tag_name_casing");
+
+    let names: BTreeSet<String> =
+        match rule_settings::<TagNameWhitelist>(config, "tag_name_whitelist") {
+            Some(whitelist) => whitelist
+                .tags
+                .iter()
+                .map(|tag| tag.trim().to_string())
+                .collect(),
+            None => elements::elements().keys().cloned().collect(),
+        };
+
+    names
+        .into_iter()
+        .map(|name| CompletionItem {
+            label: match &casing {
+                Some(casing) => casing.style.convert(&name),
+                None => name.clone(),
+            },
+            kind: Some(CompletionItemKind::PROPERTY),
+            detail: elements::element(&name).map(|element| element.desc.clone()),
+            ..Default::default()
+        })
+        .collect()
+}
+
+fn attribute_names(document: &Document, tag: &str, present: &[String]) -> Vec<CompletionItem> {
+    let config = document.config();
+    let casing: Option<Casing<CaseStyle>> = rule_settings(config, "attribute_name_casing_style");
+    let order: Option<Order<DeserializableRegex>> = rule_settings(config, "attributes_order");
+    let whitelist = AttributeNameWhitelist::from_config(config);
+
+    let mut names: BTreeSet<String> = match elements::element(tag) {
+        Some(element) => element.attributes.iter().cloned().collect(),
+        None => elements::global_attributes().clone(),
+    };
+    if let Some(whitelist) = &whitelist {
+        let patterns = whitelist
+            .globals
+            .iter()
+            .chain(whitelist.tags.get(tag).into_iter().flatten());
+        names.extend(
+            patterns
+                .map(|regex| regex.as_str())
+                .filter(|pattern| is_literal(pattern))
+                .map(String::from),
+        );
+    }
+
+    names
+        .into_iter()
+        .filter(|name| {
+            !present
+                .iter()
+                .any(|present| present.eq_ignore_ascii_case(name))
+        })
+        .filter(|name| {
+            whitelist
+                .as_ref()
+                .and_then(|whitelist| whitelist.allows(tag, name))
+                != Some(false)
+        })
+        .map(|name| {
+            let position = order.as_ref().and_then(|order| order.position(&name));
+            let detail = if elements::global_attributes().contains(&name) {
+                "Global attribute".to_string()
+            } else {
+                format!("Attribute of <{tag}>")
+            };
+
+            CompletionItem {
+                label: match &casing {
+                    Some(casing) => casing.style.convert(&name),
+                    None => name.clone(),
+                },
+                kind: Some(CompletionItemKind::FIELD),
+                detail: Some(detail),
+                // Attributes in the configured order first, then the rest alphabetically
+                sort_text: Some(match position {
+                    Some(position) => format!("0{position:04}{name}"),
+                    None => format!("1{name}"),
+                }),
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
+fn attribute_values(document: &Document, tag: &str, attribute: &str) -> Vec<CompletionItem> {
+    let Some(whitelist) =
+        rule_settings::<AttributeValueWhitelist>(document.config(), "attribute_value_whitelist")
+    else {
+        return Vec::new();
+    };
+    let Some(values) = whitelist.values(tag, attribute) else {
+        return Vec::new();
+    };
+
+    ordered_items(values, CompletionItemKind::ENUM_MEMBER)
+}
+
+/// The class names that the config allows or orders, except the ones already in the attribute
+fn class_names(document: &Document, tag: &str, present: &[String]) -> Vec<CompletionItem> {
+    let config = document.config();
+
+    // Only the whitelisted classes, if the classes of the tag are restricted
+    let classes = match rule_settings::<ClassWhitelist>(config, "class_whitelist")
+        .and_then(|whitelist| whitelist.tags.get(tag).cloned())
+    {
+        Some(classes) => classes,
+        None => match rule_settings::<Order<String>>(config, "class_order") {
+            Some(class_order) => class_order.order,
+            None => return Vec::new(),
+        },
+    };
+
+    let classes: Vec<String> = classes
+        .into_iter()
+        .filter(|class| !present.contains(class))
+        .collect();
+    ordered_items(&classes, CompletionItemKind::CLASS)
+}
+
+/// Items for the literal names, in the order of the config
+fn ordered_items(names: &[String], kind: CompletionItemKind) -> Vec<CompletionItem> {
+    names
+        .iter()
+        .filter(|name| !name.is_empty() && is_literal(name))
+        .enumerate()
+        .map(|(index, name)| CompletionItem {
+            label: name.clone(),
+            kind: Some(kind),
+            sort_text: Some(format!("{index:05}")),
+            ..Default::default()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::ResolvedConfig;
+    use htmlsnob::template_language::TemplateLanguage;
+    use htmlsnob_rules::registry;
+    use tower_lsp::lsp_types::Url;
+
+    const CONFIG: &str = r#"
+        [[rules]]
+        kind = "tag_name_whitelist"
+        tags = ["a", "div", "input"]
+
+        [[rules]]
+        kind = "tag_name_casing"
+        style = "upper"
+
+        [[rules]]
+        kind = "attribute_name_whitelist"
+        globals = ["^class$", "^id$"]
+
+        [rules.tags]
+        a = ["^href$", "^target$"]
+
+        [[rules]]
+        kind = "attributes_order"
+        order = ["class", "id", "href"]
+
+        [[rules]]
+        kind = "attribute_value_whitelist"
+
+        [rules.tag_attributes]
+        a.target = ["_blank", "_self", "_.+"]
+
+        [rules.global_attributes]
+        dir = ["ltr", "rtl", "auto"]
+
+        [[rules]]
+        kind = "class_order"
+        autofix = false
+        order = ["flex", "p-.*", "text-center"]
+    "#;
+
+    fn labels(text: &str) -> Vec<String> {
+        let config = Config::from_toml(CONFIG, &htmlsnob_rules::registry());
+        let resolved_config = ResolvedConfig {
+            config,
+            path: None,
+            template_language: TemplateLanguage::None,
+            error: None,
+        };
+        let uri = Url::parse("file:///tmp/index.html").unwrap();
+        let document = Document::new(uri, text.to_string(), 1, resolved_config);
+
+        let lines: Vec<&str> = text.split('\n').collect();
+        let position = Position::new(
+            lines.len() as u32 - 1,
+            lines.last().unwrap().encode_utf16().count() as u32,
+        );
+        let mut items = completions(&document, position);
+        items.sort_by(|a, b| a.sort_text.cmp(&b.sort_text).then(a.label.cmp(&b.label)));
+        items.into_iter().map(|item| item.label).collect()
+    }
+
+    fn context_of(text: &str) -> Option<Context> {
+        let config = Config::from_toml("template_language = \"handlebars\"", &registry());
+        context(text, &config)
+    }
+
+    #[test]
+    fn context_at_end_of_text() {
+        assert_eq!(context_of("<div>text"), None);
+        assert_eq!(context_of("<di"), Some(Context::TagName));
+        assert_eq!(context_of("<"), Some(Context::TagName));
+        assert_eq!(context_of("<p>\n  <"), Some(Context::TagName));
+        assert_eq!(context_of("</di"), Some(Context::TagName));
+        assert_eq!(
+            context_of("<a href=\"/\" hidden target"),
+            Some(Context::AttributeName {
+                tag: "a".to_string(),
+                present: vec!["href".to_string(), "hidden".to_string()],
+            })
+        );
+        assert_eq!(
+            context_of("<li dir=\"ltr\" "),
+            Some(Context::AttributeName {
+                tag: "li".to_string(),
+                present: vec!["dir".to_string()],
+            })
+        );
+        assert_eq!(
+            context_of("<a title='a > b' class=\"x y"),
+            Some(Context::AttributeValue {
+                tag: "a".to_string(),
+                attribute: "class".to_string(),
+                present: vec!["x".to_string()],
+            })
+        );
+        assert_eq!(context_of("<a title='a > b'>"), None);
+        assert_eq!(context_of("<!-- a"), None);
+    }
+
+    #[test]
+    fn context_outside_of_tags() {
+        assert_eq!(context_of("<!-- <a "), None);
+        assert_eq!(context_of("<script>if (a <b"), None);
+        assert_eq!(context_of("<a class=\"{{ x }}\"></a>{{ y <"), None);
+    }
+
+    #[test]
+    fn tag_names_are_whitelisted_and_cased() {
+        assert_eq!(labels("<p>\n  <"), vec!["A", "DIV", "INPUT"]);
+    }
+
+    #[test]
+    fn attribute_names_are_allowed_and_ordered() {
+        assert_eq!(labels("<a id=\"x\" "), vec!["class", "href", "target"]);
+        assert!(labels("<div ").contains(&"hidden".to_string()));
+    }
+
+    #[test]
+    fn attribute_values_are_whitelisted() {
+        assert_eq!(labels("<a target=\""), vec!["_blank", "_self"]);
+        assert_eq!(labels("<div dir="), vec!["ltr", "rtl", "auto"]);
+        assert_eq!(labels("<div title=\""), Vec::<String>::new());
+    }
+
+    #[test]
+    fn class_names_from_class_order() {
+        assert_eq!(labels("<div class=\"flex "), vec!["text-center"]);
+        assert_eq!(labels("<div class=\"fl"), vec!["flex", "text-center"]);
+    }
+}
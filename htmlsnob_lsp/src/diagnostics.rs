@@ -0,0 +1,256 @@
+use htmlsnob::{Warning, WarningSeverity};
+use tower_lsp::lsp_types::{
+    CodeDescription, Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, DiagnosticTag,
+    Location, NumberOrString, Url,
+};
+
+use crate::document::Document;
+use crate::rule_config::{rule_index, rule_kind};
+
+pub const SOURCE: &str = "htmlsnob-lsp";
+
+/// Elements that are obsolete in the HTML specification
+const OBSOLETE_ELEMENTS: [&str; 29] = [
+    "acronym",
+    "applet",
+    "basefont",
+    "bgsound",
+    "big",
+    "blink",
+    "center",
+    "dir",
+    "font",
+    "frame",
+    "frameset",
+    "isindex",
+    "keygen",
+    "listing",
+    "marquee",
+    "menuitem",
+    "multicol",
+    "nextid",
+    "nobr",
+    "noembed",
+    "noframes",
+    "param",
+    "plaintext",
+    "rb",
+    "rtc",
+    "spacer",
+    "strike",
+    "tt",
+    "xmp",
+];
+
+/// Presentational and other attributes that are obsolete on every element in the HTML
+/// specification
+const OBSOLETE_ATTRIBUTES: [&str; 30] = [
+    "align",
+    "alink",
+    "archive",
+    "axis",
+    "background",
+    "bgcolor",
+    "cellpadding",
+    "cellspacing",
+    "char",
+    "charoff",
+    "classid",
+    "clear",
+    "codebase",
+    "codetype",
+    "compact",
+    "datafld",
+    "dataformatas",
+    "datasrc",
+    "declare",
+    "frameborder",
+    "hspace",
+    "longdesc",
+    "lowsrc",
+    "marginheight",
+    "marginwidth",
+    "nowrap",
+    "rev",
+    "scrolling",
+    "valign",
+    "vspace",
+];
+
+/// The diagnostics of the warnings of the last lint, and of the config, if it's invalid
+pub fn diagnostics(document: &Document) -> Vec<Diagnostic> {
+    document
+        .config
+        .document_diagnostic()
+        .into_iter()
+        .chain(
+            document
+                .warnings
+                .iter()
+                .map(|warning| diagnostic(document, warning)),
+        )
+        .collect()
+}
+
+/// A diagnostic at the first area of the warning, the other areas are related information
+fn diagnostic(document: &Document, warning: &Warning) -> Diagnostic {
+    let kind = rule_index(document.config(), &warning.name)
+        .map(|index| rule_kind(document.config(), index))
+        .unwrap_or(&warning.name);
+
+    let mut areas = warning.areas.iter();
+    let range = areas
+        .next()
+        .map(|area| document.area_to_range(area))
+        .unwrap_or_default();
+    let related_information: Vec<DiagnosticRelatedInformation> = areas
+        .map(|area| DiagnosticRelatedInformation {
+            location: Location {
+                uri: document.uri.clone(),
+                range: document.area_to_range(area),
+            },
+            message: warning.message.clone(),
+        })
+        .collect();
+
+    let flagged_name = warning
+        .areas
+        .first()
+        .map(|area| name(document.area_text(area)))
+        .unwrap_or_default();
+    let tag = match kind {
+        "duplicate_attribute_names_disallowed" | "duplicate_classes_disallowed" => {
+            Some(DiagnosticTag::UNNECESSARY)
+        }
+        "tag_name_blacklist" | "tag_name_whitelist"
+            if OBSOLETE_ELEMENTS.contains(&flagged_name.as_str()) =>
+        {
+            Some(DiagnosticTag::DEPRECATED)
+        }
+        "attribute_name_blacklist" | "attribute_name_whitelist"
+            if OBSOLETE_ATTRIBUTES.contains(&flagged_name.as_str()) =>
+        {
+            Some(DiagnosticTag::DEPRECATED)
+        }
+        _ => None,
+    };
+
+    Diagnostic {
+        range,
+        severity: Some(severity(&warning.severity)),
+        code: Some(NumberOrString::String(warning.name.clone())),
+        code_description: documentation_url(kind).map(|href| CodeDescription { href }),
+        source: Some(SOURCE.to_string()),
+        message: warning.message.clone(),
+        related_information: (!related_information.is_empty()).then_some(related_information),
+        tags: tag.map(|tag| vec![tag]),
+        data: None,
+    }
+}
+
+/// The lowercase tag or attribute name at the start of the text of an area
+fn name(text: &str) -> String {
+    text.trim_start_matches(['<', '/'])
+        .trim_start()
+        .split(|c: char| c.is_whitespace() || c == '=' || c == '>' || c == '/')
+        .next()
+        .unwrap_or("")
+        .to_lowercase()
+}
+
+/// The source of the rule in the repository, where its documentation and tests are
+fn documentation_url(kind: &str) -> Option<Url> {
+    let path = htmlsnob_rules::source_path(kind)?;
+    Url::parse(&format!(
+        "{}/blob/main/{path}",
+        env!("CARGO_PKG_REPOSITORY")
+    ))
+    .ok()
+}
+
+pub fn severity(warning_severity: &WarningSeverity) -> DiagnosticSeverity {
+    match warning_severity {
+        WarningSeverity::ERROR => DiagnosticSeverity::ERROR,
+        WarningSeverity::WARNING => DiagnosticSeverity::WARNING,
+        WarningSeverity::INFORMATION => DiagnosticSeverity::INFORMATION,
+        WarningSeverity::HINT => DiagnosticSeverity::HINT,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::ResolvedConfig;
+    use htmlsnob::config::Config;
+    use htmlsnob::template_language::TemplateLanguage;
+    use tower_lsp::lsp_types::{Position, Range};
+
+    const CONFIG: &str = r#"
+        [[rules]]
+        kind = "tag_name_blacklist"
+        tags = ["center"]
+
+        [[rules]]
+        kind = "attribute_name_blacklist"
+
+        [rules.tags]
+        div = ["bgcolor", "onclick"]
+
+        [[rules]]
+        kind = "duplicate_classes_disallowed"
+    "#;
+
+    fn diagnostics_of(text: &str) -> Vec<Diagnostic> {
+        let resolved_config = ResolvedConfig {
+            config: Config::from_toml(CONFIG, &htmlsnob_rules::registry()),
+            path: None,
+            template_language: TemplateLanguage::None,
+            error: None,
+        };
+        let uri = Url::parse("file:///tmp/index.html").unwrap();
+        let mut document = Document::new(uri, text.to_string(), 1, resolved_config);
+        document.lint();
+        diagnostics(&document)
+    }
+
+    #[test]
+    fn one_diagnostic_with_related_information() {
+        let diagnostics = diagnostics_of("<center>\n  text\n</center>");
+        assert_eq!(diagnostics.len(), 1);
+
+        let diagnostic = &diagnostics[0];
+        assert_eq!(
+            diagnostic.code,
+            Some(NumberOrString::String("tag_name_blacklist".to_string()))
+        );
+        assert_eq!(
+            diagnostic.code_description.as_ref().unwrap().href.as_str(),
+            "https://github.com/mollerhoj/htmlsnob/blob/main/htmlsnob_rules/src/tags/tag_name_blacklist.rs"
+        );
+        assert_eq!(
+            diagnostic.range,
+            Range::new(Position::new(0, 0), Position::new(0, 8))
+        );
+        let related_information = diagnostic.related_information.as_ref().unwrap();
+        assert_eq!(
+            related_information[0].location.range,
+            Range::new(Position::new(2, 0), Position::new(2, 9))
+        );
+        assert_eq!(diagnostic.tags, Some(vec![DiagnosticTag::DEPRECATED]));
+    }
+
+    #[test]
+    fn tags_of_obsolete_and_unnecessary_code() {
+        let diagnostics =
+            diagnostics_of("<div bgcolor=\"red\" onclick=\"f()\"></div><p class=\"a b a\"></p>");
+        let tags: Vec<(String, Option<Vec<DiagnosticTag>>)> = diagnostics
+            .into_iter()
+            .map(|diagnostic| (diagnostic.message, diagnostic.tags))
+            .collect();
+
+        assert_eq!(tags.len(), 3);
+        assert_eq!(tags[0].1, Some(vec![DiagnosticTag::DEPRECATED]));
+        assert_eq!(tags[1].1, None);
+        assert_eq!(tags[2].1, Some(vec![DiagnosticTag::UNNECESSARY]));
+    }
+}
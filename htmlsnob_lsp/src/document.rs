@@ -0,0 +1,252 @@
+use htmlsnob::ast::{Area, Node, OpenTag};
+use htmlsnob::config::Config;
+use htmlsnob::template_language::TemplateLanguage;
+use htmlsnob::{lint, Warning};
+use tower_lsp::lsp_types::{Position, Range, TextDocumentContentChangeEvent, Url};
+
+use crate::settings::ResolvedConfig;
+
+/// An open text document, with the result of the last lint of its content
+#[derive(Debug)]
+pub struct Document {
+    pub uri: Url,
+    pub text: String,
+    pub version: i32,
+    pub config: ResolvedConfig,
+    pub ast: Vec<Node>,
+    pub warnings: Vec<Warning>,
+    /// The version the ast and warnings were produced from
+    pub linted_version: Option<i32>,
+}
+
+impl Document {
+    pub fn new(uri: Url, text: String, version: i32, config: ResolvedConfig) -> Self {
+        Self {
+            uri,
+            text,
+            version,
+            config,
+            ast: Vec::new(),
+            warnings: Vec::new(),
+            linted_version: None,
+        }
+    }
+
+    /// Applies a full or incremental change from `textDocument/didChange`
+    pub fn apply_change(&mut self, change: TextDocumentContentChangeEvent) {
+        match change.range {
+            Some(range) => {
+                let start = position_to_offset(&self.text, range.start);
+                let end = position_to_offset(&self.text, range.end).max(start);
+                self.text.replace_range(start..end, &change.text);
+            }
+            None => self.text = change.text,
+        }
+    }
+
+    /// Lints the current text, unless it has already been linted
+    pub fn lint(&mut self) {
+        if self.linted_version == Some(self.version) {
+            return;
+        }
+
+        let path = self.config.relative_path(&self.uri);
+        let config = &mut self.config.config;
+        config.options.template_language =
+            TemplateLanguage::detect(&path, &self.text, &config.options)
+                .unwrap_or_else(|| self.config.template_language.clone());
+
+        let (ast, warnings) = lint(&self.text, config);
+        self.ast = ast;
+        self.warnings = warnings;
+        self.linted_version = Some(self.version);
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config.config
+    }
+
+    pub fn area_to_range(&self, area: &Area) -> Range {
+        Range {
+            start: char_position_to_position(&self.text, area.start.line, area.start.column),
+            end: char_position_to_position(&self.text, area.end.line, area.end.column),
+        }
+    }
+
+    /// The range from the start of the open tag to the end of the close tag of an element
+    pub fn element_range(&self, open_tag: &OpenTag) -> Range {
+        let end = match open_tag.close_tag_index.map(|index| &self.ast[index]) {
+            Some(Node::CloseTag(close_tag)) => &close_tag.area.end,
+            _ => &open_tag.area.end,
+        };
+
+        Range {
+            start: self.area_to_range(&open_tag.area).start,
+            end: char_position_to_position(&self.text, end.line, end.column),
+        }
+    }
+
+    /// The text of the area, as written in the document, fixes may have changed the ast
+    pub fn area_text(&self, area: &Area) -> &str {
+        let start = char_position_to_offset(&self.text, area.start.line, area.start.column);
+        let end = char_position_to_offset(&self.text, area.end.line, area.end.column);
+        &self.text[start..end.max(start)]
+    }
+
+    /// The range of the name of an open or close tag
+    pub fn tag_name_range(&self, node: &Node) -> Option<Range> {
+        match node {
+            Node::OpenTag(_) => {}
+            // Implied close tags aren't in the document
+            Node::CloseTag(close_tag) if !close_tag.is_implied => {}
+            _ => return None,
+        }
+
+        let area = node.area();
+        let start = char_position_to_offset(&self.text, area.start.line, area.start.column);
+        let tag = self.area_text(area);
+        let name_start = tag
+            .find(|c: char| c != '<' && c != '/' && !c.is_whitespace())
+            .unwrap_or(tag.len());
+        let name_length = tag[name_start..]
+            .find(|c: char| c.is_whitespace() || c == '>' || c == '/' || c == '<')
+            .unwrap_or(tag.len() - name_start);
+
+        Some(Range {
+            start: offset_to_position(&self.text, start + name_start),
+            end: offset_to_position(&self.text, start + name_start + name_length),
+        })
+    }
+
+    /// The innermost element that contains the whole range
+    pub fn enclosing_element(&self, range: Range) -> Option<&OpenTag> {
+        self.ast
+            .iter()
+            .rev()
+            .filter_map(|node| match node {
+                Node::OpenTag(open_tag) => Some(open_tag),
+                _ => None,
+            })
+            .find(|open_tag| {
+                let element_range = self.element_range(open_tag);
+                element_range.start <= range.start && range.end <= element_range.end
+            })
+    }
+}
+
+/// Converts an LSP position, where the character is counted in UTF-16 code units, to a byte offset
+pub fn position_to_offset(text: &str, position: Position) -> usize {
+    let mut line_start = 0;
+    for _ in 0..position.line {
+        match text[line_start..].find('\n') {
+            Some(index) => line_start += index + 1,
+            None => return text.len(),
+        }
+    }
+
+    let mut utf16_column = 0;
+    for (index, char) in text[line_start..].char_indices() {
+        if utf16_column >= position.character as usize || char == '\n' {
+            return line_start + index;
+        }
+        utf16_column += char.len_utf16();
+    }
+
+    text.len()
+}
+
+/// Converts a line and a column counted in chars, as used by the ast, to an LSP position
+pub fn char_position_to_position(text: &str, line: usize, column: usize) -> Position {
+    let character = text
+        .split('\n')
+        .nth(line)
+        .map(|line| line.chars().take(column).map(char::len_utf16).sum())
+        .unwrap_or(column);
+
+    Position {
+        line: u32::try_from(line).unwrap_or(u32::MAX),
+        character: u32::try_from(character).unwrap_or(u32::MAX),
+    }
+}
+
+/// Converts a line and a column counted in chars, as used by the ast, to a byte offset
+pub fn char_position_to_offset(text: &str, line: usize, column: usize) -> usize {
+    let line_start = match line {
+        0 => 0,
+        _ => match text.match_indices('\n').nth(line - 1) {
+            Some((index, _)) => index + 1,
+            None => return text.len(),
+        },
+    };
+
+    text[line_start..]
+        .char_indices()
+        .nth(column)
+        .map_or(text.len(), |(index, _)| line_start + index)
+}
+
+/// Converts a byte offset to an LSP position
+pub fn offset_to_position(text: &str, offset: usize) -> Position {
+    let before = &text[..offset.min(text.len())];
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+
+    Position {
+        line: u32::try_from(before.matches('\n').count()).unwrap_or(u32::MAX),
+        character: u32::try_from(before[line_start..].encode_utf16().count()).unwrap_or(u32::MAX),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(range: Option<Range>, text: &str) -> TextDocumentContentChangeEvent {
+        TextDocumentContentChangeEvent {
+            range,
+            range_length: None,
+            text: text.to_string(),
+        }
+    }
+
+    fn range(start: (u32, u32), end: (u32, u32)) -> Range {
+        Range {
+            start: Position::new(start.0, start.1),
+            end: Position::new(end.0, end.1),
+        }
+    }
+
+    #[test]
+    fn incremental_changes() {
+        let uri = Url::parse("file:///tmp/index.html").unwrap();
+        let mut document = Document::new(
+            uri,
+            "<p>\n  ø😀x\n</p>".to_string(),
+            1,
+            ResolvedConfig::default(),
+        );
+
+        // The emoji is two UTF-16 code units wide
+        document.apply_change(change(Some(range((1, 3), (1, 5))), "y"));
+        assert_eq!(document.text, "<p>\n  øyx\n</p>");
+
+        document.apply_change(change(Some(range((0, 0), (0, 3))), "<div>"));
+        assert_eq!(document.text, "<div>\n  øyx\n</p>");
+
+        document.apply_change(change(Some(range((2, 2), (2, 3))), "div"));
+        assert_eq!(document.text, "<div>\n  øyx\n</div>");
+
+        document.apply_change(change(None, "<p></p>"));
+        assert_eq!(document.text, "<p></p>");
+    }
+
+    #[test]
+    fn position_conversions() {
+        let text = "a\n😀b\n";
+        assert_eq!(position_to_offset(text, Position::new(1, 2)), 6);
+        assert_eq!(offset_to_position(text, 6), Position::new(1, 2));
+        assert_eq!(char_position_to_position(text, 1, 1), Position::new(1, 2));
+        assert_eq!(char_position_to_offset(text, 1, 1), 6);
+        assert_eq!(char_position_to_offset(text, 2, 0), text.len());
+        assert_eq!(position_to_offset(text, Position::new(9, 0)), text.len());
+    }
+}
@@ -0,0 +1,87 @@
+use serde::Deserialize;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::OnceLock;
+
+/// An HTML element from the specification data in `htmlsnob_scripts/data/elements.json`
+#[derive(Debug, Deserialize)]
+pub struct Element {
+    #[serde(default)]
+    pub attributes: Vec<String>,
+    #[serde(default)]
+    pub categories: Vec<String>,
+    #[serde(default)]
+    pub desc: String,
+}
+
+pub fn elements() -> &'static HashMap<String, Element> {
+    static ELEMENTS: OnceLock<HashMap<String, Element>> = OnceLock::new();
+
+    ELEMENTS.get_or_init(|| {
+        let mut data: HashMap<String, serde_json::Value> =
+            serde_json::from_str(include_str!("../../htmlsnob_scripts/data/elements.json"))
+                .expect("Failed to parse elements.json");
+        data.remove("__META__");
+
+        data.into_iter()
+            .map(|(name, value)| {
+                let element = serde_json::from_value(value).expect("Failed to parse element");
+                (name, element)
+            })
+            .collect()
+    })
+}
+
+pub fn element(name: &str) -> Option<&'static Element> {
+    elements().get(&name.to_lowercase())
+}
+
+/// The attributes that every HTML element has, i.e. the global attributes. The attributes of the
+/// foreign `svg` and `math` elements are given as `per [SVG]` in the data, so they are skipped.
+pub fn global_attributes() -> &'static BTreeSet<String> {
+    static GLOBAL_ATTRIBUTES: OnceLock<BTreeSet<String>> = OnceLock::new();
+
+    GLOBAL_ATTRIBUTES.get_or_init(|| {
+        let mut elements = elements().values().filter(|element| {
+            !element
+                .attributes
+                .iter()
+                .any(|name| name.starts_with("per "))
+        });
+        let first: BTreeSet<String> = elements
+            .next()
+            .map(|element| element.attributes.iter().cloned().collect())
+            .unwrap_or_default();
+
+        elements.fold(first, |globals, element| {
+            globals
+                .into_iter()
+                .filter(|attribute| element.attributes.contains(attribute))
+                .collect()
+        })
+    })
+}
+
+impl Element {
+    /// The attributes of the element that are not global attributes
+    pub fn own_attributes(&self) -> Vec<&str> {
+        self.attributes
+            .iter()
+            .filter(|attribute| !global_attributes().contains(*attribute))
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn element_data() {
+        let a = element("A").unwrap();
+        assert!(a.desc.contains("Hyperlink"));
+        assert!(a.own_attributes().contains(&"href"));
+        assert!(!a.own_attributes().contains(&"class"));
+        assert!(global_attributes().contains("class"));
+    }
+}
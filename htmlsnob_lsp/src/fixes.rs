@@ -0,0 +1,305 @@
+use htmlsnob::ast::{Attribute, AttributeValue, Either, Node, OpenTag, Position};
+use htmlsnob::config::Config;
+use htmlsnob::formatter::{print_attribute, print_node};
+use htmlsnob::lint;
+use tower_lsp::lsp_types::{Range, TextEdit};
+
+use crate::document::{char_position_to_offset, offset_to_position, Document};
+
+/// Computes the edits of autofixes, by linting the document once with autofix disabled for all
+/// rules, and once with autofix enabled for some rules, and comparing the nodes. Only the
+/// changed parts of nodes are replaced, so the layout of the document is kept.
+pub struct Fixer<'a> {
+    document: &'a Document,
+    unfixed_ast: Vec<Node>,
+}
+
+impl<'a> Fixer<'a> {
+    pub fn new(document: &'a Document) -> Self {
+        let mut config = config_with_autofix(document.config(), |_| false);
+        let (unfixed_ast, _) = lint(&document.text, &mut config);
+
+        Self {
+            document,
+            unfixed_ast,
+        }
+    }
+
+    /// The edits of the rules, by index in the config, for which `autofix` returns true
+    pub fn edits(&self, autofix: impl Fn(usize) -> bool) -> Vec<TextEdit> {
+        let mut config = config_with_autofix(self.document.config(), autofix);
+        let (fixed_ast, _) = lint(&self.document.text, &mut config);
+
+        // Fixes don't add or remove nodes, if they did, the nodes can't be compared
+        if fixed_ast.len() != self.unfixed_ast.len() {
+            return Vec::new();
+        }
+
+        let mut edits = Edits {
+            text: &self.document.text,
+            edits: Vec::new(),
+        };
+        for (unfixed, fixed) in self.unfixed_ast.iter().zip(&fixed_ast) {
+            if unfixed != fixed {
+                edits.node(unfixed, fixed);
+            }
+        }
+        edits.edits
+    }
+}
+
+fn config_with_autofix(config: &Config, autofix: impl Fn(usize) -> bool) -> Config {
+    let rule_values = config
+        .rule_values
+        .iter()
+        .enumerate()
+        .map(|(index, rule_value)| {
+            let mut rule_value = rule_value.clone();
+            if let Some(table) = rule_value.as_table_mut() {
+                table.insert("autofix".to_string(), toml::Value::Boolean(autofix(index)));
+            }
+            rule_value
+        })
+        .collect();
+
+    Config::from_rule_values(
+        rule_values,
+        config.options.clone(),
+        &htmlsnob_rules::registry(),
+    )
+}
+
+struct Edits<'a> {
+    text: &'a str,
+    edits: Vec<TextEdit>,
+}
+
+impl Edits<'_> {
+    fn offset(&self, position: &Position) -> usize {
+        char_position_to_offset(self.text, position.line, position.column)
+    }
+
+    fn replace(&mut self, start: usize, end: usize, new_text: String) {
+        self.edits.push(TextEdit {
+            range: Range {
+                start: offset_to_position(self.text, start),
+                end: offset_to_position(self.text, end),
+            },
+            new_text,
+        });
+    }
+
+    fn node(&mut self, unfixed: &Node, fixed: &Node) {
+        match (unfixed, fixed) {
+            (Node::OpenTag(unfixed), Node::OpenTag(fixed)) => self.open_tag(unfixed, fixed),
+            // The content of text nodes is trimmed, so it can't replace the original
+            (Node::Text(_), _) => {}
+            _ => {
+                let area = unfixed.area();
+                let (start, end) = (self.offset(&area.start), self.offset(&area.end));
+                self.replace(start, end, print_node(fixed));
+            }
+        }
+    }
+
+    fn open_tag(&mut self, unfixed: &OpenTag, fixed: &OpenTag) {
+        let start = self.offset(&unfixed.area.start);
+        let end = self.offset(&unfixed.area.end);
+
+        if unfixed.attributes.len() != fixed.attributes.len() {
+            self.replace(start, end, print_node(&Node::OpenTag(fixed.clone())));
+            return;
+        }
+
+        if unfixed.name != fixed.name {
+            let name_start = start
+                + 1
+                + self.text[start + 1..]
+                    .find(|c: char| !c.is_whitespace())
+                    .unwrap_or(0);
+            self.replace(
+                name_start,
+                name_start + unfixed.name.len(),
+                fixed.name.clone(),
+            );
+        }
+
+        for (unfixed, fixed) in unfixed.attributes.iter().zip(&fixed.attributes) {
+            if unfixed != fixed {
+                self.attribute(unfixed, fixed);
+            }
+        }
+
+        let ending = |open_tag: &OpenTag| (open_tag.self_closed, open_tag.is_missing_end_bracket);
+        if ending(unfixed) != ending(fixed) {
+            let ending_start = if unfixed.self_closed {
+                self.text[..end - 2].trim_end().len()
+            } else if unfixed.is_missing_end_bracket {
+                end
+            } else {
+                end - 1
+            };
+            let new_ending = if fixed.self_closed {
+                " />"
+            } else if fixed.is_missing_end_bracket {
+                ""
+            } else {
+                ">"
+            };
+            self.replace(ending_start, end, new_ending.to_string());
+        }
+    }
+
+    fn attribute(&mut self, unfixed: &Attribute, fixed: &Attribute) {
+        let same_shape = match (&unfixed.value, &fixed.value) {
+            (Some(unfixed), Some(fixed)) => unfixed.parts.len() == fixed.parts.len(),
+            (None, None) => true,
+            _ => false,
+        } && unfixed.name.is_left() == fixed.name.is_left();
+
+        if !same_shape {
+            // Parts are printed separated by spaces, which is only right for parts that were
+            // separated by whitespace, and not for template expressions
+            let has_template_parts = fixed
+                .value
+                .as_ref()
+                .is_some_and(|value| value.parts.iter().any(Either::is_right));
+            if !has_template_parts {
+                let start = self.offset(&unfixed.area.start);
+                let end = self.offset(&unfixed.area.end);
+                self.replace(start, end, print_attribute(fixed));
+            }
+            return;
+        }
+
+        if let (Either::Left(unfixed_name), Either::Left(fixed_name)) = (&unfixed.name, &fixed.name)
+        {
+            if unfixed_name.content != fixed_name.content {
+                let start = self.offset(&unfixed_name.area.start);
+                let end = self.offset(&unfixed_name.area.end);
+                self.replace(start, end, fixed_name.content.clone());
+            }
+        }
+
+        if let (Some(unfixed), Some(fixed)) = (&unfixed.value, &fixed.value) {
+            self.attribute_value(unfixed, fixed);
+        }
+    }
+
+    fn attribute_value(&mut self, unfixed: &AttributeValue, fixed: &AttributeValue) {
+        let quote = |quote: Option<char>| quote.map(String::from).unwrap_or_default();
+
+        if unfixed.start_quote != fixed.start_quote {
+            let start = self.offset(&unfixed.area.start);
+            let end = start + unfixed.start_quote.map_or(0, char::len_utf8);
+            self.replace(start, end, quote(fixed.start_quote));
+        }
+
+        for (unfixed, fixed) in unfixed.parts.iter().zip(&fixed.parts) {
+            let (area, unfixed_content) = match unfixed {
+                Either::Left(string_area) => (&string_area.area, &string_area.content),
+                Either::Right(expression) => (&expression.area, &expression.content),
+            };
+            let fixed_content = match fixed {
+                Either::Left(string_area) => &string_area.content,
+                Either::Right(expression) => &expression.content,
+            };
+
+            if unfixed_content != fixed_content {
+                let (start, end) = (self.offset(&area.start), self.offset(&area.end));
+                self.replace(start, end, fixed_content.clone());
+            }
+        }
+
+        if unfixed.end_quote != fixed.end_quote {
+            let end = self.offset(&unfixed.area.end);
+            let start = end - unfixed.end_quote.map_or(0, char::len_utf8);
+            self.replace(start, end, quote(fixed.end_quote));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::ResolvedConfig;
+    use htmlsnob::template_language::TemplateLanguage;
+    use tower_lsp::lsp_types::Url;
+
+    const CONFIG: &str = r#"
+        [[rules]]
+        kind = "tag_name_casing"
+        style = "lower"
+
+        [[rules]]
+        kind = "attribute_value_quote_style"
+        style = "double"
+        autofix = false
+
+        [[rules]]
+        kind = "self_closing_tag_style"
+        style = "closed"
+        tags = ["br"]
+
+        [[rules]]
+        kind = "boolean_attribute_style"
+        style = "no_value"
+        attributes = ["checked"]
+
+        [[rules]]
+        kind = "optional_tag_style"
+        style = "explicit"
+        tags = ["li"]
+    "#;
+
+    fn fix(text: &str, autofix: impl Fn(usize) -> bool) -> String {
+        let config = Config::from_toml(CONFIG, &htmlsnob_rules::registry());
+        let resolved_config = ResolvedConfig {
+            config,
+            path: None,
+            template_language: TemplateLanguage::None,
+            error: None,
+        };
+        let uri = Url::parse("file:///tmp/index.html").unwrap();
+        let mut document = Document::new(uri, text.to_string(), 1, resolved_config);
+        document.lint();
+
+        let mut edits = Fixer::new(&document).edits(autofix);
+        edits.sort_by_key(|edit| edit.range.start);
+
+        let mut result = text.to_string();
+        for edit in edits.iter().rev() {
+            let start = crate::document::position_to_offset(text, edit.range.start);
+            let end = crate::document::position_to_offset(text, edit.range.end);
+            result.replace_range(start..end, &edit.new_text);
+        }
+        result
+    }
+
+    #[test]
+    fn fixes_keep_the_layout() {
+        let text = "<DIV\n  class='a  b'\n  checked=\"checked\">\n  <br>\n</DIV>\n";
+
+        assert_eq!(
+            fix(text, |_| true),
+            "<div\n  class=\"a  b\"\n  checked>\n  <br />\n</div>\n"
+        );
+    }
+
+    #[test]
+    fn fixes_of_one_rule() {
+        let text = "<DIV class='a'><br></DIV>";
+
+        assert_eq!(fix(text, |index| index == 1), "<DIV class=\"a\"><br></DIV>");
+    }
+
+    #[test]
+    fn fixes_insert_implied_close_tags_after_the_content() {
+        let text = "<ul>\n  <li>One\n  <li>Two\n</ul>\n";
+
+        assert_eq!(
+            fix(text, |index| index == 4),
+            "<ul>\n  <li>One</li>\n  <li>Two</li>\n</ul>\n"
+        );
+    }
+}
@@ -0,0 +1,255 @@
+use htmlsnob::ast::{Either, Node};
+use htmlsnob::Warning;
+use tower_lsp::lsp_types::{Hover, HoverContents, MarkupContent, MarkupKind, Position, Range};
+
+use crate::document::Document;
+use crate::elements;
+use crate::rule_config::{rule_index, rule_kind, AttributeNameWhitelist};
+use crate::settings::ResolvedConfig;
+
+/// Rule configs longer than this are cut off in the hover
+const MAX_CONFIG_LINES: usize = 15;
+
+/// Documentation of the warnings at the position, and of the tag or attribute name under it
+pub fn hover(document: &Document, position: Position) -> Option<Hover> {
+    let mut sections: Vec<String> = document
+        .warnings
+        .iter()
+        .filter(|warning| {
+            warning
+                .areas
+                .iter()
+                .any(|area| contains(&document.area_to_range(area), position))
+        })
+        .map(|warning| warning_section(document, warning))
+        .collect();
+
+    sections.extend(html_section(document, position));
+
+    if sections.is_empty() {
+        return None;
+    }
+
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: sections.join("\n\n---\n\n"),
+        }),
+        range: None,
+    })
+}
+
+fn warning_section(document: &Document, warning: &Warning) -> String {
+    let Some(index) = rule_index(document.config(), &warning.name) else {
+        return format!("**{}**", warning.name);
+    };
+
+    let severity = format!("{:?}", warning.severity).to_lowercase();
+    rule_section(&document.config, index, &severity)
+}
+
+/// The documentation of the rule at the index of the config, and how it is configured
+pub fn rule_section(resolved_config: &ResolvedConfig, index: usize, severity: &str) -> String {
+    let config = &resolved_config.config;
+    let kind = rule_kind(config, index);
+
+    let mut section = format!("**{}** (`{kind}`, {severity})", config.rule_name(index));
+    if let Some(documentation) = htmlsnob_rules::documentation(kind) {
+        section.push_str(&format!("\n\n{documentation}"));
+    }
+
+    // Wrapped in a `rules` array, so nested tables are printed as `[rules.tags]`
+    let mut table = toml::Table::new();
+    table.insert(
+        "rules".to_string(),
+        toml::Value::Array(vec![config.rule_values[index].clone()]),
+    );
+    let rule_config = toml::to_string(&table).unwrap_or_default();
+    let mut lines: Vec<&str> = rule_config.lines().collect();
+    if lines.len() > MAX_CONFIG_LINES {
+        lines.truncate(MAX_CONFIG_LINES);
+        lines.push("# ...");
+    }
+
+    let source = match &resolved_config.path {
+        Some(path) => format!("`{}`", path.display()),
+        None => "the default config".to_string(),
+    };
+    section.push_str(&format!(
+        "\n\nConfigured in {source}:\n```toml\n{}\n```",
+        lines.join("\n")
+    ));
+
+    section
+}
+
+/// Documentation of the tag or attribute name at the position
+fn html_section(document: &Document, position: Position) -> Option<String> {
+    for node in &document.ast {
+        match node {
+            Node::OpenTag(open_tag) => {
+                if is_on_tag_name(document, node, position) {
+                    return Some(tag_section(document, &open_tag.name));
+                }
+
+                for attribute in &open_tag.attributes {
+                    let Either::Left(name) = &attribute.name else {
+                        continue;
+                    };
+                    if contains(&document.area_to_range(&name.area), position) {
+                        return Some(attribute_section(document, &open_tag.name, &name.content));
+                    }
+                }
+            }
+            Node::CloseTag(close_tag) if is_on_tag_name(document, node, position) => {
+                return Some(tag_section(document, &close_tag.name));
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+fn tag_section(document: &Document, tag: &str) -> String {
+    let mut section = format!("**`<{tag}>`**");
+
+    if let Some(element) = elements::element(tag) {
+        section.push_str(&format!("\n\n{}", element.desc));
+        if !element.categories.is_empty() {
+            section.push_str(&format!(
+                "\n\nCategories: {}",
+                element.categories.join(", ")
+            ));
+        }
+        section.push_str(&format!(
+            "\n\nAttributes: {}",
+            code_list(element.own_attributes(), "global attributes")
+        ));
+    }
+
+    let whitelist = AttributeNameWhitelist::from_config(document.config());
+    if let Some(allowed) = whitelist
+        .as_ref()
+        .and_then(|whitelist| whitelist.tags.get(tag))
+    {
+        let allowed: Vec<String> = allowed.iter().map(|regex| regex.to_string()).collect();
+        section.push_str(&format!(
+            "\n\nAllowed by `attribute_name_whitelist`: {}",
+            code_list(
+                allowed.iter().map(String::as_str).collect(),
+                "global attributes"
+            )
+        ));
+    }
+
+    section
+}
+
+fn attribute_section(document: &Document, tag: &str, attribute: &str) -> String {
+    let mut section = format!("**`{attribute}`** on `<{tag}>`");
+
+    if elements::global_attributes().contains(attribute) {
+        section.push_str("\n\nGlobal attribute");
+    } else if let Some(element) = elements::element(tag) {
+        if element.attributes.iter().any(|name| name == attribute) {
+            section.push_str(&format!("\n\nAttribute of `<{tag}>`"));
+        } else {
+            section.push_str(&format!(
+                "\n\nNot an attribute of `<{tag}>` in the HTML specification"
+            ));
+        }
+    }
+
+    let whitelist = AttributeNameWhitelist::from_config(document.config());
+    match whitelist.and_then(|whitelist| whitelist.allows(tag, attribute)) {
+        Some(true) => section.push_str("\n\nAllowed by `attribute_name_whitelist`"),
+        Some(false) => section.push_str("\n\nNot allowed by `attribute_name_whitelist`"),
+        None => {}
+    }
+
+    section
+}
+
+fn code_list(items: Vec<&str>, rest: &str) -> String {
+    let mut items: Vec<String> = items.iter().map(|item| format!("`{item}`")).collect();
+    items.push(rest.to_string());
+    items.join(", ")
+}
+
+fn is_on_tag_name(document: &Document, node: &Node, position: Position) -> bool {
+    document
+        .tag_name_range(node)
+        .is_some_and(|range| contains(&range, position))
+}
+
+fn contains(range: &Range, position: Position) -> bool {
+    range.start <= position && position <= range.end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use htmlsnob::config::Config;
+    use htmlsnob::template_language::TemplateLanguage;
+    use tower_lsp::lsp_types::Url;
+
+    const CONFIG: &str = r#"
+        [[rules]]
+        kind = "tag_name_casing"
+        name = "lowercase_tags"
+        style = "lower"
+
+        [[rules]]
+        kind = "attribute_name_whitelist"
+        globals = ["class", "id"]
+
+        [rules.tags]
+        a = ["href"]
+    "#;
+
+    fn hover_text(text: &str, line: u32, character: u32) -> Option<String> {
+        let config = Config::from_toml(CONFIG, &htmlsnob_rules::registry());
+        let resolved_config = ResolvedConfig {
+            config,
+            path: None,
+            template_language: TemplateLanguage::None,
+            error: None,
+        };
+        let uri = Url::parse("file:///tmp/index.html").unwrap();
+        let mut document = Document::new(uri, text.to_string(), 1, resolved_config);
+        document.lint();
+
+        hover(&document, Position::new(line, character)).map(|hover| match hover.contents {
+            HoverContents::Markup(markup) => markup.value,
+            _ => unreachable!(),
+        })
+    }
+
+    #[test]
+    fn hover_over_warning() {
+        let text = hover_text("<DIV></DIV>", 0, 2).unwrap();
+
+        assert!(text.contains("**lowercase_tags** (`tag_name_casing`, error)"));
+        assert!(text.contains("Enforces"));
+        assert!(text.contains("[[rules]]\nkind = \"tag_name_casing\""));
+        assert!(text.contains("the default config"));
+        assert!(text.contains("**`<DIV>`**"));
+    }
+
+    #[test]
+    fn hover_over_tag_and_attribute() {
+        let tag = hover_text("<a href=\"/\" target=\"_blank\"></a>", 0, 1).unwrap();
+        assert!(tag.contains("Hyperlink"));
+        assert!(tag.contains("Allowed by `attribute_name_whitelist`: `href`, global attributes"));
+
+        let href = hover_text("<a href=\"/\" target=\"_blank\"></a>", 0, 4).unwrap();
+        assert!(href.contains("Attribute of `<a>`"));
+        assert!(href.contains("Allowed by `attribute_name_whitelist`"));
+
+        let target = hover_text("<a href=\"/\" target=\"_blank\"></a>", 0, 14).unwrap();
+        assert!(target.contains("Not allowed by `attribute_name_whitelist`"));
+
+        assert_eq!(hover_text("<a href=\"/\"> text </a>", 0, 15), None);
+    }
+}
@@ -1,32 +1,157 @@
-use htmlsnob::{format, lint};
+mod code_actions;
+mod commands;
+mod completion;
+mod diagnostics;
+mod document;
+mod elements;
+mod fixes;
+mod hover;
+mod outline;
+mod references;
+mod rule_config;
+mod semantic_tokens;
+mod settings;
+mod text_edits;
+mod workspace;
+
+use document::Document;
+use htmlsnob::format;
+use serde_json::Value;
+use settings::{ResolvedConfig, Settings, CONFIG_FILE_NAME};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::Mutex;
-use tower_lsp::jsonrpc::Result;
+use tower_lsp::jsonrpc::{Error, Result};
 use tower_lsp::lsp_types::*;
 use tower_lsp::{Client, LanguageServer, LspService, Server};
+use workspace::{ConfigCache, Reports};
 
-#[derive(Debug)]
-struct Backend {
-    state: Mutex<State>,
-    client: Client,
+/// How long to wait after the last change before linting a document
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// What the client supports, from its capabilities
+#[derive(Debug, Clone, Copy, Default)]
+struct ClientSupport {
+    /// Registering file watchers for the config and HTML files
+    watch_files: bool,
+    /// Pulling diagnostics, they aren't published when it does
+    pull_diagnostics: bool,
+    /// `workspace/diagnostic/refresh`, to pull again after the workspace was linted
+    workspace_diagnostic_refresh: bool,
+    /// `$/progress` notifications with server created tokens
+    work_done_progress: bool,
 }
 
-#[derive(Debug)]
-struct State {
-    ast: Vec<htmlsnob::ast::Node>,
-    config: htmlsnob::config::Config,
+#[derive(Debug, Clone)]
+struct Backend {
+    documents: Arc<Mutex<HashMap<Url, Document>>>,
+    settings: Arc<Mutex<Settings>>,
+    client_support: Arc<Mutex<ClientSupport>>,
+    /// The config files that have diagnostics of config errors
+    invalid_config_files: Arc<Mutex<HashSet<Url>>>,
+    /// The diagnostics of the open documents and the HTML files of the workspace
+    reports: Arc<Mutex<Reports>>,
+    /// Counts the progress tokens, to make them unique
+    progress_count: Arc<AtomicU64>,
+    /// Counts the runs of `lint_workspace`, a run stops when a newer one starts
+    workspace_lint_count: Arc<AtomicU64>,
+    client: Client,
 }
 
 #[tower_lsp::async_trait]
 impl LanguageServer for Backend {
-    async fn initialize(&self, _: InitializeParams) -> Result<InitializeResult> {
+    async fn initialize(&self, params: InitializeParams) -> Result<InitializeResult> {
+        let workspace_folder = params
+            .workspace_folders
+            .as_ref()
+            .and_then(|folders| folders.first())
+            .map(|folder| &folder.uri)
+            .or(params.root_uri.as_ref())
+            .and_then(|uri| uri.to_file_path().ok());
+        let options = params.initialization_options.unwrap_or_default();
+        match Settings::from_value(options, workspace_folder.clone()) {
+            Ok(settings) => *self.settings.lock().await = settings,
+            Err(error) => {
+                self.settings.lock().await.workspace_folder = workspace_folder;
+                self.client
+                    .log_message(
+                        MessageType::ERROR,
+                        format!("Invalid initialization options: {error}"),
+                    )
+                    .await;
+            }
+        }
+
+        let capabilities = params.capabilities;
+        let workspace = capabilities.workspace.as_ref();
+        *self.client_support.lock().await = ClientSupport {
+            watch_files: workspace
+                .and_then(|workspace| workspace.did_change_watched_files)
+                .and_then(|capability| capability.dynamic_registration)
+                .unwrap_or(false),
+            pull_diagnostics: capabilities
+                .text_document
+                .as_ref()
+                .is_some_and(|text_document| text_document.diagnostic.is_some()),
+            workspace_diagnostic_refresh: workspace
+                .and_then(|workspace| workspace.diagnostic.as_ref())
+                .and_then(|diagnostic| diagnostic.refresh_support)
+                .unwrap_or(false),
+            work_done_progress: capabilities
+                .window
+                .as_ref()
+                .and_then(|window| window.work_done_progress)
+                .unwrap_or(false),
+        };
+
         Ok(InitializeResult {
             capabilities: ServerCapabilities {
-                hover_provider: Some(HoverProviderCapability::Simple(false)),
-                // Configure sync to run only on save
+                hover_provider: Some(HoverProviderCapability::Simple(true)),
+                diagnostic_provider: Some(DiagnosticServerCapabilities::Options(
+                    DiagnosticOptions {
+                        identifier: Some("htmlsnob".to_string()),
+                        inter_file_dependencies: false,
+                        workspace_diagnostics: true,
+                        ..Default::default()
+                    },
+                )),
+                document_symbol_provider: Some(OneOf::Left(true)),
+                semantic_tokens_provider: Some(
+                    SemanticTokensServerCapabilities::SemanticTokensOptions(
+                        SemanticTokensOptions {
+                            legend: semantic_tokens::legend(),
+                            range: Some(true),
+                            full: Some(SemanticTokensFullOptions::Bool(true)),
+                            ..Default::default()
+                        },
+                    ),
+                ),
+                document_highlight_provider: Some(OneOf::Left(true)),
+                linked_editing_range_provider: Some(LinkedEditingRangeServerCapabilities::Simple(
+                    true,
+                )),
+                rename_provider: Some(OneOf::Right(RenameOptions {
+                    prepare_provider: Some(true),
+                    work_done_progress_options: Default::default(),
+                })),
+                folding_range_provider: Some(FoldingRangeProviderCapability::Simple(true)),
+                selection_range_provider: Some(SelectionRangeProviderCapability::Simple(true)),
+                completion_provider: Some(CompletionOptions {
+                    trigger_characters: Some(
+                        completion::TRIGGER_CHARACTERS
+                            .iter()
+                            .map(|character| character.to_string())
+                            .collect(),
+                    ),
+                    ..Default::default()
+                }),
                 text_document_sync: Some(TextDocumentSyncCapability::Options(
                     TextDocumentSyncOptions {
                         open_close: Some(true),
-                        change: Some(TextDocumentSyncKind::NONE),
+                        change: Some(TextDocumentSyncKind::INCREMENTAL),
                         will_save: Some(false),
                         will_save_wait_until: Some(false),
                         save: Some(TextDocumentSyncSaveOptions::SaveOptions(SaveOptions {
@@ -35,6 +160,23 @@ impl LanguageServer for Backend {
                     },
                 )),
                 document_formatting_provider: Some(OneOf::Left(true)),
+                execute_command_provider: Some(ExecuteCommandOptions {
+                    commands: commands::COMMANDS
+                        .iter()
+                        .map(|command| command.to_string())
+                        .collect(),
+                    ..Default::default()
+                }),
+                document_range_formatting_provider: Some(OneOf::Left(true)),
+                code_action_provider: Some(CodeActionProviderCapability::Options(
+                    CodeActionOptions {
+                        code_action_kinds: Some(vec![
+                            CodeActionKind::QUICKFIX,
+                            CodeActionKind::new(code_actions::SOURCE_FIX_ALL),
+                        ]),
+                        ..Default::default()
+                    },
+                )),
                 ..Default::default()
             },
             ..Default::default()
@@ -45,68 +187,471 @@ impl LanguageServer for Backend {
         self.client
             .log_message(MessageType::INFO, "Server initialized!")
             .await;
+
+        if self.client_support.lock().await.watch_files {
+            let mut watchers = vec![
+                FileSystemWatcher {
+                    glob_pattern: GlobPattern::String(format!("**/{CONFIG_FILE_NAME}")),
+                    kind: None,
+                },
+                FileSystemWatcher {
+                    glob_pattern: GlobPattern::String(format!(
+                        "**/*.{{{}}}",
+                        workspace::EXTENSIONS.join(",")
+                    )),
+                    kind: None,
+                },
+            ];
+            if let Some(config_path) = self.settings.lock().await.config_path() {
+                watchers.push(FileSystemWatcher {
+                    glob_pattern: GlobPattern::String(config_path.to_string_lossy().into_owned()),
+                    kind: None,
+                });
+            }
+            let registration = Registration {
+                id: "htmlsnob-files".to_string(),
+                method: "workspace/didChangeWatchedFiles".to_string(),
+                register_options: serde_json::to_value(DidChangeWatchedFilesRegistrationOptions {
+                    watchers,
+                })
+                .ok(),
+            };
+
+            // The response is awaited, so the server keeps handling messages in the meantime
+            let client = self.client.clone();
+            tokio::spawn(async move {
+                if let Err(error) = client.register_capability(vec![registration]).await {
+                    client
+                        .log_message(
+                            MessageType::WARNING,
+                            format!("Failed to watch files: {error}"),
+                        )
+                        .await;
+                }
+            });
+        }
+
+        let backend = self.clone();
+        tokio::spawn(async move { backend.lint_workspace().await });
+    }
+
+    async fn did_change_configuration(&self, params: DidChangeConfigurationParams) {
+        let mut settings = self.settings.lock().await;
+        match Settings::from_value(params.settings, settings.workspace_folder.clone()) {
+            Ok(new_settings) => *settings = new_settings,
+            Err(error) => {
+                self.client
+                    .log_message(MessageType::ERROR, format!("Invalid settings: {error}"))
+                    .await;
+                return;
+            }
+        }
+        drop(settings);
+
+        self.reload_configs().await;
+    }
+
+    async fn did_change_watched_files(&self, params: DidChangeWatchedFilesParams) {
+        let config_path = self.settings.lock().await.config_path();
+        let is_config_file = |uri: &Url| {
+            let Ok(path) = uri.to_file_path() else {
+                return false;
+            };
+            path.file_name() == Some(CONFIG_FILE_NAME.as_ref())
+                || config_path.as_ref() == Some(&path)
+        };
+
+        if params
+            .changes
+            .iter()
+            .any(|change| is_config_file(&change.uri))
+        {
+            self.reload_configs().await;
+            return;
+        }
+
+        // HTML files that changed on disk, the open ones are linted from their buffers
+        for change in params.changes {
+            if !self.documents.lock().await.contains_key(&change.uri) {
+                self.check_file(&change.uri).await;
+            }
+        }
     }
 
     async fn did_open(&self, params: DidOpenTextDocumentParams) {
-        self.client
-            .log_message(MessageType::INFO, "File opened!")
-            .await;
+        let text_document = params.text_document;
+        let config = ResolvedConfig::resolve(&text_document.uri, &*self.settings.lock().await);
+        let document = Document::new(
+            text_document.uri.clone(),
+            text_document.text,
+            text_document.version,
+            config,
+        );
+        self.documents
+            .lock()
+            .await
+            .insert(text_document.uri.clone(), document);
 
-        // Run initial diagnostics when file is opened
-        self.check_document(&params.text_document.uri, &params.text_document.text)
-            .await;
+        // Lint right away when a file is opened
+        self.publish_config_errors().await;
+        self.check_document(&text_document.uri, None).await;
+    }
+
+    async fn did_change(&self, params: DidChangeTextDocumentParams) {
+        let uri = params.text_document.uri;
+        let version = params.text_document.version;
+
+        let mut documents = self.documents.lock().await;
+        let Some(document) = documents.get_mut(&uri) else {
+            return;
+        };
+        for change in params.content_changes {
+            document.apply_change(change);
+        }
+        document.version = version;
+        drop(documents);
+
+        // Lint once the user has stopped typing, changes in the meantime supersede this one
+        let backend = self.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(DEBOUNCE).await;
+            backend.check_document(&uri, Some(version)).await;
+        });
     }
 
     async fn did_save(&self, params: DidSaveTextDocumentParams) {
-        self.client
-            .log_message(MessageType::INFO, "File saved!")
-            .await;
+        let uri = params.text_document.uri;
 
-        // Only check the document when it's saved
+        let mut documents = self.documents.lock().await;
+        let Some(document) = documents.get_mut(&uri) else {
+            return;
+        };
+        // The saved text may differ from the buffer, e.g. when the client formatted on save
         if let Some(text) = params.text {
-            self.check_document(&params.text_document.uri, &text).await;
+            if text != document.text {
+                document.text = text;
+                document.linted_version = None;
+            }
         }
+        drop(documents);
+
+        self.check_document(&uri, None).await;
+    }
+
+    async fn did_close(&self, params: DidCloseTextDocumentParams) {
+        let uri = params.text_document.uri;
+        self.documents.lock().await.remove(&uri);
+
+        // The file on disk may differ from the closed buffer
+        self.check_file(&uri).await;
     }
 
     async fn formatting(&self, params: DocumentFormattingParams) -> Result<Option<Vec<TextEdit>>> {
-        self.client
-            .log_message(MessageType::INFO, "Formatting document requested")
-            .await;
+        let mut documents = self.documents.lock().await;
+        let Some(document) = documents.get_mut(&params.text_document.uri) else {
+            return Ok(None);
+        };
+        document.lint();
+
+        let formatted_text = format(&document.ast, document.config());
+        Ok(Some(text_edits::diff(&document.text, &formatted_text)))
+    }
+
+    async fn range_formatting(
+        &self,
+        params: DocumentRangeFormattingParams,
+    ) -> Result<Option<Vec<TextEdit>>> {
+        let mut documents = self.documents.lock().await;
+        let Some(document) = documents.get_mut(&params.text_document.uri) else {
+            return Ok(None);
+        };
+        document.lint();
+
+        // Expand the selection to the lines of the innermost element around it, and keep the
+        // edits of the whole document formatting that fall within those lines
+        let range = document
+            .enclosing_element(params.range)
+            .map_or(params.range, |open_tag| document.element_range(open_tag));
+        let first_line = range.start.line;
+        let last_line = range.end.line;
+
+        let formatted_text = format(&document.ast, document.config());
+        let edits = text_edits::diff(&document.text, &formatted_text)
+            .into_iter()
+            .filter(|edit| {
+                let end_line = if edit.range.end.character == 0 && edit.range.end > edit.range.start
+                {
+                    edit.range.end.line - 1
+                } else {
+                    edit.range.end.line
+                };
+                first_line <= edit.range.start.line && end_line <= last_line
+            })
+            .collect();
+
+        Ok(Some(edits))
+    }
+
+    async fn code_action(&self, params: CodeActionParams) -> Result<Option<CodeActionResponse>> {
+        let mut documents = self.documents.lock().await;
+        let Some(document) = documents.get_mut(&params.text_document.uri) else {
+            return Ok(None);
+        };
+        document.lint();
+
+        Ok(Some(code_actions::code_actions(document, &params)))
+    }
+
+    async fn hover(&self, params: HoverParams) -> Result<Option<Hover>> {
+        let position = params.text_document_position_params;
+        let mut documents = self.documents.lock().await;
+        let Some(document) = documents.get_mut(&position.text_document.uri) else {
+            return Ok(None);
+        };
+        document.lint();
+
+        Ok(hover::hover(document, position.position))
+    }
+
+    async fn completion(&self, params: CompletionParams) -> Result<Option<CompletionResponse>> {
+        let position = params.text_document_position;
+        let documents = self.documents.lock().await;
+        let Some(document) = documents.get(&position.text_document.uri) else {
+            return Ok(None);
+        };
+
+        Ok(Some(CompletionResponse::Array(completion::completions(
+            document,
+            position.position,
+        ))))
+    }
+
+    async fn document_symbol(
+        &self,
+        params: DocumentSymbolParams,
+    ) -> Result<Option<DocumentSymbolResponse>> {
+        let mut documents = self.documents.lock().await;
+        let Some(document) = documents.get_mut(&params.text_document.uri) else {
+            return Ok(None);
+        };
+        document.lint();
+
+        Ok(Some(DocumentSymbolResponse::Nested(
+            outline::document_symbols(document),
+        )))
+    }
+
+    async fn semantic_tokens_full(
+        &self,
+        params: SemanticTokensParams,
+    ) -> Result<Option<SemanticTokensResult>> {
+        let mut documents = self.documents.lock().await;
+        let Some(document) = documents.get_mut(&params.text_document.uri) else {
+            return Ok(None);
+        };
+        document.lint();
+
+        Ok(Some(SemanticTokensResult::Tokens(SemanticTokens {
+            result_id: None,
+            data: semantic_tokens::semantic_tokens(document, None),
+        })))
+    }
+
+    async fn semantic_tokens_range(
+        &self,
+        params: SemanticTokensRangeParams,
+    ) -> Result<Option<SemanticTokensRangeResult>> {
+        let mut documents = self.documents.lock().await;
+        let Some(document) = documents.get_mut(&params.text_document.uri) else {
+            return Ok(None);
+        };
+        document.lint();
+
+        Ok(Some(SemanticTokensRangeResult::Tokens(SemanticTokens {
+            result_id: None,
+            data: semantic_tokens::semantic_tokens(document, Some(params.range)),
+        })))
+    }
+
+    async fn folding_range(&self, params: FoldingRangeParams) -> Result<Option<Vec<FoldingRange>>> {
+        let mut documents = self.documents.lock().await;
+        let Some(document) = documents.get_mut(&params.text_document.uri) else {
+            return Ok(None);
+        };
+        document.lint();
+
+        Ok(Some(outline::folding_ranges(document)))
+    }
+
+    async fn selection_range(
+        &self,
+        params: SelectionRangeParams,
+    ) -> Result<Option<Vec<SelectionRange>>> {
+        let mut documents = self.documents.lock().await;
+        let Some(document) = documents.get_mut(&params.text_document.uri) else {
+            return Ok(None);
+        };
+        document.lint();
+
+        Ok(Some(
+            params
+                .positions
+                .into_iter()
+                .map(|position| outline::selection_range(document, position))
+                .collect(),
+        ))
+    }
+
+    async fn linked_editing_range(
+        &self,
+        params: LinkedEditingRangeParams,
+    ) -> Result<Option<LinkedEditingRanges>> {
+        let position = params.text_document_position_params;
+        let mut documents = self.documents.lock().await;
+        let Some(document) = documents.get_mut(&position.text_document.uri) else {
+            return Ok(None);
+        };
+        document.lint();
+
+        Ok(references::linked_editing_ranges(
+            document,
+            position.position,
+        ))
+    }
+
+    async fn document_highlight(
+        &self,
+        params: DocumentHighlightParams,
+    ) -> Result<Option<Vec<DocumentHighlight>>> {
+        let position = params.text_document_position_params;
+        let mut documents = self.documents.lock().await;
+        let Some(document) = documents.get_mut(&position.text_document.uri) else {
+            return Ok(None);
+        };
+        document.lint();
+
+        Ok(references::document_highlights(document, position.position))
+    }
 
-        // Get document content from file
-        let text = std::fs::read_to_string(params.text_document.uri.to_file_path().unwrap())
-            .expect("Failed to read file");
+    async fn prepare_rename(
+        &self,
+        params: TextDocumentPositionParams,
+    ) -> Result<Option<PrepareRenameResponse>> {
+        let mut documents = self.documents.lock().await;
+        let Some(document) = documents.get_mut(&params.text_document.uri) else {
+            return Ok(None);
+        };
+        document.lint();
 
-        // Format the entire document
-        let state = self.state.lock().await;
-        let formatted_text = format(&state.ast, &state.config);
-        drop(state);
+        Ok(references::prepare_rename(document, params.position).map(PrepareRenameResponse::Range))
+    }
 
-        // If no changes were made, return None
-        if formatted_text == text {
+    async fn rename(&self, params: RenameParams) -> Result<Option<WorkspaceEdit>> {
+        let position = params.text_document_position;
+        let mut documents = self.documents.lock().await;
+        let Some(document) = documents.get_mut(&position.text_document.uri) else {
             return Ok(None);
+        };
+        document.lint();
+
+        Ok(references::rename(
+            document,
+            position.position,
+            &params.new_name,
+        ))
+    }
+
+    async fn diagnostic(
+        &self,
+        params: DocumentDiagnosticParams,
+    ) -> Result<DocumentDiagnosticReportResult> {
+        let uri = params.text_document.uri;
+        let mut documents = self.documents.lock().await;
+        let mut reports = self.reports.lock().await;
+        if let Some(document) = documents.get_mut(&uri) {
+            document.lint();
+            reports.update(
+                uri.clone(),
+                diagnostics::diagnostics(document),
+                Some(document.version),
+            );
         }
 
-        // TEST: Let's always just return None
-        Ok(None)
+        let report = match reports.get(&uri) {
+            Some(report) => report.document_report(params.previous_result_id.as_deref()),
+            None => DocumentDiagnosticReport::Full(Default::default()),
+        };
+        Ok(DocumentDiagnosticReportResult::Report(report))
+    }
 
-        //// Create a TextEdit that replaces the entire document
-        //let edit = TextEdit {
-        //    range: Range {
-        //        start: Position {
-        //            line: 0,
-        //            character: 0,
-        //        },
-        //        end: Position {
-        //            // This effectively covers the entire document
-        //            line: u32::MAX, // A very large number to ensure the entire document is covered
-        //            character: 0,
-        //        },
-        //    },
-        //    new_text: formatted_text,
-        //};
+    async fn workspace_diagnostic(
+        &self,
+        params: WorkspaceDiagnosticParams,
+    ) -> Result<WorkspaceDiagnosticReportResult> {
+        let previous_result_ids = params
+            .previous_result_ids
+            .into_iter()
+            .map(|previous| (previous.uri, previous.value))
+            .collect();
+        let items = self
+            .reports
+            .lock()
+            .await
+            .workspace_reports(&previous_result_ids);
 
-        //Ok(Some(vec![edit]))
+        Ok(WorkspaceDiagnosticReportResult::Report(
+            WorkspaceDiagnosticReport { items },
+        ))
+    }
+
+    async fn execute_command(&self, params: ExecuteCommandParams) -> Result<Option<Value>> {
+        match params.command.as_str() {
+            commands::FIX_ALL_IN_WORKSPACE => {
+                self.edit_workspace("Fixing workspace", commands::fix_all_edits)
+                    .await;
+                Ok(None)
+            }
+            commands::FORMAT_WORKSPACE => {
+                self.edit_workspace("Formatting workspace", commands::format_edits)
+                    .await;
+                Ok(None)
+            }
+            commands::EXPLAIN_RULE => {
+                let Some(rule) = params.arguments.first().and_then(Value::as_str) else {
+                    return Err(Error::invalid_params("Expected the name of a rule"));
+                };
+                let uri = params
+                    .arguments
+                    .get(1)
+                    .and_then(Value::as_str)
+                    .and_then(|uri| Url::parse(uri).ok());
+
+                let documents = self.documents.lock().await;
+                let explanation = match uri.as_ref().and_then(|uri| documents.get(uri)) {
+                    Some(document) => commands::explain_rule(&document.config, rule),
+                    None => {
+                        let settings = self.settings.lock().await;
+                        let config = match (&uri, &settings.workspace_folder) {
+                            (Some(uri), _) => ResolvedConfig::resolve(uri, &settings),
+                            (None, Some(folder)) => match Url::from_file_path(folder.join("_")) {
+                                Ok(uri) => ResolvedConfig::resolve(&uri, &settings),
+                                Err(_) => ResolvedConfig::default(),
+                            },
+                            (None, None) => ResolvedConfig::default(),
+                        };
+                        commands::explain_rule(&config, rule)
+                    }
+                };
+
+                match explanation {
+                    Some(explanation) => Ok(Some(Value::String(explanation))),
+                    None => Err(Error::invalid_params(format!("Unknown rule `{rule}`"))),
+                }
+            }
+            command => Err(Error::invalid_params(format!(
+                "Unknown command `{command}`"
+            ))),
+        }
     }
 
     async fn shutdown(&self) -> Result<()> {
@@ -115,67 +660,313 @@ impl LanguageServer for Backend {
 }
 
 impl Backend {
-    async fn check_document(&self, uri: &Url, content: &str) {
-        let template_language =
-            htmlsnob::template_language::TemplateLanguage::from_filename(uri.path());
-
-        let mut state = self.state.lock().await;
-        state.config.options.template_language = template_language;
-        let (ast, warnings) = lint(content, &mut state.config);
-        state.ast = ast;
-        drop(state);
-
-        // Convert the linter diagnostics to LSP diagnostics
-        let diagnostics = warnings
-            .into_iter()
-            .flat_map(|warning| {
-                warning
-                    .areas
-                    .iter()
-                    .map(|area| {
-                        // Create a diagnostic for each range
-                        Diagnostic {
-                            range: Self::area_to_range(area),
-                            severity: Self::warning_severity_to_diagnostic_severity(
-                                &warning.severity,
-                            ),
-                            source: Some("htmlsnob-lsp".to_string()),
-                            message: warning.message.clone(),
-                            ..Default::default()
-                        }
+    /// Resolves the configs of the open documents again, after the settings or a config file
+    /// changed, and lints them with the new configs
+    async fn reload_configs(&self) {
+        let settings = self.settings.lock().await.clone();
+        let mut documents = self.documents.lock().await;
+        for document in documents.values_mut() {
+            document.config = ResolvedConfig::resolve(&document.uri, &settings);
+            document.linted_version = None;
+        }
+        let uris: Vec<Url> = documents.keys().cloned().collect();
+        drop(documents);
+
+        self.publish_config_errors().await;
+        for uri in uris {
+            self.check_document(&uri, None).await;
+        }
+
+        let backend = self.clone();
+        tokio::spawn(async move { backend.lint_workspace().await });
+    }
+
+    /// Lints the HTML files of the workspace folder that aren't open, reporting the progress.
+    /// Stops when it's run again, e.g. after a config change, since the newer run lints with the
+    /// newer config.
+    async fn lint_workspace(&self) {
+        let run = self.workspace_lint_count.fetch_add(1, Ordering::SeqCst) + 1;
+        let is_superseded = || self.workspace_lint_count.load(Ordering::SeqCst) != run;
+
+        let settings = self.settings.lock().await.clone();
+        let Some(folder) = settings.workspace_folder.clone() else {
+            return;
+        };
+        let client_support = *self.client_support.lock().await;
+
+        let progress = match client_support.work_done_progress {
+            true => self.begin_progress("Linting workspace").await,
+            false => None,
+        };
+
+        let files = tokio::task::spawn_blocking(move || workspace::files(&folder))
+            .await
+            .unwrap_or_default();
+        let settings = Arc::new(settings);
+        let configs = Arc::new(ConfigCache::default());
+        for (index, path) in files.iter().enumerate() {
+            if is_superseded() {
+                if let Some(token) = progress {
+                    self.end_progress(token, "Restarted".to_string()).await;
+                }
+                return;
+            }
+            if let Some(token) = &progress {
+                self.report_progress(token, index, files.len()).await;
+            }
+            self.lint_file(path.clone(), &settings, &configs).await;
+        }
+
+        if let Some(token) = progress {
+            self.end_progress(token, format!("Linted {} files", files.len()))
+                .await;
+        }
+        if client_support.pull_diagnostics && client_support.workspace_diagnostic_refresh {
+            let _ = self.client.workspace_diagnostic_refresh().await;
+        }
+    }
+
+    /// Publishes the errors of the configs of the open documents in the config files, and
+    /// clears the ones of config files that are valid now
+    async fn publish_config_errors(&self) {
+        let mut diagnostics: HashMap<Url, Vec<Diagnostic>> = HashMap::new();
+        for document in self.documents.lock().await.values() {
+            if let Some((uri, diagnostic)) = document.config.config_file_diagnostic() {
+                diagnostics.insert(uri, vec![diagnostic]);
+            }
+        }
+
+        let mut invalid_config_files = self.invalid_config_files.lock().await;
+        for uri in invalid_config_files.drain() {
+            diagnostics.entry(uri).or_default();
+        }
+        for (uri, diagnostics) in diagnostics {
+            if !diagnostics.is_empty() {
+                invalid_config_files.insert(uri.clone());
+            }
+            self.client
+                .publish_diagnostics(uri, diagnostics, None)
+                .await;
+        }
+    }
+
+    /// Lints a file that changed on disk or was closed, if it belongs to the workspace, or
+    /// clears its diagnostics
+    async fn check_file(&self, uri: &Url) {
+        let settings = Arc::new(self.settings.lock().await.clone());
+        let path = uri.to_file_path().ok();
+        let is_workspace_file = match (&settings.workspace_folder, &path) {
+            (Some(folder), Some(path)) => {
+                path.is_file() && workspace::is_workspace_file(folder, path)
+            }
+            _ => false,
+        };
+
+        match path {
+            Some(path) if is_workspace_file => {
+                let configs = Arc::new(ConfigCache::default());
+                self.lint_file(path, &settings, &configs).await
+            }
+            _ => {
+                self.reports.lock().await.remove(uri);
+                if !self.client_support.lock().await.pull_diagnostics {
+                    self.client
+                        .publish_diagnostics(uri.clone(), Vec::new(), None)
+                        .await;
+                }
+            }
+        }
+    }
+
+    /// Lints a file on disk, unless it is open, then its buffer is linted instead
+    async fn lint_file(&self, path: PathBuf, settings: &Arc<Settings>, configs: &Arc<ConfigCache>) {
+        let settings = settings.clone();
+        let configs = configs.clone();
+        let Ok(Some((uri, diagnostics))) =
+            tokio::task::spawn_blocking(move || workspace::lint_file(&path, &settings, &configs))
+                .await
+        else {
+            return;
+        };
+
+        // Opened while it was linted
+        if self.documents.lock().await.contains_key(&uri) {
+            return;
+        }
+        self.publish(uri, diagnostics, None).await;
+    }
+
+    /// Stores the diagnostics for the pull model, or publishes them if the client doesn't pull
+    async fn publish(&self, uri: Url, diagnostics: Vec<Diagnostic>, version: Option<i32>) {
+        self.reports
+            .lock()
+            .await
+            .update(uri.clone(), diagnostics.clone(), version);
+
+        if !self.client_support.lock().await.pull_diagnostics {
+            self.client
+                .publish_diagnostics(uri, diagnostics, version)
+                .await;
+        }
+    }
+
+    /// Applies the edits to the open documents and the files of the workspace with
+    /// `workspace/applyEdit`, so they can be undone in the editor
+    async fn edit_workspace(&self, title: &str, edits: fn(&Document) -> Vec<TextEdit>) {
+        let settings = Arc::new(self.settings.lock().await.clone());
+
+        let mut changes = HashMap::new();
+        let mut documents = self.documents.lock().await;
+        for (uri, document) in documents.iter_mut() {
+            document.lint();
+            changes.insert(uri.clone(), edits(document));
+        }
+        let open: HashSet<Url> = documents.keys().cloned().collect();
+        drop(documents);
+
+        if let Some(folder) = settings.workspace_folder.clone() {
+            let progress = match self.client_support.lock().await.work_done_progress {
+                true => self.begin_progress(title).await,
+                false => None,
+            };
+
+            let files = tokio::task::spawn_blocking(move || workspace::files(&folder))
+                .await
+                .unwrap_or_default();
+            let configs = Arc::new(ConfigCache::default());
+            for (index, path) in files.iter().enumerate() {
+                if let Some(token) = &progress {
+                    self.report_progress(token, index, files.len()).await;
+                }
+                if Url::from_file_path(path).is_ok_and(|uri| open.contains(&uri)) {
+                    continue;
+                }
+
+                let path = path.clone();
+                let settings = settings.clone();
+                let configs = configs.clone();
+                let file_edits = tokio::task::spawn_blocking(move || {
+                    workspace::with_document(&path, &settings, &configs, |document| {
+                        (document.uri.clone(), edits(document))
                     })
-                    .collect::<Vec<_>>()
-            })
-            .collect();
+                });
+                if let Ok(Some((uri, file_edits))) = file_edits.await {
+                    changes.insert(uri, file_edits);
+                }
+            }
 
-        // Send the diagnostics to the client
+            if let Some(token) = progress {
+                self.end_progress(token, format!("Checked {} files", files.len()))
+                    .await;
+            }
+        }
+
+        changes.retain(|_, edits| !edits.is_empty());
+        if changes.is_empty() {
+            self.client
+                .show_message(MessageType::INFO, format!("{title}: nothing to change"))
+                .await;
+            return;
+        }
+
+        let edit = WorkspaceEdit {
+            changes: Some(changes),
+            ..Default::default()
+        };
+        match self.client.apply_edit(edit).await {
+            Ok(response) if response.applied => {}
+            Ok(response) => {
+                let reason = response.failure_reason.unwrap_or_default();
+                self.client
+                    .show_message(MessageType::WARNING, format!("{title} failed: {reason}"))
+                    .await;
+            }
+            Err(error) => {
+                self.client
+                    .show_message(MessageType::ERROR, format!("{title} failed: {error}"))
+                    .await;
+            }
+        }
+    }
+
+    /// Creates a unique progress token and begins the progress, `None` if the client refused the
+    /// token
+    async fn begin_progress(&self, title: &str) -> Option<ProgressToken> {
+        let count = self.progress_count.fetch_add(1, Ordering::SeqCst);
+        let token = ProgressToken::String(format!("htmlsnob-{title}-{count}").replace(' ', "-"));
         self.client
-            .publish_diagnostics(uri.clone(), diagnostics, None)
-            .await;
+            .send_request::<request::WorkDoneProgressCreate>(WorkDoneProgressCreateParams {
+                token: token.clone(),
+            })
+            .await
+            .ok()?;
+
+        self.send_progress(
+            &token,
+            WorkDoneProgress::Begin(WorkDoneProgressBegin {
+                title: title.to_string(),
+                percentage: Some(0),
+                ..Default::default()
+            }),
+        )
+        .await;
+        Some(token)
     }
 
-    fn area_to_range(area: &htmlsnob::ast::Area) -> Range {
-        Range {
-            start: Position {
-                line: u32::try_from(area.start.line).unwrap(),
-                character: u32::try_from(area.start.column).unwrap(),
-            },
-            end: Position {
-                line: u32::try_from(area.end.line).unwrap(),
-                character: u32::try_from(area.end.column).unwrap(),
-            },
+    /// Reports the files done so far, when the percentage changes
+    async fn report_progress(&self, token: &ProgressToken, done: usize, total: usize) {
+        let percentage = done * 100 / total;
+        if done > 0 && percentage == (done - 1) * 100 / total {
+            return;
         }
+
+        self.send_progress(
+            token,
+            WorkDoneProgress::Report(WorkDoneProgressReport {
+                message: Some(format!("{done}/{total} files")),
+                percentage: Some(percentage as u32),
+                ..Default::default()
+            }),
+        )
+        .await;
+    }
+
+    async fn end_progress(&self, token: ProgressToken, message: String) {
+        self.send_progress(
+            &token,
+            WorkDoneProgress::End(WorkDoneProgressEnd {
+                message: Some(message),
+            }),
+        )
+        .await;
+    }
+
+    async fn send_progress(&self, token: &ProgressToken, progress: WorkDoneProgress) {
+        self.client
+            .send_notification::<notification::Progress>(ProgressParams {
+                token: token.clone(),
+                value: ProgressParamsValue::WorkDone(progress),
+            })
+            .await;
     }
 
-    fn warning_severity_to_diagnostic_severity(
-        warning_severity: &htmlsnob::WarningSeverity,
-    ) -> Option<DiagnosticSeverity> {
-        match warning_severity {
-            htmlsnob::WarningSeverity::ERROR => Some(DiagnosticSeverity::ERROR),
-            htmlsnob::WarningSeverity::WARNING => Some(DiagnosticSeverity::WARNING),
-            htmlsnob::WarningSeverity::INFORMATION => Some(DiagnosticSeverity::INFORMATION),
-            htmlsnob::WarningSeverity::HINT => Some(DiagnosticSeverity::HINT),
+    /// Lints a document and publishes its diagnostics. When `version` is given, the document is
+    /// only linted if it is still at that version.
+    async fn check_document(&self, uri: &Url, version: Option<i32>) {
+        let mut documents = self.documents.lock().await;
+        let Some(document) = documents.get_mut(uri) else {
+            return;
+        };
+        if version.is_some_and(|version| version != document.version) {
+            return;
         }
+        document.lint();
+
+        let diagnostics = diagnostics::diagnostics(document);
+        let version = document.version;
+        drop(documents);
+
+        self.publish(uri.clone(), diagnostics, Some(version)).await;
     }
 }
 
@@ -184,28 +975,14 @@ async fn main() {
     let stdin = tokio::io::stdin();
     let stdout = tokio::io::stdout();
 
-    let registry = htmlsnob_rules::registry();
-
-    // Load config from file
-    let default_config = std::fs::read_to_string("default_config/default_config.toml")
-        .expect("Failed to read config file");
-    let attribute_name_whitelist =
-        std::fs::read_to_string("default_config/attribute_name_whitelist.toml")
-            .expect("Failed to read config file");
-    let class_order = std::fs::read_to_string("default_config/class_order.toml")
-        .expect("Failed to read config file");
-
-    let config_string = format!(
-        "{}\n{}\n{}",
-        default_config, attribute_name_whitelist, class_order
-    );
-
-    let config = htmlsnob::config::Config::from_toml(&config_string, &registry);
     let (service, socket) = LspService::new(|client| Backend {
-        state: Mutex::new(State {
-            ast: Vec::new(),
-            config: config,
-        }),
+        documents: Arc::new(Mutex::new(HashMap::new())),
+        settings: Arc::new(Mutex::new(Settings::default())),
+        client_support: Arc::new(Mutex::new(ClientSupport::default())),
+        invalid_config_files: Arc::new(Mutex::new(HashSet::new())),
+        reports: Arc::new(Mutex::new(Reports::default())),
+        progress_count: Arc::new(AtomicU64::new(0)),
+        workspace_lint_count: Arc::new(AtomicU64::new(0)),
         client,
     });
     Server::new(stdin, stdout, socket).serve(service).await;
@@ -0,0 +1,428 @@
+use htmlsnob::ast::{CommentKind, Construct, Either, Node};
+use tower_lsp::lsp_types::{
+    DocumentSymbol, FoldingRange, FoldingRangeKind, Position, Range, SelectionRange, SymbolKind,
+};
+
+use crate::document::Document;
+
+/// An element, or a template construct like an if or a loop, with the sections nested in it
+#[derive(Debug)]
+struct Section {
+    /// The index of the open tag or template expression that starts the section
+    start: usize,
+    /// The index of the last node in the section
+    end: usize,
+    /// The index of the close tag or template expression that ends the section, if it's explicit
+    closer: Option<usize>,
+    children: Vec<Section>,
+}
+
+/// Builds the tree of sections of the document, the close tags of elements are known from the
+/// parser, the ends of template constructs are matched here
+fn sections(ast: &[Node]) -> Vec<Section> {
+    let mut root = Vec::new();
+    let mut stack: Vec<Section> = Vec::new();
+
+    fn close(stack: &mut Vec<Section>, root: &mut Vec<Section>, end: usize, closer: Option<usize>) {
+        if let Some(mut section) = stack.pop() {
+            section.end = end.max(section.start);
+            section.closer = closer;
+            match stack.last_mut() {
+                Some(parent) => parent.children.push(section),
+                None => root.push(section),
+            }
+        }
+    }
+
+    // The last node before the index that isn't whitespace, where an implicitly closed section ends
+    let last_node = |index: usize| {
+        (0..index)
+            .rev()
+            .find(|index| !matches!(&ast[*index], Node::Text(text) if text.content.is_empty()))
+            .unwrap_or(0)
+    };
+
+    // Closes the sections above the section that matches, and the matching section itself
+    let close_matching = |stack: &mut Vec<Section>,
+                          root: &mut Vec<Section>,
+                          index: usize,
+                          matches: &dyn Fn(usize) -> bool| {
+        let Some(position) = stack.iter().rposition(|section| matches(section.start)) else {
+            return;
+        };
+        while stack.len() > position + 1 {
+            close(stack, root, last_node(index), None);
+        }
+        close(stack, root, index, Some(index));
+    };
+
+    for (index, node) in ast.iter().enumerate() {
+        let section = Section {
+            start: index,
+            end: index,
+            closer: None,
+            children: Vec::new(),
+        };
+
+        match node {
+            Node::OpenTag(open_tag) if open_tag.close_tag_index.is_some() => stack.push(section),
+            Node::OpenTag(_) => match stack.last_mut() {
+                Some(parent) => parent.children.push(section),
+                None => root.push(section),
+            },
+            Node::CloseTag(close_tag) => {
+                if let Some(open_tag_index) = close_tag.open_tag_index {
+                    close_matching(&mut stack, &mut root, index, &|start| {
+                        start == open_tag_index
+                    });
+                }
+            }
+            Node::TemplateExpression(expression) => {
+                let kind_at = |start: usize| match &ast[start] {
+                    Node::TemplateExpression(expression) => Some(expression.kind.clone()),
+                    _ => None,
+                };
+                let is_top = |stack: &Vec<Section>, kinds: &[Construct]| {
+                    stack
+                        .last()
+                        .and_then(|section| kind_at(section.start))
+                        .is_some_and(|kind| kinds.contains(&kind))
+                };
+
+                match expression.kind {
+                    Construct::If | Construct::Loop | Construct::Block | Construct::Switch => {
+                        stack.push(section)
+                    }
+                    // A branch ends the previous branch, and is a section of its own
+                    Construct::Else | Construct::Case => {
+                        if is_top(
+                            &stack,
+                            &[
+                                Construct::If,
+                                Construct::Loop,
+                                Construct::Else,
+                                Construct::Case,
+                            ],
+                        ) {
+                            close(&mut stack, &mut root, last_node(index), None);
+                        }
+                        stack.push(section);
+                    }
+                    Construct::EndIf | Construct::EndLoop | Construct::EndBlock => {
+                        let opener = match expression.kind {
+                            Construct::EndIf => Construct::If,
+                            Construct::EndLoop => Construct::Loop,
+                            _ => Construct::Block,
+                        };
+                        if is_top(&stack, &[Construct::Else]) {
+                            close(&mut stack, &mut root, index, Some(index));
+                        } else {
+                            close_matching(&mut stack, &mut root, index, &|start| {
+                                kind_at(start) == Some(opener.clone())
+                            });
+                        }
+                    }
+                    Construct::EndSwitch => {
+                        if is_top(&stack, &[Construct::Case]) {
+                            close(&mut stack, &mut root, last_node(index), None);
+                        }
+                        close_matching(&mut stack, &mut root, index, &|start| {
+                            kind_at(start) == Some(Construct::Switch)
+                        });
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    while !stack.is_empty() {
+        close(&mut stack, &mut root, last_node(ast.len()), None);
+    }
+
+    root
+}
+
+impl Section {
+    fn range(&self, document: &Document) -> Range {
+        Range {
+            start: document
+                .area_to_range(document.ast[self.start].area())
+                .start,
+            end: document.area_to_range(document.ast[self.end].area()).end,
+        }
+    }
+}
+
+/// The outline of the document, elements and template constructs
+pub fn document_symbols(document: &Document) -> Vec<DocumentSymbol> {
+    sections(&document.ast)
+        .iter()
+        .map(|section| document_symbol(document, section))
+        .collect()
+}
+
+#[allow(deprecated)] // `DocumentSymbol::deprecated` has to be set
+fn document_symbol(document: &Document, section: &Section) -> DocumentSymbol {
+    let node = &document.ast[section.start];
+    let (name, detail, kind) = match node {
+        Node::OpenTag(open_tag) => {
+            let mut detail = Vec::new();
+            for attribute in &open_tag.attributes {
+                let (Either::Left(name), Some(value)) = (&attribute.name, &attribute.value) else {
+                    continue;
+                };
+                let prefix = match name.content.as_str() {
+                    "id" => "#",
+                    "class" => ".",
+                    _ => continue,
+                };
+                detail.extend(value.parts.iter().filter_map(|part| match part {
+                    Either::Left(part) => Some(format!("{prefix}{}", part.content)),
+                    Either::Right(_) => None,
+                }));
+            }
+            let detail = (!detail.is_empty()).then(|| detail.join(" "));
+            (open_tag.name.clone(), detail, SymbolKind::FIELD)
+        }
+        Node::TemplateExpression(expression) => {
+            let kind = match expression.kind {
+                Construct::Loop => SymbolKind::ARRAY,
+                Construct::Block => SymbolKind::NAMESPACE,
+                _ => SymbolKind::KEY,
+            };
+            (expression.content.clone(), None, kind)
+        }
+        _ => unreachable!("Sections only start at open tags and template expressions"),
+    };
+
+    let children: Vec<DocumentSymbol> = section
+        .children
+        .iter()
+        .map(|child| document_symbol(document, child))
+        .collect();
+
+    DocumentSymbol {
+        name,
+        detail,
+        kind,
+        tags: None,
+        deprecated: None,
+        range: section.range(document),
+        selection_range: document.area_to_range(node.area()),
+        children: (!children.is_empty()).then_some(children),
+    }
+}
+
+/// Folding ranges of multi-line elements, template constructs and comments. The line of the
+/// close tag or end of the construct is kept visible.
+pub fn folding_ranges(document: &Document) -> Vec<FoldingRange> {
+    fn add(document: &Document, sections: &[Section], ranges: &mut Vec<FoldingRange>) {
+        for section in sections {
+            let range = section.range(document);
+            let end_line = match section.closer {
+                Some(closer) => document
+                    .area_to_range(document.ast[closer].area())
+                    .start
+                    .line
+                    .saturating_sub(1),
+                None => range.end.line,
+            };
+            if end_line > range.start.line {
+                ranges.push(folding_range(range.start.line, end_line, None));
+            }
+            add(document, &section.children, ranges);
+        }
+    }
+
+    let mut ranges = Vec::new();
+    add(document, &sections(&document.ast), &mut ranges);
+
+    for node in &document.ast {
+        if let Node::Comment(comment) = node {
+            let range = document.area_to_range(&comment.area);
+            if range.end.line > range.start.line {
+                // The content of CDATA sections is text
+                let kind =
+                    (comment.kind != CommentKind::Cdata).then_some(FoldingRangeKind::Comment);
+                ranges.push(folding_range(range.start.line, range.end.line, kind));
+            }
+        }
+    }
+
+    ranges.sort_by_key(|range| (range.start_line, range.end_line));
+    ranges
+}
+
+fn folding_range(start_line: u32, end_line: u32, kind: Option<FoldingRangeKind>) -> FoldingRange {
+    FoldingRange {
+        start_line,
+        end_line,
+        kind,
+        ..Default::default()
+    }
+}
+
+/// Selection ranges that expand from the attribute at the position, to its tag, its element, and
+/// then the enclosing elements and template constructs
+pub fn selection_range(document: &Document, position: Position) -> SelectionRange {
+    let contains = |range: &Range| range.start <= position && position <= range.end;
+
+    let mut ranges = Vec::new();
+    let mut sections = sections(&document.ast);
+    while let Some(section) = sections
+        .into_iter()
+        .find(|section| contains(&section.range(document)))
+    {
+        ranges.push(section.range(document));
+
+        // The node at the position, if it's not in a nested section
+        let node = (section.start..=section.end).find(|index| {
+            contains(&document.area_to_range(document.ast[*index].area()))
+                && !section
+                    .children
+                    .iter()
+                    .any(|child| (child.start..=child.end).contains(index))
+        });
+        if let Some(index) = node {
+            let node = &document.ast[index];
+            ranges.push(document.area_to_range(node.area()));
+            if let Node::OpenTag(open_tag) = node {
+                ranges.extend(
+                    open_tag
+                        .attributes
+                        .iter()
+                        .map(|attribute| document.area_to_range(&attribute.area))
+                        .find(contains),
+                );
+            }
+        }
+
+        sections = section.children;
+    }
+
+    if ranges.is_empty() {
+        ranges.push(Range::new(position, position));
+    }
+    ranges.dedup();
+
+    let mut selection_range = None;
+    for range in ranges {
+        selection_range = Some(SelectionRange {
+            range,
+            parent: selection_range.map(Box::new),
+        });
+    }
+    selection_range.expect("There is at least one range")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::ResolvedConfig;
+    use htmlsnob::config::Config;
+    use htmlsnob::template_language::TemplateLanguage;
+    use tower_lsp::lsp_types::Url;
+
+    const TEXT: &str = r#"<main id="content" class="page wide">
+  {% if user %}
+    <p>
+      Hello <b>{{ user.name }}</b>
+    </p>
+  {% else %}
+    <a href="/login">Log in</a>
+  {% endif %}
+  <!--
+    Footer
+  -->
+  <br>
+</main>
+"#;
+
+    fn document() -> Document {
+        let resolved_config = ResolvedConfig {
+            config: Config::from_toml("", &htmlsnob_rules::registry()),
+            path: None,
+            template_language: TemplateLanguage::Jinja2,
+            error: None,
+        };
+        let uri = Url::parse("file:///tmp/index.html").unwrap();
+        let mut document = Document::new(uri, TEXT.to_string(), 1, resolved_config);
+        document.lint();
+        document
+    }
+
+    fn outline(symbols: &[DocumentSymbol], depth: usize, lines: &mut Vec<String>) {
+        for symbol in symbols {
+            lines.push(format!(
+                "{}{} {} {}-{}",
+                "  ".repeat(depth),
+                symbol.name,
+                symbol.detail.as_deref().unwrap_or("-"),
+                symbol.range.start.line,
+                symbol.range.end.line,
+            ));
+            outline(symbol.children.as_deref().unwrap_or(&[]), depth + 1, lines);
+        }
+    }
+
+    #[test]
+    fn document_symbols_of_elements_and_templates() {
+        let mut lines = Vec::new();
+        outline(&document_symbols(&document()), 0, &mut lines);
+
+        assert_eq!(
+            lines,
+            vec![
+                "main #content .page .wide 0-12",
+                "  {% if user %} - 1-4",
+                "    p - 2-4",
+                "      b - 3-3",
+                "  {% else %} - 5-7",
+                "    a - 6-6",
+                "  br - 11-11",
+            ]
+        );
+    }
+
+    #[test]
+    fn folding_ranges_keep_the_last_line_visible() {
+        let ranges: Vec<(u32, u32, Option<FoldingRangeKind>)> = folding_ranges(&document())
+            .into_iter()
+            .map(|range| (range.start_line, range.end_line, range.kind))
+            .collect();
+
+        assert_eq!(
+            ranges,
+            vec![
+                (0, 11, None),
+                (1, 4, None),
+                (2, 3, None),
+                (5, 6, None),
+                (8, 10, Some(FoldingRangeKind::Comment)),
+            ]
+        );
+    }
+
+    #[test]
+    fn selection_range_expands_to_parents() {
+        let mut selection_range = Some(selection_range(&document(), Position::new(6, 14)));
+        let mut ranges = Vec::new();
+        while let Some(range) = selection_range {
+            ranges.push(range.range);
+            selection_range = range.parent.map(|parent| *parent);
+        }
+
+        assert_eq!(
+            ranges,
+            vec![
+                Range::new(Position::new(6, 7), Position::new(6, 20)),
+                Range::new(Position::new(6, 4), Position::new(6, 21)),
+                Range::new(Position::new(6, 4), Position::new(6, 31)),
+                Range::new(Position::new(5, 2), Position::new(7, 13)),
+                Range::new(Position::new(0, 0), Position::new(12, 7)),
+            ]
+        );
+    }
+}
@@ -0,0 +1,296 @@
+use htmlsnob::ast::{Construct, Either, Node, StringArea};
+use std::collections::HashMap;
+use tower_lsp::lsp_types::{
+    DocumentHighlight, DocumentHighlightKind, LinkedEditingRanges, Position, Range, TextEdit,
+    WorkspaceEdit,
+};
+
+use crate::document::Document;
+
+/// Attributes that refer to ids, by name, and the prefix of the id in their values
+const ID_REFERENCES: [(&str, &str); 3] = [("id", ""), ("for", ""), ("href", "#")];
+
+/// Attributes whose values are whitespace separated lists of ids
+const ID_LIST_REFERENCES: [&str; 2] = ["aria-labelledby", "aria-describedby"];
+
+/// The open or close tag with its name at the position, and the tag it's paired with
+fn tag_pair(document: &Document, position: Position) -> Option<Vec<Range>> {
+    let (index, node) = document.ast.iter().enumerate().find(|(_, node)| {
+        document
+            .tag_name_range(node)
+            .is_some_and(|range| contains(&range, position))
+    })?;
+
+    let pair_index = match node {
+        Node::OpenTag(open_tag) => open_tag.close_tag_index,
+        Node::CloseTag(close_tag) => close_tag.open_tag_index,
+        _ => None,
+    };
+
+    // The pair of an open tag may be an implied close tag, which has no name in the document
+    let ranges = [Some(index), pair_index]
+        .into_iter()
+        .flatten()
+        .filter_map(|index| document.tag_name_range(&document.ast[index]))
+        .collect();
+    Some(ranges)
+}
+
+/// The names of an element's open and close tags, which are edited together
+pub fn linked_editing_ranges(
+    document: &Document,
+    position: Position,
+) -> Option<LinkedEditingRanges> {
+    let ranges = tag_pair(document, position)?;
+    if ranges.len() < 2 {
+        return None;
+    }
+
+    Some(LinkedEditingRanges {
+        ranges,
+        word_pattern: None,
+    })
+}
+
+/// The matching tags of the tag at the position, or the matching parts of the template construct,
+/// like the `if`, `else` and `endif` of a condition
+pub fn document_highlights(
+    document: &Document,
+    position: Position,
+) -> Option<Vec<DocumentHighlight>> {
+    let ranges = match tag_pair(document, position) {
+        Some(ranges) => ranges,
+        None => {
+            let index = document.ast.iter().position(|node| {
+                matches!(node, Node::TemplateExpression(_))
+                    && contains(&document.area_to_range(node.area()), position)
+            })?;
+            template_groups(&document.ast)
+                .into_iter()
+                .find(|group| group.contains(&index))?
+                .iter()
+                .map(|index| document.area_to_range(document.ast[*index].area()))
+                .collect()
+        }
+    };
+
+    Some(
+        ranges
+            .into_iter()
+            .map(|range| DocumentHighlight {
+                range,
+                kind: Some(DocumentHighlightKind::TEXT),
+            })
+            .collect(),
+    )
+}
+
+/// The indexes of the template expressions that belong together, like `if`, `else` and `endif`
+fn template_groups(ast: &[Node]) -> Vec<Vec<usize>> {
+    let mut groups = Vec::new();
+    let mut stack: Vec<(Construct, Vec<usize>)> = Vec::new();
+
+    for (index, node) in ast.iter().enumerate() {
+        let Node::TemplateExpression(expression) = node else {
+            continue;
+        };
+
+        let opener = match expression.kind {
+            Construct::If | Construct::Loop | Construct::Block | Construct::Switch => {
+                stack.push((expression.kind.clone(), vec![index]));
+                continue;
+            }
+            Construct::Else => {
+                if let Some((Construct::If | Construct::Loop, group)) = stack.last_mut() {
+                    group.push(index);
+                }
+                continue;
+            }
+            Construct::Case => {
+                if let Some((Construct::Switch, group)) = stack.last_mut() {
+                    group.push(index);
+                }
+                continue;
+            }
+            Construct::EndIf => Construct::If,
+            Construct::EndLoop => Construct::Loop,
+            Construct::EndBlock => Construct::Block,
+            Construct::EndSwitch => Construct::Switch,
+            _ => continue,
+        };
+
+        // Unclosed constructs inside the matching one are dropped
+        if let Some(position) = stack.iter().rposition(|(kind, _)| *kind == opener) {
+            stack.truncate(position + 1);
+            let (_, mut group) = stack.pop().expect("The matching construct is on the stack");
+            group.push(index);
+            groups.push(group);
+        }
+    }
+
+    groups
+}
+
+/// The id at the position, in an `id` attribute or in an attribute that refers to an id
+fn id_at(document: &Document, position: Position) -> Option<(String, Range)> {
+    id_references(document).find_map(|(string_area, prefix)| {
+        let range = document.area_to_range(&string_area.area);
+        let id = document.area_text(&string_area.area).strip_prefix(prefix)?;
+        contains(&range, position).then(|| (id.to_string(), range))
+    })
+}
+
+/// The values of `id` attributes and of attributes that refer to ids, with the prefix of the id.
+/// Lists of ids, like in `aria-labelledby`, give each id, while other values are only ids when
+/// they have no template expressions.
+fn id_references(document: &Document) -> impl Iterator<Item = (&StringArea, &'static str)> {
+    document
+        .ast
+        .iter()
+        .filter_map(|node| match node {
+            Node::OpenTag(open_tag) => Some(&open_tag.attributes),
+            _ => None,
+        })
+        .flatten()
+        .flat_map(|attribute| {
+            let mut ids: Vec<(&StringArea, &'static str)> = Vec::new();
+            let (Either::Left(name), Some(value)) = (&attribute.name, &attribute.value) else {
+                return ids;
+            };
+            let name = document.area_text(&name.area).to_lowercase();
+
+            if ID_LIST_REFERENCES.contains(&name.as_str()) {
+                ids.extend(value.words().map(|word| (word, "")));
+            } else if let Some((_, prefix)) = ID_REFERENCES.iter().find(|(n, _)| *n == name) {
+                if let [Either::Left(string_area)] = value.parts.as_slice() {
+                    ids.push((string_area, *prefix));
+                }
+            }
+            ids
+        })
+}
+
+/// The range of the id at the position, for `textDocument/prepareRename`
+pub fn prepare_rename(document: &Document, position: Position) -> Option<Range> {
+    let (id, range) = id_at(document, position)?;
+    let prefix_length =
+        range.end.character - range.start.character - id.encode_utf16().count() as u32;
+
+    Some(Range {
+        start: Position::new(range.start.line, range.start.character + prefix_length),
+        end: range.end,
+    })
+}
+
+/// Renames the id at the position in its `id` attribute and in every attribute that refers to it
+pub fn rename(document: &Document, position: Position, new_name: &str) -> Option<WorkspaceEdit> {
+    let (id, _) = id_at(document, position)?;
+
+    let edits: Vec<TextEdit> = id_references(document)
+        .filter(|(string_area, prefix)| {
+            document.area_text(&string_area.area).strip_prefix(prefix) == Some(id.as_str())
+        })
+        .map(|(string_area, prefix)| TextEdit {
+            range: document.area_to_range(&string_area.area),
+            new_text: format!("{prefix}{new_name}"),
+        })
+        .collect();
+
+    Some(WorkspaceEdit {
+        changes: Some(HashMap::from([(document.uri.clone(), edits)])),
+        ..Default::default()
+    })
+}
+
+fn contains(range: &Range, position: Position) -> bool {
+    range.start <= position && position <= range.end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::ResolvedConfig;
+    use htmlsnob::config::Config;
+    use htmlsnob::template_language::TemplateLanguage;
+    use tower_lsp::lsp_types::Url;
+
+    const TEXT: &str = r##"<form>
+  {% if show %}
+    <label for="user-name">Name</label>
+    <input id="user-name" aria-describedby="user-name-hint user-name">
+  {% elif other %}
+    <a href="#user-name">Name</a>
+  {% endif %}
+</form>
+"##;
+
+    fn document() -> Document {
+        let resolved_config = ResolvedConfig {
+            config: Config::from_toml("", &htmlsnob_rules::registry()),
+            path: None,
+            template_language: TemplateLanguage::Jinja2,
+            error: None,
+        };
+        let uri = Url::parse("file:///tmp/index.html").unwrap();
+        let mut document = Document::new(uri, TEXT.to_string(), 1, resolved_config);
+        document.lint();
+        document
+    }
+
+    fn range(line: u32, start: u32, end: u32) -> Range {
+        Range::new(Position::new(line, start), Position::new(line, end))
+    }
+
+    #[test]
+    fn linked_editing_of_tag_names() {
+        let document = document();
+
+        let ranges = linked_editing_ranges(&document, Position::new(7, 4)).unwrap();
+        assert_eq!(ranges.ranges, vec![range(7, 2, 6), range(0, 1, 5)]);
+
+        assert!(linked_editing_ranges(&document, Position::new(3, 6)).is_none());
+        assert!(linked_editing_ranges(&document, Position::new(2, 15)).is_none());
+    }
+
+    #[test]
+    fn highlights_of_template_constructs() {
+        let highlights = document_highlights(&document(), Position::new(4, 5)).unwrap();
+        let ranges: Vec<Range> = highlights.iter().map(|highlight| highlight.range).collect();
+
+        assert_eq!(
+            ranges,
+            vec![range(1, 2, 15), range(4, 2, 18), range(6, 2, 13)]
+        );
+    }
+
+    #[test]
+    fn rename_id_and_references() {
+        let document = document();
+        assert_eq!(
+            prepare_rename(&document, Position::new(5, 15)),
+            Some(range(5, 14, 23))
+        );
+        assert_eq!(
+            prepare_rename(&document, Position::new(3, 62)),
+            Some(range(3, 59, 68))
+        );
+
+        let edit = rename(&document, Position::new(3, 16), "full-name").unwrap();
+        let mut edits = edit.changes.unwrap().remove(&document.uri).unwrap();
+        edits.sort_by_key(|edit| edit.range.start);
+        let edits: Vec<(Range, &str)> = edits
+            .iter()
+            .map(|edit| (edit.range, edit.new_text.as_str()))
+            .collect();
+
+        assert_eq!(
+            edits,
+            vec![
+                (range(2, 16, 25), "full-name"),
+                (range(3, 15, 24), "full-name"),
+                (range(3, 59, 68), "full-name"),
+                (range(5, 13, 23), "#full-name"),
+            ]
+        );
+    }
+}
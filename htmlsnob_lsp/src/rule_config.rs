@@ -0,0 +1,106 @@
+use htmlsnob::config::Config;
+use htmlsnob::rule_utils::deserialize_regex::DeserializableRegex;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// The index of the rule with the given name (or kind, if it has no name) in the config
+pub fn rule_index(config: &Config, rule_name: &str) -> Option<usize> {
+    (0..config.rules.len()).find(|index| config.rule_name(*index) == rule_name)
+}
+
+pub fn rule_kind(config: &Config, index: usize) -> &str {
+    config.rule_values[index]
+        .get("kind")
+        .and_then(|kind| kind.as_str())
+        .unwrap_or("")
+}
+
+/// The settings of the first rule of the kind, deserialized into `T`
+pub fn rule_settings<T: for<'de> Deserialize<'de>>(config: &Config, kind: &str) -> Option<T> {
+    config
+        .rule_values
+        .iter()
+        .find(|value| value.get("kind").and_then(|kind| kind.as_str()) == Some(kind))
+        .and_then(|value| value.clone().try_into().ok())
+}
+
+/// The settings of the `attribute_name_whitelist` rule
+#[derive(Debug, Deserialize)]
+pub struct AttributeNameWhitelist {
+    pub globals: Vec<DeserializableRegex>,
+    pub tags: HashMap<String, Vec<DeserializableRegex>>,
+}
+
+impl AttributeNameWhitelist {
+    pub fn from_config(config: &Config) -> Option<Self> {
+        rule_settings(config, "attribute_name_whitelist")
+    }
+
+    /// Whether the attribute is allowed on the tag, None if the tag's attributes aren't restricted
+    pub fn allows(&self, tag: &str, attribute: &str) -> Option<bool> {
+        let whitelist = self.tags.get(tag)?;
+
+        Some(
+            self.globals
+                .iter()
+                .chain(whitelist)
+                .any(|regex| regex.is_match(attribute)),
+        )
+    }
+}
+
+/// The settings of the `tag_name_whitelist` rule
+#[derive(Debug, Deserialize)]
+pub struct TagNameWhitelist {
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// The settings of the `attribute_value_whitelist` rule
+#[derive(Debug, Deserialize)]
+pub struct AttributeValueWhitelist {
+    pub tag_attributes: HashMap<String, HashMap<String, Vec<String>>>,
+    pub global_attributes: HashMap<String, Vec<String>>,
+}
+
+impl AttributeValueWhitelist {
+    /// The allowed values of the attribute on the tag, the tag specific ones take precedence
+    pub fn values(&self, tag: &str, attribute: &str) -> Option<&Vec<String>> {
+        self.tag_attributes
+            .get(tag)
+            .and_then(|attributes| attributes.get(attribute))
+            .or_else(|| self.global_attributes.get(attribute))
+    }
+}
+
+/// The settings of the `class_whitelist` rule
+#[derive(Debug, Deserialize)]
+pub struct ClassWhitelist {
+    pub tags: HashMap<String, Vec<String>>,
+}
+
+/// The settings of rules that only have an `order`, like `attributes_order` and `class_order`
+#[derive(Debug, Deserialize)]
+pub struct Order<T> {
+    pub order: Vec<T>,
+}
+
+impl Order<DeserializableRegex> {
+    /// The index of the first pattern in the order that matches the name
+    pub fn position(&self, name: &str) -> Option<usize> {
+        self.order.iter().position(|regex| regex.is_match(name))
+    }
+}
+
+/// The settings of rules that only have a casing `style`, like `tag_name_casing`
+#[derive(Debug, Deserialize)]
+pub struct Casing<T> {
+    pub style: T,
+}
+
+/// Whether a pattern from the config only matches itself, so it can be suggested as is
+pub fn is_literal(pattern: &str) -> bool {
+    !pattern.contains([
+        '.', '*', '+', '?', '(', ')', '[', ']', '{', '}', '|', '^', '$', '\\',
+    ])
+}
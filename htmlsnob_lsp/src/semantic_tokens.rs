@@ -0,0 +1,353 @@
+use htmlsnob::ast::{Area, CommentKind, Construct, Either, Node, TemplateExpression};
+use htmlsnob::template_language::TemplateLanguage;
+use regex::Regex;
+use tower_lsp::lsp_types::{
+    Position, Range, SemanticToken, SemanticTokenType, SemanticTokensLegend,
+};
+
+use crate::document::Document;
+
+/// The token types, in the order of their indexes in the legend
+const TOKEN_TYPES: [SemanticTokenType; 7] = [
+    SemanticTokenType::TYPE,
+    SemanticTokenType::PROPERTY,
+    SemanticTokenType::STRING,
+    SemanticTokenType::OPERATOR,
+    SemanticTokenType::KEYWORD,
+    SemanticTokenType::VARIABLE,
+    SemanticTokenType::COMMENT,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TokenType {
+    TagName,
+    AttributeName,
+    AttributeValue,
+    /// The delimiters of template expressions, like `{%` and `%}`
+    Delimiter,
+    /// Template constructs like `if`, `for` and `block`, and statements
+    ControlFlow,
+    /// Template expressions that output a value, like `{{ name }}`
+    Output,
+    Comment,
+}
+
+pub fn legend() -> SemanticTokensLegend {
+    SemanticTokensLegend {
+        token_types: TOKEN_TYPES.to_vec(),
+        token_modifiers: Vec::new(),
+    }
+}
+
+/// The tokens of the tags, attributes, comments and template expressions of the document, only
+/// the ones that overlap the range if given. Template expressions inside attribute names and
+/// values are tokens too, which the HTML grammars of editors can't highlight.
+pub fn semantic_tokens(document: &Document, range: Option<Range>) -> Vec<SemanticToken> {
+    let constructs = TemplateLanguage::constructs(&document.config().options.template_language);
+    let mut tokens = Tokens {
+        lines: Lines::new(&document.text),
+        constructs,
+        tokens: Vec::new(),
+    };
+
+    for node in &document.ast {
+        match node {
+            Node::OpenTag(open_tag) => {
+                tokens.tag_name(document, node);
+                for attribute in &open_tag.attributes {
+                    match &attribute.name {
+                        Either::Left(name) => tokens.push(&name.area, TokenType::AttributeName),
+                        Either::Right(expression) => tokens.template_expression(expression),
+                    }
+
+                    let Some(value) = &attribute.value else {
+                        continue;
+                    };
+                    for part in &value.parts {
+                        match part {
+                            Either::Left(string) => {
+                                tokens.push(&string.area, TokenType::AttributeValue)
+                            }
+                            Either::Right(expression) => tokens.template_expression(expression),
+                        }
+                    }
+
+                    // The quotes are parts of the value, but not of its parts
+                    let (start, end) = tokens.lines.offsets(&value.area);
+                    if value.start_quote.is_some() {
+                        tokens.push_offsets(start, start + 1, TokenType::AttributeValue);
+                    }
+                    if value.end_quote.is_some() && end > start + 1 {
+                        tokens.push_offsets(end - 1, end, TokenType::AttributeValue);
+                    }
+                }
+            }
+            Node::CloseTag(_) => tokens.tag_name(document, node),
+            // The content of CDATA sections is text
+            Node::Comment(comment) if comment.kind == CommentKind::Cdata => {}
+            Node::Comment(comment) => tokens.push(&comment.area, TokenType::Comment),
+            Node::TemplateExpression(expression) => tokens.template_expression(expression),
+            Node::Doctype(_) | Node::Text(_) => {}
+        }
+    }
+
+    let mut tokens = tokens.tokens;
+    tokens.sort_by_key(|(start, ..)| *start);
+    if let Some(range) = range {
+        tokens.retain(|(start, length, _)| {
+            let end = Position::new(start.line, start.character + length);
+            range.start <= end && *start <= range.end
+        });
+    }
+
+    encode(&tokens)
+}
+
+/// Single line tokens, by start position and length in UTF-16 code units
+struct Tokens<'a> {
+    lines: Lines<'a>,
+    constructs: Vec<(Regex, Regex, Regex, Construct)>,
+    tokens: Vec<(Position, u32, TokenType)>,
+}
+
+impl Tokens<'_> {
+    fn push(&mut self, area: &Area, token_type: TokenType) {
+        let (start, end) = self.lines.offsets(area);
+        self.push_offsets(start, end, token_type);
+    }
+
+    /// Adds the text between the byte offsets as tokens, one per line, as clients may not
+    /// support tokens that span lines
+    fn push_offsets(&mut self, start: usize, end: usize, token_type: TokenType) {
+        let text = self.lines.text;
+        let mut line_start = start;
+        for line in text[start..end.max(start)].split('\n') {
+            let length = line.trim_end_matches('\r').encode_utf16().count() as u32;
+            if length > 0 {
+                self.tokens
+                    .push((self.lines.position(line_start), length, token_type));
+            }
+            line_start += line.len() + 1;
+        }
+    }
+
+    fn tag_name(&mut self, document: &Document, node: &Node) {
+        if let Some(range) = document.tag_name_range(node) {
+            let length = range.end.character.saturating_sub(range.start.character);
+            self.tokens.push((range.start, length, TokenType::TagName));
+        }
+    }
+
+    /// The delimiters and the trimmed content of the expression, comments are one token
+    fn template_expression(&mut self, expression: &TemplateExpression) {
+        let (start, end) = self.lines.offsets(&expression.area);
+        let content_type = match expression.kind {
+            Construct::Comment => return self.push_offsets(start, end, TokenType::Comment),
+            Construct::Expression => TokenType::Output,
+            _ => TokenType::ControlFlow,
+        };
+
+        let text = &self.lines.text[start..end];
+        let (open_end, close_start) = self.delimiters(text);
+        let content = &text[open_end..close_start];
+        let content_start = open_end + (content.len() - content.trim_start().len());
+        let content_end = open_end + content.trim_end().len();
+
+        self.push_offsets(start, start + open_end, TokenType::Delimiter);
+        if content_start < content_end {
+            self.push_offsets(start + content_start, start + content_end, content_type);
+        }
+        self.push_offsets(start + close_start, end, TokenType::Delimiter);
+    }
+
+    /// The end of the opening and the start of the closing delimiter of an expression, with the
+    /// delimiters of the construct of the template language that matches it
+    fn delimiters(&self, text: &str) -> (usize, usize) {
+        let Some((_, open, close, _)) = self
+            .constructs
+            .iter()
+            .find(|(start, ..)| start.is_match(text))
+        else {
+            return (0, text.len());
+        };
+
+        let open_end = open.find(text).map_or(0, |open| open.end());
+        // The shortest suffix that is a closing delimiter, missing when the expression is
+        // unterminated
+        let close_start = text
+            .char_indices()
+            .rev()
+            .take(4)
+            .map(|(index, _)| index)
+            .filter(|index| *index >= open_end)
+            .find(|index| {
+                close
+                    .find(&text[*index..])
+                    .is_some_and(|close| index + close.end() == text.len())
+            })
+            .unwrap_or(text.len());
+
+        (open_end, close_start)
+    }
+}
+
+/// Converts between the character based positions of the AST, byte offsets and LSP positions,
+/// without scanning the text from the start for every token
+struct Lines<'a> {
+    text: &'a str,
+    /// The byte offsets of the starts of the lines
+    starts: Vec<usize>,
+}
+
+impl<'a> Lines<'a> {
+    fn new(text: &'a str) -> Self {
+        let starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+        Self { text, starts }
+    }
+
+    fn offset(&self, position: &htmlsnob::ast::Position) -> usize {
+        let Some(line_start) = self.starts.get(position.line) else {
+            return self.text.len();
+        };
+        self.text[*line_start..]
+            .char_indices()
+            .nth(position.column)
+            .map_or(self.text.len(), |(index, _)| line_start + index)
+    }
+
+    fn offsets(&self, area: &Area) -> (usize, usize) {
+        let start = self.offset(&area.start);
+        (start, self.offset(&area.end).max(start))
+    }
+
+    fn position(&self, offset: usize) -> Position {
+        let line = self.starts.partition_point(|start| *start <= offset) - 1;
+        let character = self.text[self.starts[line]..offset].encode_utf16().count();
+        Position::new(line as u32, character as u32)
+    }
+}
+
+/// Encodes the tokens relative to the previous one, as the protocol requires
+fn encode(tokens: &[(Position, u32, TokenType)]) -> Vec<SemanticToken> {
+    let mut previous = Position::new(0, 0);
+    tokens
+        .iter()
+        .map(|(start, length, token_type)| {
+            let delta_line = start.line - previous.line;
+            let delta_start = match delta_line {
+                0 => start.character - previous.character,
+                _ => start.character,
+            };
+            previous = *start;
+
+            SemanticToken {
+                delta_line,
+                delta_start,
+                length: *length,
+                token_type: *token_type as u32,
+                token_modifiers_bitset: 0,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::ResolvedConfig;
+    use htmlsnob::config::Config;
+    use tower_lsp::lsp_types::Url;
+
+    const TEXT: &str = r#"<a href="{{ url }}" class="x {% if on %}on{% endif %}">
+  {# A note #}
+  <!-- Two
+  lines -->
+</a>"#;
+
+    fn tokens(range: Option<Range>) -> Vec<(u32, u32, String, u32)> {
+        let resolved_config = ResolvedConfig {
+            config: Config::from_toml("", &htmlsnob_rules::registry()),
+            path: None,
+            template_language: TemplateLanguage::Jinja2,
+            error: None,
+        };
+        let uri = Url::parse("file:///tmp/index.html").unwrap();
+        let mut document = Document::new(uri, TEXT.to_string(), 1, resolved_config);
+        document.lint();
+
+        // Decoded to absolute positions and the text of the token
+        let lines: Vec<Vec<u16>> = TEXT
+            .lines()
+            .map(|line| line.encode_utf16().collect())
+            .collect();
+        let mut position = Position::new(0, 0);
+        semantic_tokens(&document, range)
+            .iter()
+            .map(|token| {
+                position = match token.delta_line {
+                    0 => Position::new(position.line, position.character + token.delta_start),
+                    delta_line => Position::new(position.line + delta_line, token.delta_start),
+                };
+                let line = &lines[position.line as usize];
+                let start = position.character as usize;
+                let text = String::from_utf16(&line[start..start + token.length as usize]).unwrap();
+                (position.line, position.character, text, token.token_type)
+            })
+            .collect()
+    }
+
+    fn token(
+        line: u32,
+        character: u32,
+        text: &str,
+        token_type: TokenType,
+    ) -> (u32, u32, String, u32) {
+        (line, character, text.to_string(), token_type as u32)
+    }
+
+    #[test]
+    fn tokens_of_tags_attributes_and_template_expressions() {
+        use TokenType::*;
+
+        assert_eq!(
+            tokens(None),
+            vec![
+                token(0, 1, "a", TagName),
+                token(0, 3, "href", AttributeName),
+                token(0, 8, "\"", AttributeValue),
+                token(0, 9, "{{", Delimiter),
+                token(0, 12, "url", Output),
+                token(0, 16, "}}", Delimiter),
+                token(0, 18, "\"", AttributeValue),
+                token(0, 20, "class", AttributeName),
+                token(0, 26, "\"", AttributeValue),
+                token(0, 27, "x", AttributeValue),
+                token(0, 29, "{%", Delimiter),
+                token(0, 32, "if on", ControlFlow),
+                token(0, 38, "%}", Delimiter),
+                token(0, 40, "on", AttributeValue),
+                token(0, 42, "{%", Delimiter),
+                token(0, 45, "endif", ControlFlow),
+                token(0, 51, "%}", Delimiter),
+                token(0, 53, "\"", AttributeValue),
+                token(1, 2, "{# A note #}", Comment),
+                token(2, 2, "<!-- Two", Comment),
+                token(3, 0, "  lines -->", Comment),
+                token(4, 2, "a", TagName),
+            ]
+        );
+    }
+
+    #[test]
+    fn tokens_in_a_range() {
+        let range = Range::new(Position::new(2, 0), Position::new(3, 0));
+        let texts: Vec<String> = tokens(Some(range))
+            .into_iter()
+            .map(|(_, _, text, _)| text)
+            .collect();
+
+        assert_eq!(texts, vec!["<!-- Two", "  lines -->"]);
+    }
+}
@@ -0,0 +1,325 @@
+use htmlsnob::config::{Config, ConfigError};
+use htmlsnob::template_language::TemplateLanguage;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, Position, Range, Url};
+
+use crate::document::offset_to_position;
+
+pub const CONFIG_FILE_NAME: &str = ".htmlsnob.toml";
+
+/// Settings from the client, given as `initializationOptions` and with
+/// `workspace/didChangeConfiguration`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    /// The config file for all documents, instead of the nearest `.htmlsnob.toml`. Relative paths
+    /// are resolved against the workspace folder.
+    pub config_path: Option<PathBuf>,
+    /// The names of the rules to run, all rules of the config run when not set
+    pub enabled_rules: Option<Vec<String>>,
+    /// Severities by rule name, overriding the ones in the config
+    pub severities: HashMap<String, String>,
+    /// Maps file globs to template languages, in addition to the `template_languages` of the config
+    pub template_languages: HashMap<String, TemplateLanguage>,
+    #[serde(skip)]
+    pub workspace_folder: Option<PathBuf>,
+}
+
+impl Settings {
+    /// Parses the settings, which may be in an `htmlsnob` section, like VS Code sends them
+    pub fn from_value(
+        mut value: serde_json::Value,
+        workspace_folder: Option<PathBuf>,
+    ) -> Result<Self, serde_json::Error> {
+        if let Some(section) = value.get_mut("htmlsnob") {
+            value = section.take();
+        }
+
+        let mut settings = match value {
+            serde_json::Value::Null => Self::default(),
+            value => Self::deserialize(value)?,
+        };
+        settings.workspace_folder = workspace_folder;
+        Ok(settings)
+    }
+
+    /// The configured config file, made absolute
+    pub fn config_path(&self) -> Option<PathBuf> {
+        let config_path = self.config_path.as_ref()?;
+        Some(match &self.workspace_folder {
+            Some(folder) if config_path.is_relative() => folder.join(config_path),
+            _ => config_path.clone(),
+        })
+    }
+
+    /// Applies the rule selection, severity overrides and template languages to the config
+    fn apply(&self, config: Config) -> Result<Config, ConfigError> {
+        let rule_values = (0..config.rules.len())
+            .filter(|index| {
+                self.enabled_rules
+                    .as_ref()
+                    .is_none_or(|enabled_rules| enabled_rules.contains(&config.rule_name(*index)))
+            })
+            .map(|index| {
+                let mut rule_value = config.rule_values[index].clone();
+                let severity = self.severities.get(&config.rule_name(index));
+                if let (Some(severity), Some(table)) = (severity, rule_value.as_table_mut()) {
+                    table.insert(
+                        "severity".to_string(),
+                        toml::Value::String(severity.clone()),
+                    );
+                }
+                rule_value
+            })
+            .collect();
+
+        let mut options = config.options.clone();
+        options
+            .template_languages
+            .extend(self.template_languages.clone());
+
+        Config::try_from_rule_values(rule_values, options, &htmlsnob_rules::registry())
+    }
+}
+
+/// The config used for a document, found by walking up from the document to the nearest
+/// `.htmlsnob.toml`. Documents without one use the default config.
+#[derive(Debug)]
+pub struct ResolvedConfig {
+    pub config: Config,
+    /// The `.htmlsnob.toml` the config was loaded from
+    pub path: Option<PathBuf>,
+    /// The `template_language` of the config, used when detection finds nothing
+    pub template_language: TemplateLanguage,
+    /// Why the config file couldn't be used, the default config is used instead
+    pub error: Option<ConfigError>,
+}
+
+impl Default for ResolvedConfig {
+    fn default() -> Self {
+        Self::from_config(default_config(), None, None)
+    }
+}
+
+impl ResolvedConfig {
+    /// The config for the document, from the configured config file, or the nearest
+    /// `.htmlsnob.toml`, or the default config. Errors are kept, they are reported as diagnostics.
+    pub fn resolve(uri: &Url, settings: &Settings) -> Self {
+        Self::load(Self::config_path(uri, settings), settings)
+    }
+
+    /// The config file of the document, the configured one or the nearest `.htmlsnob.toml`
+    pub fn config_path(uri: &Url, settings: &Settings) -> Option<PathBuf> {
+        settings.config_path().or_else(|| {
+            uri.to_file_path()
+                .ok()
+                .and_then(|path| find_config_file(&path))
+        })
+    }
+
+    /// The config from the config file, or the default config if there is none
+    pub fn load(path: Option<PathBuf>, settings: &Settings) -> Self {
+        let config = match &path {
+            Some(path) => {
+                Config::try_from_file(path.to_str().unwrap_or(""), &htmlsnob_rules::registry())
+            }
+            None => Ok(default_config()),
+        };
+
+        match config.and_then(|config| settings.apply(config)) {
+            Ok(config) => Self::from_config(config, path, None),
+            Err(error) => Self::from_config(default_config(), path, Some(error)),
+        }
+    }
+
+    fn from_config(config: Config, path: Option<PathBuf>, error: Option<ConfigError>) -> Self {
+        Self {
+            template_language: config.options.template_language.clone(),
+            config,
+            path,
+            error,
+        }
+    }
+
+    /// The diagnostic of the config error, in the config file
+    pub fn config_file_diagnostic(&self) -> Option<(Url, Diagnostic)> {
+        let error = self.error.as_ref()?;
+        let path = self.path.as_ref()?;
+        let uri = Url::from_file_path(path).ok()?;
+        let text = std::fs::read_to_string(path).unwrap_or_default();
+
+        let range = match error.span.clone() {
+            Some(span) => Range {
+                start: offset_to_position(&text, span.start),
+                end: offset_to_position(&text, span.end),
+            },
+            None => Range::default(),
+        };
+
+        Some((
+            uri,
+            Diagnostic {
+                range,
+                severity: Some(DiagnosticSeverity::ERROR),
+                source: Some(crate::diagnostics::SOURCE.to_string()),
+                message: error.message.clone(),
+                ..Default::default()
+            },
+        ))
+    }
+
+    /// The diagnostic of the config error, at the start of a document that uses the config
+    pub fn document_diagnostic(&self) -> Option<Diagnostic> {
+        let error = self.error.as_ref()?;
+        let path = self
+            .path
+            .as_ref()
+            .map_or(String::new(), |path| format!(" `{}`", path.display()));
+
+        Some(Diagnostic {
+            range: Range::new(Position::new(0, 0), Position::new(0, 0)),
+            severity: Some(DiagnosticSeverity::ERROR),
+            source: Some(crate::diagnostics::SOURCE.to_string()),
+            message: format!(
+                "Invalid htmlsnob config{path}, using the default config: {}",
+                error.message
+            ),
+            ..Default::default()
+        })
+    }
+
+    /// The path of the document relative to the directory of the config file, which is what the
+    /// `template_languages` globs are matched against
+    pub fn relative_path(&self, uri: &Url) -> String {
+        let Ok(file_path) = uri.to_file_path() else {
+            return uri.path().to_string();
+        };
+
+        let relative = self
+            .path
+            .as_ref()
+            .and_then(|path| path.parent())
+            .and_then(|root| file_path.strip_prefix(root).ok());
+
+        relative
+            .unwrap_or(&file_path)
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
+fn find_config_file(file_path: &Path) -> Option<PathBuf> {
+    file_path
+        .ancestors()
+        .skip(1)
+        .map(|directory| directory.join(CONFIG_FILE_NAME))
+        .find(|path| path.is_file())
+}
+
+/// The default config of the CLI, with the attribute names from the HTML spec and the Tailwind
+/// CSS class order
+fn default_config() -> Config {
+    let default_config = [
+        &htmlsnob_rules::default_config_toml(),
+        include_str!("../../default_config/attribute_name_whitelist.toml"),
+        include_str!("../../default_config/class_order.toml"),
+    ]
+    .join("\n");
+
+    Config::from_toml(&default_config, &htmlsnob_rules::registry())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn config_dir(name: &str, config: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("htmlsnob_lsp_{name}_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(CONFIG_FILE_NAME), config).unwrap();
+        dir
+    }
+
+    #[test]
+    fn settings_in_a_section() {
+        let settings = Settings::from_value(
+            json!({ "htmlsnob": { "configPath": "config.toml", "enabledRules": ["a"] } }),
+            Some(PathBuf::from("/workspace")),
+        )
+        .unwrap();
+
+        assert_eq!(
+            settings.config_path(),
+            Some(PathBuf::from("/workspace/config.toml"))
+        );
+        assert_eq!(settings.enabled_rules, Some(vec!["a".to_string()]));
+        assert!(Settings::from_value(json!({ "enabledRules": 1 }), None).is_err());
+    }
+
+    #[test]
+    fn settings_override_the_config() {
+        let dir = config_dir(
+            "override",
+            r#"
+            [[rules]]
+            kind = "tag_name_casing"
+            style = "lower"
+
+            [[rules]]
+            kind = "attribute_name_casing_style"
+            name = "attribute_casing"
+            style = "kebab_case"
+            "#,
+        );
+        let uri = Url::from_file_path(dir.join("index.html")).unwrap();
+        let settings = Settings {
+            enabled_rules: Some(vec!["attribute_casing".to_string()]),
+            severities: HashMap::from([("attribute_casing".to_string(), "hint".to_string())]),
+            ..Default::default()
+        };
+
+        let resolved_config = ResolvedConfig::resolve(&uri, &settings);
+        assert_eq!(resolved_config.error, None);
+        assert_eq!(resolved_config.config.rules.len(), 1);
+        assert_eq!(
+            resolved_config.config.rule_values[0].get("severity"),
+            Some(&toml::Value::String("hint".to_string()))
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn invalid_config_is_reported() {
+        let dir = config_dir(
+            "invalid",
+            "indent_size = 2\n# [[rules]] are below\n[[rules]]\nkind = \"tag_name_casing\"\nstyle = \"sideways\"\n",
+        );
+        let uri = Url::from_file_path(dir.join("index.html")).unwrap();
+
+        let resolved_config = ResolvedConfig::resolve(&uri, &Settings::default());
+        assert!(resolved_config.error.is_some());
+        // The default config is used instead
+        assert!(!resolved_config.config.rules.is_empty());
+
+        let (config_uri, diagnostic) = resolved_config.config_file_diagnostic().unwrap();
+        assert_eq!(
+            config_uri,
+            Url::from_file_path(dir.join(CONFIG_FILE_NAME)).unwrap()
+        );
+        assert_eq!(
+            diagnostic.range,
+            Range::new(Position::new(2, 0), Position::new(4, 18))
+        );
+        assert!(resolved_config
+            .document_diagnostic()
+            .unwrap()
+            .message
+            .starts_with("Invalid htmlsnob config"));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
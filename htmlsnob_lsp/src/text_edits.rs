@@ -0,0 +1,156 @@
+use tower_lsp::lsp_types::{Range, TextEdit};
+
+use crate::document::offset_to_position;
+
+/// Above this many compared line pairs, the changed lines are replaced as a single edit instead
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+/// Computes line based edits that turn `old` into `new`, so the client keeps the cursor, folds
+/// and undo history of the lines that did not change.
+pub fn diff(old: &str, new: &str) -> Vec<TextEdit> {
+    let old_lines: Vec<&str> = old.split_inclusive('\n').collect();
+    let new_lines: Vec<&str> = new.split_inclusive('\n').collect();
+
+    let prefix = old_lines
+        .iter()
+        .zip(&new_lines)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = old_lines[prefix..]
+        .iter()
+        .rev()
+        .zip(new_lines[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let old_middle = &old_lines[prefix..old_lines.len() - suffix];
+    let new_middle = &new_lines[prefix..new_lines.len() - suffix];
+
+    let hunks = if old_middle.len() * new_middle.len() > MAX_DIFF_CELLS {
+        vec![Hunk {
+            old: 0..old_middle.len(),
+            new: 0..new_middle.len(),
+        }]
+    } else {
+        hunks(old_middle, new_middle)
+    };
+
+    // Byte offset of the start of every old line, plus the end of the text
+    let mut line_offsets = vec![0];
+    for line in &old_lines {
+        line_offsets.push(line_offsets.last().unwrap() + line.len());
+    }
+
+    hunks
+        .into_iter()
+        .map(|hunk| TextEdit {
+            range: Range {
+                start: offset_to_position(old, line_offsets[prefix + hunk.old.start]),
+                end: offset_to_position(old, line_offsets[prefix + hunk.old.end]),
+            },
+            new_text: new_middle[hunk.new].concat(),
+        })
+        .collect()
+}
+
+struct Hunk {
+    old: std::ops::Range<usize>,
+    new: std::ops::Range<usize>,
+}
+
+/// Groups the lines that are not part of the longest common subsequence into hunks
+fn hunks(old: &[&str], new: &[&str]) -> Vec<Hunk> {
+    // lengths[i][j] is the length of the longest common subsequence of old[i..] and new[j..]
+    let mut lengths = vec![vec![0u32; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i][j] = if old[i] == new[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut hunks: Vec<Hunk> = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            i += 1;
+            j += 1;
+            continue;
+        }
+
+        let (removed, added) =
+            if j == new.len() || (i < old.len() && lengths[i + 1][j] >= lengths[i][j + 1]) {
+                (1, 0)
+            } else {
+                (0, 1)
+            };
+
+        match hunks.last_mut() {
+            Some(hunk) if hunk.old.end == i && hunk.new.end == j => {
+                hunk.old.end += removed;
+                hunk.new.end += added;
+            }
+            _ => hunks.push(Hunk {
+                old: i..i + removed,
+                new: j..j + added,
+            }),
+        }
+        i += removed;
+        j += added;
+    }
+
+    hunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::position_to_offset;
+
+    /// Applies the edits back to front, like a client would
+    fn apply(text: &str, edits: &[TextEdit]) -> String {
+        let mut result = text.to_string();
+        for edit in edits.iter().rev() {
+            let start = position_to_offset(text, edit.range.start);
+            let end = position_to_offset(text, edit.range.end);
+            result.replace_range(start..end, &edit.new_text);
+        }
+        result
+    }
+
+    #[test]
+    fn no_changes() {
+        assert!(diff("<p>\n</p>\n", "<p>\n</p>\n").is_empty());
+    }
+
+    #[test]
+    fn only_changed_lines_are_edited() {
+        let old = "<div>\n<p>a</p>\n  <p>b</p>\n<p>c</p>\n</div>\n";
+        let new = "<div>\n  <p>a</p>\n  <p>b</p>\n  <p>c</p>\n</div>\n";
+        let edits = diff(old, new);
+
+        assert_eq!(edits.len(), 2);
+        assert_eq!(edits[0].range.start.line, 1);
+        assert_eq!(edits[0].range.end.line, 2);
+        assert_eq!(edits[0].new_text, "  <p>a</p>\n");
+        assert_eq!(apply(old, &edits), new);
+    }
+
+    #[test]
+    fn insertions_and_deletions() {
+        let cases = [
+            ("<p>a</p>", "<p>\n  a\n</p>\n"),
+            ("<p>\n\n\n</p>\n", "<p></p>\n"),
+            ("a\nb\nc\n", "x\na\nc\ny"),
+            ("", "<p></p>\n"),
+            ("<p></p>\n", ""),
+        ];
+
+        for (old, new) in cases {
+            assert_eq!(apply(old, &diff(old, new)), new, "{old:?} -> {new:?}");
+        }
+    }
+}
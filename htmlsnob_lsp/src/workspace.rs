@@ -0,0 +1,473 @@
+use glob::{MatchOptions, Pattern};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tower_lsp::lsp_types::{
+    Diagnostic, DocumentDiagnosticReport, FullDocumentDiagnosticReport,
+    RelatedFullDocumentDiagnosticReport, RelatedUnchangedDocumentDiagnosticReport,
+    UnchangedDocumentDiagnosticReport, Url, WorkspaceDocumentDiagnosticReport,
+    WorkspaceFullDocumentDiagnosticReport, WorkspaceUnchangedDocumentDiagnosticReport,
+};
+
+use crate::diagnostics::diagnostics;
+use crate::document::Document;
+use crate::settings::{ResolvedConfig, Settings};
+
+/// Extensions of the files that are linted in the workspace
+pub const EXTENSIONS: [&str; 16] = [
+    "html",
+    "htm",
+    "xhtml",
+    "erb",
+    "eex",
+    "gohtml",
+    "gotmpl",
+    "tmpl",
+    "hbs",
+    "handlebars",
+    "jinja",
+    "jinja2",
+    "njk",
+    "liquid",
+    "mustache",
+    "twig",
+];
+
+/// Files with paths to skip in the workspace, in the `.gitignore` format
+const IGNORE_FILES: [&str; 3] = [".gitignore", ".ignore", ".htmlsnobignore"];
+
+/// A line of an ignore file
+#[derive(Debug)]
+struct IgnorePattern {
+    /// The directory of the ignore file, the pattern is relative to it
+    directory: PathBuf,
+    pattern: Pattern,
+    /// `!pattern`, which includes paths that a previous pattern ignored
+    negated: bool,
+    /// `pattern/`, which only matches directories
+    directory_only: bool,
+    /// Patterns with a slash are matched against the relative path, the others against the name
+    anchored: bool,
+}
+
+fn ignore_patterns(directory: &Path) -> Vec<IgnorePattern> {
+    IGNORE_FILES
+        .iter()
+        .filter_map(|name| std::fs::read_to_string(directory.join(name)).ok())
+        .flat_map(|text| {
+            text.lines()
+                .filter_map(|line| ignore_pattern(directory, line))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+fn ignore_pattern(directory: &Path, line: &str) -> Option<IgnorePattern> {
+    let line = line.trim_end();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+
+    let (negated, line) = match line.strip_prefix('!') {
+        Some(line) => (true, line),
+        None => (false, line.strip_prefix('\\').unwrap_or(line)),
+    };
+    let (directory_only, line) = match line.strip_suffix('/') {
+        Some(line) => (true, line),
+        None => (false, line),
+    };
+    let anchored = line.contains('/');
+
+    Some(IgnorePattern {
+        directory: directory.to_path_buf(),
+        pattern: Pattern::new(line.trim_start_matches('/')).ok()?,
+        negated,
+        directory_only,
+        anchored,
+    })
+}
+
+/// Whether the last pattern that matches the path ignores it
+fn is_ignored(patterns: &[IgnorePattern], path: &Path, is_directory: bool) -> bool {
+    let options = MatchOptions {
+        require_literal_separator: true,
+        ..Default::default()
+    };
+
+    patterns
+        .iter()
+        .rev()
+        .find(|pattern| {
+            if pattern.directory_only && !is_directory {
+                return false;
+            }
+            let Ok(relative) = path.strip_prefix(&pattern.directory) else {
+                return false;
+            };
+            if pattern.anchored {
+                pattern.pattern.matches_path_with(relative, options)
+            } else {
+                path.file_name().is_some_and(|name| {
+                    pattern
+                        .pattern
+                        .matches_with(&name.to_string_lossy(), options)
+                })
+            }
+        })
+        .is_some_and(|pattern| !pattern.negated)
+}
+
+pub fn is_html_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| EXTENSIONS.iter().any(|known| extension == *known))
+}
+
+/// The HTML files in the folder and its subfolders, except the ignored ones
+pub fn files(folder: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    walk(folder, &mut Vec::new(), &mut files);
+    files
+}
+
+fn walk(directory: &Path, patterns: &mut Vec<IgnorePattern>, files: &mut Vec<PathBuf>) {
+    // The patterns of the ignore files in this directory apply to its subfolders only
+    let inherited_patterns = patterns.len();
+    patterns.extend(ignore_patterns(directory));
+
+    let Ok(entries) = std::fs::read_dir(directory) else {
+        return;
+    };
+    let mut entries: Vec<_> = entries.flatten().collect();
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let path = entry.path();
+        // Symlinks aren't followed, so there are no cycles
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if entry.file_name() == ".git" || is_ignored(patterns, &path, file_type.is_dir()) {
+            continue;
+        }
+
+        if file_type.is_dir() {
+            walk(&path, patterns, files);
+        } else if file_type.is_file() && is_html_file(&path) {
+            files.push(path);
+        }
+    }
+
+    patterns.truncate(inherited_patterns);
+}
+
+/// Whether the file is one of the `files` of the folder
+pub fn is_workspace_file(folder: &Path, path: &Path) -> bool {
+    let Ok(relative) = path.strip_prefix(folder) else {
+        return false;
+    };
+    if !is_html_file(path) {
+        return false;
+    }
+
+    let mut patterns = ignore_patterns(folder);
+    let mut current = folder.to_path_buf();
+    let components: Vec<_> = relative.components().collect();
+    for (index, component) in components.iter().enumerate() {
+        current.push(component);
+        let is_directory = index + 1 < components.len();
+        if component.as_os_str() == ".git" || is_ignored(&patterns, &current, is_directory) {
+            return false;
+        }
+        if is_directory {
+            patterns.extend(ignore_patterns(&current));
+        }
+    }
+
+    true
+}
+
+/// The resolved configs of a walk over the workspace by config file, so that each config file is
+/// loaded once per walk instead of once per file
+#[derive(Debug, Default)]
+pub struct ConfigCache {
+    configs: Mutex<HashMap<Option<PathBuf>, ResolvedConfig>>,
+}
+
+impl ConfigCache {
+    /// Takes the config of the file out of the cache, or loads it, `restore` puts it back
+    fn take(&self, uri: &Url, settings: &Settings) -> ResolvedConfig {
+        let path = ResolvedConfig::config_path(uri, settings);
+        let cached = self.configs.lock().unwrap().remove(&path);
+        cached.unwrap_or_else(|| ResolvedConfig::load(path, settings))
+    }
+
+    fn restore(&self, config: ResolvedConfig) {
+        self.configs
+            .lock()
+            .unwrap()
+            .insert(config.path.clone(), config);
+    }
+}
+
+/// Lints a file on disk with the config it resolves to, and passes the linted document to `f`
+pub fn with_document<T>(
+    path: &Path,
+    settings: &Settings,
+    configs: &ConfigCache,
+    f: impl FnOnce(&Document) -> T,
+) -> Option<T> {
+    let text = std::fs::read_to_string(path).ok()?;
+    let uri = Url::from_file_path(path).ok()?;
+    let config = configs.take(&uri, settings);
+
+    let mut document = Document::new(uri, text, 0, config);
+    document.lint();
+    let result = f(&document);
+    configs.restore(document.config);
+    Some(result)
+}
+
+/// Lints a file on disk with the config it resolves to
+pub fn lint_file(
+    path: &Path,
+    settings: &Settings,
+    configs: &ConfigCache,
+) -> Option<(Url, Vec<Diagnostic>)> {
+    with_document(path, settings, configs, |document| {
+        (document.uri.clone(), diagnostics(document))
+    })
+}
+
+/// The last diagnostics of a file
+#[derive(Debug, Clone)]
+pub struct Report {
+    /// Identifies the diagnostics, so clients can tell whether they changed since they last
+    /// pulled them
+    pub result_id: String,
+    pub diagnostics: Vec<Diagnostic>,
+    /// The version of the document, if it is open
+    pub version: Option<i32>,
+}
+
+/// The diagnostics of the open documents and of the files in the workspace
+#[derive(Debug, Default)]
+pub struct Reports {
+    reports: HashMap<Url, Report>,
+    last_result_id: u64,
+}
+
+impl Reports {
+    /// Stores the diagnostics of a file, the result id only changes when the diagnostics do
+    pub fn update(&mut self, uri: Url, diagnostics: Vec<Diagnostic>, version: Option<i32>) {
+        if let Some(report) = self.reports.get_mut(&uri) {
+            if report.diagnostics == diagnostics {
+                report.version = version;
+                return;
+            }
+        }
+
+        self.last_result_id += 1;
+        self.reports.insert(
+            uri,
+            Report {
+                result_id: self.last_result_id.to_string(),
+                diagnostics,
+                version,
+            },
+        );
+    }
+
+    pub fn get(&self, uri: &Url) -> Option<&Report> {
+        self.reports.get(uri)
+    }
+
+    pub fn remove(&mut self, uri: &Url) {
+        self.reports.remove(uri);
+    }
+
+    /// The reports of all files, for `workspace/diagnostic`. Files whose diagnostics are
+    /// still the ones of the previous result ids are reported as unchanged.
+    pub fn workspace_reports(
+        &self,
+        previous_result_ids: &HashMap<Url, String>,
+    ) -> Vec<WorkspaceDocumentDiagnosticReport> {
+        self.reports
+            .iter()
+            .map(|(uri, report)| {
+                let version = report.version.map(i64::from);
+                if previous_result_ids.get(uri) == Some(&report.result_id) {
+                    WorkspaceUnchangedDocumentDiagnosticReport {
+                        uri: uri.clone(),
+                        version,
+                        unchanged_document_diagnostic_report: UnchangedDocumentDiagnosticReport {
+                            result_id: report.result_id.clone(),
+                        },
+                    }
+                    .into()
+                } else {
+                    WorkspaceFullDocumentDiagnosticReport {
+                        uri: uri.clone(),
+                        version,
+                        full_document_diagnostic_report: report.full(),
+                    }
+                    .into()
+                }
+            })
+            .collect()
+    }
+}
+
+impl Report {
+    /// The report for `textDocument/diagnostic`, unchanged if the client has the diagnostics of
+    /// the previous result id
+    pub fn document_report(&self, previous_result_id: Option<&str>) -> DocumentDiagnosticReport {
+        if previous_result_id == Some(self.result_id.as_str()) {
+            return RelatedUnchangedDocumentDiagnosticReport {
+                related_documents: None,
+                unchanged_document_diagnostic_report: UnchangedDocumentDiagnosticReport {
+                    result_id: self.result_id.clone(),
+                },
+            }
+            .into();
+        }
+
+        RelatedFullDocumentDiagnosticReport {
+            related_documents: None,
+            full_document_diagnostic_report: self.full(),
+        }
+        .into()
+    }
+
+    fn full(&self) -> FullDocumentDiagnosticReport {
+        FullDocumentDiagnosticReport {
+            result_id: Some(self.result_id.clone()),
+            items: self.diagnostics.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workspace(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let folder =
+            std::env::temp_dir().join(format!("htmlsnob_lsp_{name}_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&folder);
+        for (path, content) in files {
+            let path = folder.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+        folder
+    }
+
+    #[test]
+    fn files_except_ignored_ones() {
+        let folder = workspace(
+            "files",
+            &[
+                (".gitignore", "node_modules/\n/build\n*.min.html\n"),
+                ("index.html", ""),
+                ("index.min.html", ""),
+                ("style.css", ""),
+                ("build/index.html", ""),
+                ("node_modules/package/index.html", ""),
+                (
+                    "templates/.htmlsnobignore",
+                    "# Vendored\nvendor\n!vendor/keep.hbs\n",
+                ),
+                ("templates/base.html.erb", ""),
+                ("templates/vendor/ignored.hbs", ""),
+                ("templates/vendor/keep.hbs", ""),
+                ("templates/build/index.html", ""),
+            ],
+        );
+
+        let files: Vec<String> = files(&folder)
+            .iter()
+            .map(|path| {
+                path.strip_prefix(&folder)
+                    .unwrap()
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect();
+        assert_eq!(
+            files,
+            vec![
+                "index.html",
+                "templates/base.html.erb",
+                "templates/build/index.html",
+            ]
+        );
+
+        assert!(is_workspace_file(
+            &folder,
+            &folder.join("templates/base.html.erb")
+        ));
+        assert!(!is_workspace_file(
+            &folder,
+            &folder.join("build/index.html")
+        ));
+        assert!(!is_workspace_file(
+            &folder,
+            &folder.join("templates/vendor/ignored.hbs")
+        ));
+        assert!(!is_workspace_file(&folder, &folder.join("style.css")));
+
+        std::fs::remove_dir_all(folder).unwrap();
+    }
+
+    #[test]
+    fn files_with_the_same_config_share_it() {
+        let folder = workspace(
+            "config_cache",
+            &[
+                (
+                    ".htmlsnob.toml",
+                    "[[rules]]\nkind = \"tag_name_casing\"\nstyle = \"lower\"\n",
+                ),
+                ("index.html", "<DIV></DIV>"),
+                ("pages/about.html", "<SPAN></SPAN>"),
+            ],
+        );
+        let settings = Settings::default();
+        let configs = ConfigCache::default();
+
+        for path in ["index.html", "pages/about.html"] {
+            let (_, diagnostics) = lint_file(&folder.join(path), &settings, &configs).unwrap();
+            assert_eq!(diagnostics.len(), 2);
+        }
+        assert_eq!(configs.configs.lock().unwrap().len(), 1);
+
+        std::fs::remove_dir_all(folder).unwrap();
+    }
+
+    #[test]
+    fn result_ids_change_with_the_diagnostics() {
+        let uri = Url::parse("file:///tmp/index.html").unwrap();
+        let diagnostic = Diagnostic {
+            message: "Problem".to_string(),
+            ..Default::default()
+        };
+        let mut reports = Reports::default();
+
+        reports.update(uri.clone(), vec![diagnostic.clone()], None);
+        let first = reports.get(&uri).unwrap().result_id.clone();
+        reports.update(uri.clone(), vec![diagnostic], Some(2));
+        assert_eq!(reports.get(&uri).unwrap().result_id, first);
+        assert!(matches!(
+            reports.get(&uri).unwrap().document_report(Some(&first)),
+            DocumentDiagnosticReport::Unchanged(_)
+        ));
+
+        reports.update(uri.clone(), Vec::new(), Some(3));
+        assert_ne!(reports.get(&uri).unwrap().result_id, first);
+        let previous_result_ids = HashMap::from([(uri.clone(), first)]);
+        assert!(matches!(
+            reports.workspace_reports(&previous_result_ids)[..],
+            [WorkspaceDocumentDiagnosticReport::Full(_)]
+        ));
+    }
+}
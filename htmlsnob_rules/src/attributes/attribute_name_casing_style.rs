@@ -1,12 +1,15 @@
-use htmlsnob::ast::{Attribute, Either, StringArea};
+use htmlsnob::ast::{Attribute, Either, OpenTag, StringArea};
 use htmlsnob::case_converter::CaseStyle;
 use htmlsnob::dynamic_format::dynamic_format;
+use htmlsnob::namespace::Namespace;
+use htmlsnob::parser::ParseState;
 use htmlsnob::rule_trait::RuleTrait;
 use htmlsnob::warning::Warning;
 use htmlsnob::WarningSeverity;
 use serde::Deserialize;
 
-/// Enforces that all attribute names match a specified casing style.
+/// Enforces that all attribute names match a specified casing style. Attributes of SVG and MathML
+/// elements, like `viewBox`, must match their case-sensitive names instead.
 #[derive(Debug, Clone, Deserialize)]
 pub struct Rule {
     #[serde(default)]
@@ -28,13 +31,18 @@ fn default_error_message() -> String {
     "Attribute name `{name}` should be in {style}, change to `{converted_name}`".to_string()
 }
 
-impl RuleTrait for Rule {
-    fn apply_attribute(&self, attribute: &mut Attribute) -> Option<Warning> {
-        if let Either::Right(_) = attribute.name {
-            return None;
-        }
-        let attribute_name = attribute.name.left().unwrap();
-        let converted_name = self.style.convert(&attribute_name.content);
+impl Rule {
+    fn check(&self, attribute: &mut Attribute, namespace: Namespace) -> Option<Warning> {
+        let attribute_name = attribute.name.left()?;
+
+        // SVG and MathML attributes, like `viewBox`, have case-sensitive names
+        let (converted_name, style) = match namespace.attribute_name(&attribute_name.content) {
+            Some(foreign_name) => (foreign_name.to_string(), format!("{namespace} case")),
+            None => (
+                self.style.convert(&attribute_name.content),
+                self.style.to_string(),
+            ),
+        };
 
         if attribute_name.content == converted_name {
             return None;
@@ -44,7 +52,7 @@ impl RuleTrait for Rule {
             &self.error_message,
             &[
                 ("name", attribute_name.content.clone()),
-                ("style", self.style.to_string()),
+                ("style", style),
                 ("converted_name", converted_name.clone()),
             ],
         );
@@ -68,6 +76,17 @@ impl RuleTrait for Rule {
     }
 }
 
+impl RuleTrait for Rule {
+    fn apply_open_tag_vec(&self, open_tag: &mut OpenTag, parse_state: &ParseState) -> Vec<Warning> {
+        let namespace = parse_state.element_namespace(&open_tag.name);
+        open_tag
+            .attributes
+            .iter_mut()
+            .filter_map(|attribute| self.check(attribute, namespace))
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::registry;
@@ -86,6 +105,18 @@ mod tests {
         test_case("<div data-test=\"value\"></div>", CONFIG, &registry())
     }
 
+    #[test]
+    fn good_case_disabled_by_directive() {
+        test_case(
+            r#"
+            <!-- htmlsnob-disable-next-line attribute_name_casing_style -->
+            <div dataTest="value"></div>
+            "#,
+            CONFIG,
+            &registry(),
+        )
+    }
+
     #[test]
     fn bad_case() {
         test_case_autofix(
@@ -99,4 +130,28 @@ mod tests {
             &registry(),
         )
     }
+
+    #[test]
+    fn good_case_foreign_attributes() {
+        test_case(
+            r#"<svg viewBox="0 0 8 8" data-test><path stroke-width="2" pathLength="4"></path>
+            <foreignObject><div data-test></div></foreignObject></svg><math><mi definitionURL></mi></math>"#,
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn bad_case_foreign_attributes() {
+        test_case_autofix(
+            r#"
+            <svg viewbox="0 0 8 8" data-test><path pathlength="4"></path></svg>
+                 -------                           ----------
+                 attribute_name_casing_style: Attribute name `viewbox` should be in SVG case, change to `viewBox`
+            "#,
+            r#"<svg viewBox="0 0 8 8" data-test><path pathLength="4"></path></svg>"#,
+            CONFIG,
+            &registry(),
+        )
+    }
 }
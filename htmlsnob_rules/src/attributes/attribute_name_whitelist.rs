@@ -9,7 +9,8 @@ use htmlsnob::warning::Warning;
 use htmlsnob::WarningSeverity;
 use serde::Deserialize;
 
-/// Enforces that an elements attribute are in a specified whitelist.
+/// Enforces that an elements attribute are in a specified whitelist. Attributes of SVG and MathML
+/// elements are checked against the attributes of their namespace instead.
 #[derive(Debug, Clone, Deserialize)]
 pub struct Rule {
     #[serde(default)]
@@ -19,6 +20,8 @@ pub struct Rule {
     pub tags: HashMap<String, Vec<DeserializableRegex>>, // tag_name, whitelisted_attributes
     #[serde(default = "default_error_message")]
     pub error_message: String,
+    #[serde(default = "default_foreign_error_message")]
+    pub foreign_error_message: String,
     #[serde(default)]
     severity: WarningSeverity,
 }
@@ -27,13 +30,14 @@ fn default_error_message() -> String {
     "`{name}` not allowed, must be a global attribute or: `{whitelist}`".to_string()
 }
 
+fn default_foreign_error_message() -> String {
+    "`{name}` not allowed, must be a global or {namespace} attribute".to_string()
+}
+
 impl RuleTrait for Rule {
-    fn apply_open_tag_vec(
-        &self,
-        open_tag: &mut OpenTag,
-        _parse_state: &ParseState,
-    ) -> Vec<Warning> {
+    fn apply_open_tag_vec(&self, open_tag: &mut OpenTag, parse_state: &ParseState) -> Vec<Warning> {
         let mut warnings = Vec::new();
+        let namespace = parse_state.element_namespace(&open_tag.name);
 
         for attribute in open_tag.attributes.iter() {
             if !attribute.name.is_left() {
@@ -44,10 +48,6 @@ impl RuleTrait for Rule {
                 continue;
             }
 
-            let Some(whitelist) = self.tags.get(&open_tag.name) else {
-                continue;
-            };
-
             // Accept name if in list of global attributes
             if self
                 .globals
@@ -57,25 +57,45 @@ impl RuleTrait for Rule {
                 continue;
             }
 
-            // Accept name if in whitelist for this tag
-            if whitelist.iter().any(|re| re.is_match(&string_area.content)) {
-                continue;
-            }
+            // SVG and MathML elements share the attributes of their namespace, which are matched
+            // ignoring case like the HTML parser does
+            let message = if namespace.is_foreign() {
+                if namespace.attribute_name(&string_area.content).is_some() {
+                    continue;
+                }
 
-            let message = dynamic_format(
-                &self.error_message,
-                &[
-                    ("name", string_area.content.clone()),
-                    (
-                        "whitelist",
-                        whitelist
-                            .iter()
-                            .map(|re| re.to_string())
-                            .collect::<Vec<String>>()
-                            .join(", "),
-                    ),
-                ],
-            );
+                dynamic_format(
+                    &self.foreign_error_message,
+                    &[
+                        ("name", string_area.content.clone()),
+                        ("namespace", namespace.to_string()),
+                    ],
+                )
+            } else {
+                let Some(whitelist) = self.tags.get(&open_tag.name) else {
+                    continue;
+                };
+
+                // Accept name if in whitelist for this tag
+                if whitelist.iter().any(|re| re.is_match(&string_area.content)) {
+                    continue;
+                }
+
+                dynamic_format(
+                    &self.error_message,
+                    &[
+                        ("name", string_area.content.clone()),
+                        (
+                            "whitelist",
+                            whitelist
+                                .iter()
+                                .map(|re| re.to_string())
+                                .collect::<Vec<String>>()
+                                .join(", "),
+                        ),
+                    ],
+                )
+            };
 
             warnings.push(Warning::from_area(
                 &self.name,
@@ -124,4 +144,27 @@ mod tests {
             &registry(),
         )
     }
+
+    #[test]
+    fn good_case_foreign_attributes() {
+        test_case(
+            r#"<svg viewBox="0 0 8 8" preserveaspectratio="none" class><path d="M0 0" stroke-width="2"></path>
+            <foreignObject width="8"><input type></foreignObject></svg><math display="block"></math>"#,
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn bad_case_foreign_attributes() {
+        test_case(
+            r#"
+            <svg viewBox="0 0 8 8" class><path d="M0 0" src></path><foreignObject><input id></foreignObject></svg>
+                                                        ---                              --
+                                                        attribute_name_whitelist: `src` not allowed, must be a global or SVG attribute
+            "#,
+            CONFIG,
+            &registry(),
+        )
+    }
 }
@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+
+use htmlsnob::ast::OpenTag;
+use htmlsnob::dynamic_format::dynamic_format;
+use htmlsnob::parser::ParseState;
+use htmlsnob::rule_trait::RuleTrait;
+use htmlsnob::rule_utils::deserialize_regex::DeserializableRegex;
+use htmlsnob::warning::Warning;
+use htmlsnob::WarningSeverity;
+use serde::Deserialize;
+
+/// Enforces that tags do not have any of the specified class names. The entries are regexps that
+/// must match the whole class name, class names that are partly template expressions are not
+/// checked.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Rule {
+    #[serde(default)]
+    name: String,
+    kind: String,
+    /// Class names that are disallowed on all tags
+    #[serde(default)]
+    pub globals: Vec<DeserializableRegex>,
+    #[serde(default)]
+    pub tags: HashMap<String, Vec<DeserializableRegex>>, // tag_name, blacklisted_classes
+    #[serde(default = "default_error_message")]
+    pub error_message: String,
+    #[serde(default)]
+    severity: WarningSeverity,
+}
+
+fn default_error_message() -> String {
+    "Class `{name}` is not allowed on `{tag}`".to_string()
+}
+
+impl RuleTrait for Rule {
+    fn apply_open_tag_vec(
+        &self,
+        open_tag: &mut OpenTag,
+        _parse_state: &ParseState,
+    ) -> Vec<Warning> {
+        let blacklist = self.tags.get(&open_tag.name).into_iter().flatten();
+
+        let mut warnings = Vec::new();
+        for class in open_tag
+            .attribute_values("class")
+            .flat_map(|value| value.words())
+        {
+            if !self
+                .globals
+                .iter()
+                .chain(blacklist.clone())
+                .any(|re| re.is_whole_match(&class.content))
+            {
+                continue;
+            }
+
+            let message = dynamic_format(
+                &self.error_message,
+                &[
+                    ("name", class.content.clone()),
+                    ("tag", open_tag.name.clone()),
+                ],
+            );
+
+            warnings.push(Warning::from_area(
+                &self.name,
+                &self.kind,
+                class.area.clone(),
+                &message,
+                self.severity.clone(),
+            ));
+        }
+
+        warnings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::registry;
+    use htmlsnob::test_utils::tests::test_case;
+
+    const CONFIG: &str = r#"
+        template_language = "handlebars"
+
+        [[rules]]
+        kind = "class_blacklist"
+        globals = ["clearfix"]
+        [rules.tags]
+        script = ["text-.*", "color-red"]
+    "#;
+
+    #[test]
+    fn good_case() {
+        test_case(
+            "<div class=\"text-center\"></div><script class=\"module\"></script>",
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn bad_case() {
+        test_case(
+            r#"
+            <script class="module text-center"></script>
+                                  -----------
+                                  class_blacklist: Class `text-center` is not allowed on `script`
+            "#,
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn bad_case_globals() {
+        test_case(
+            r#"
+            <div class="clearfix {{classes}}"></div>
+                        --------
+            "#,
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn good_case_template_expressions() {
+        test_case(
+            "<div class=\"{{prefix}}clearfix\"></div>",
+            CONFIG,
+            &registry(),
+        )
+    }
+}
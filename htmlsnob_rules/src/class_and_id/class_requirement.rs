@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+
+use htmlsnob::ast::OpenTag;
+use htmlsnob::dynamic_format::dynamic_format;
+use htmlsnob::parser::ParseState;
+use htmlsnob::rule_trait::RuleTrait;
+use htmlsnob::rule_utils::deserialize_regex::DeserializableRegex;
+use htmlsnob::warning::Warning;
+use htmlsnob::WarningSeverity;
+use serde::Deserialize;
+
+/// Enforces that the specified tags have one of the specified class names. The entries are
+/// regexps that must match the whole class name. Classes that are template expressions may be
+/// one of them, so tags with such classes are not checked.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Rule {
+    #[serde(default)]
+    name: String,
+    kind: String,
+    pub tags: HashMap<String, Vec<DeserializableRegex>>, // tag_name, required_classes
+    #[serde(default = "default_error_message")]
+    pub error_message: String,
+    #[serde(default)]
+    severity: WarningSeverity,
+}
+
+fn default_error_message() -> String {
+    "`{tag}` must have one of the classes: `{classes}`".to_string()
+}
+
+impl RuleTrait for Rule {
+    fn apply_open_tag(&self, open_tag: &mut OpenTag, _parse_state: &ParseState) -> Option<Warning> {
+        let required_classes = self.tags.get(&open_tag.name)?;
+
+        if open_tag
+            .attribute_values("class")
+            .any(|value| value.has_template_expressions())
+        {
+            return None;
+        }
+
+        if open_tag
+            .attribute_values("class")
+            .flat_map(|value| value.words())
+            .any(|class| {
+                required_classes
+                    .iter()
+                    .any(|re| re.is_whole_match(&class.content))
+            })
+        {
+            return None;
+        }
+
+        let message = dynamic_format(
+            &self.error_message,
+            &[
+                ("tag", open_tag.name.clone()),
+                (
+                    "classes",
+                    required_classes
+                        .iter()
+                        .map(|re| re.to_string())
+                        .collect::<Vec<String>>()
+                        .join(", "),
+                ),
+            ],
+        );
+
+        Some(Warning::from_area(
+            &self.name,
+            &self.kind,
+            open_tag.area.clone(),
+            &message,
+            self.severity.clone(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::registry;
+    use htmlsnob::test_utils::tests::test_case;
+
+    const CONFIG: &str = r#"
+        template_language = "handlebars"
+
+        [[rules]]
+        kind = "class_requirement"
+        [rules.tags]
+        nav = ["navbar", "nav-.*"]
+    "#;
+
+    #[test]
+    fn good_case() {
+        test_case(
+            "<nav class=\"dark navbar\"></nav><nav class=\"nav-main\"></nav><div></div>",
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn bad_case() {
+        test_case(
+            r#"
+            <nav class="dark"></nav>
+            ------------------
+            class_requirement: `nav` must have one of the classes: `navbar, nav-.*`
+            "#,
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn bad_case_missing_class() {
+        test_case(
+            r#"
+            <nav></nav>
+            -----
+            "#,
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn good_case_template_expressions() {
+        test_case("<nav class=\"{{navClass}}\"></nav>", CONFIG, &registry())
+    }
+}
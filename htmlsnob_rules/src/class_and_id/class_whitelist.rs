@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+
+use htmlsnob::ast::OpenTag;
+use htmlsnob::dynamic_format::dynamic_format;
+use htmlsnob::parser::ParseState;
+use htmlsnob::rule_trait::RuleTrait;
+use htmlsnob::rule_utils::deserialize_regex::DeserializableRegex;
+use htmlsnob::warning::Warning;
+use htmlsnob::WarningSeverity;
+use serde::Deserialize;
+
+/// Enforces that the class names of the specified tags are in a whitelist. The entries are
+/// regexps that must match the whole class name, class names that are partly template
+/// expressions are not checked.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Rule {
+    #[serde(default)]
+    name: String,
+    kind: String,
+    /// Class names that are allowed on all the tags of the rule
+    #[serde(default)]
+    pub globals: Vec<DeserializableRegex>,
+    pub tags: HashMap<String, Vec<DeserializableRegex>>, // tag_name, whitelisted_classes
+    #[serde(default = "default_error_message")]
+    pub error_message: String,
+    #[serde(default)]
+    severity: WarningSeverity,
+}
+
+fn default_error_message() -> String {
+    "Class `{name}` is not allowed on `{tag}`, must be one of: `{whitelist}`".to_string()
+}
+
+impl RuleTrait for Rule {
+    fn apply_open_tag_vec(
+        &self,
+        open_tag: &mut OpenTag,
+        _parse_state: &ParseState,
+    ) -> Vec<Warning> {
+        let Some(whitelist) = self.tags.get(&open_tag.name) else {
+            return vec![];
+        };
+
+        let mut warnings = Vec::new();
+        for class in open_tag
+            .attribute_values("class")
+            .flat_map(|value| value.words())
+        {
+            if self
+                .globals
+                .iter()
+                .chain(whitelist)
+                .any(|re| re.is_whole_match(&class.content))
+            {
+                continue;
+            }
+
+            let message = dynamic_format(
+                &self.error_message,
+                &[
+                    ("name", class.content.clone()),
+                    ("tag", open_tag.name.clone()),
+                    (
+                        "whitelist",
+                        self.globals
+                            .iter()
+                            .chain(whitelist)
+                            .map(|re| re.to_string())
+                            .collect::<Vec<String>>()
+                            .join(", "),
+                    ),
+                ],
+            );
+
+            warnings.push(Warning::from_area(
+                &self.name,
+                &self.kind,
+                class.area.clone(),
+                &message,
+                self.severity.clone(),
+            ));
+        }
+
+        warnings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::registry;
+    use htmlsnob::test_utils::tests::test_case;
+
+    const CONFIG: &str = r#"
+        template_language = "handlebars"
+
+        [[rules]]
+        kind = "class_whitelist"
+        globals = ["hidden"]
+        [rules.tags]
+        button = ["btn", "btn-(primary|secondary)"]
+    "#;
+
+    #[test]
+    fn good_case() {
+        test_case(
+            "<button class=\"btn btn-primary hidden\"></button><div class=\"any\"></div>",
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn bad_case() {
+        test_case(
+            r#"
+            <button class="btn my-btn"></button>
+                               ------
+                               class_whitelist: Class `my-btn` is not allowed on `button`, must be one of: `hidden, btn, btn-(primary|secondary)`
+            "#,
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn good_case_template_expressions() {
+        test_case(
+            "<button class=\"btn btn-{{variant}} {{classes}}\"></button>",
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn bad_case_template_expressions() {
+        test_case(
+            r#"
+            <button class="{{#if big}}large{{/if}} btn"></button>
+                                      -----
+            "#,
+            CONFIG,
+            &registry(),
+        )
+    }
+}
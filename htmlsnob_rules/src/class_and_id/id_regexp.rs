@@ -1 +1,122 @@
+use std::collections::HashMap;
 
+use htmlsnob::ast::OpenTag;
+use htmlsnob::dynamic_format::dynamic_format;
+use htmlsnob::parser::ParseState;
+use htmlsnob::rule_trait::RuleTrait;
+use htmlsnob::rule_utils::deserialize_regex::DeserializableRegex;
+use htmlsnob::warning::Warning;
+use htmlsnob::WarningSeverity;
+use serde::Deserialize;
+
+/// Enforces that ids match a regexp. Ids that are partly template expressions are not checked.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Rule {
+    #[serde(default)]
+    name: String,
+    kind: String,
+    /// The regexp that the ids of all tags must match
+    #[serde(default)]
+    regexp: Option<DeserializableRegex>,
+    /// Regexps for the ids of specific tags, instead of `regexp`
+    #[serde(default)]
+    tags: HashMap<String, DeserializableRegex>,
+    #[serde(default = "default_error_message")]
+    error_message: String,
+    #[serde(default)]
+    severity: WarningSeverity,
+}
+
+fn default_error_message() -> String {
+    "id `{value}` must match the regexp `{regexp}`".to_string()
+}
+
+impl RuleTrait for Rule {
+    fn apply_open_tag_vec(
+        &self,
+        open_tag: &mut OpenTag,
+        _parse_state: &ParseState,
+    ) -> Vec<Warning> {
+        let Some(regexp) = self.tags.get(&open_tag.name).or(self.regexp.as_ref()) else {
+            return vec![];
+        };
+
+        open_tag
+            .attribute_values("id")
+            .flat_map(|value| value.words())
+            .filter(|id| !regexp.is_match(&id.content))
+            .map(|id| {
+                let message = dynamic_format(
+                    &self.error_message,
+                    &[
+                        ("value", id.content.clone()),
+                        ("regexp", regexp.to_string()),
+                    ],
+                );
+
+                Warning::from_area(
+                    &self.name,
+                    &self.kind,
+                    id.area.clone(),
+                    &message,
+                    self.severity.clone(),
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::registry;
+    use htmlsnob::test_utils::tests::test_case;
+
+    const CONFIG: &str = r#"
+        template_language = "handlebars"
+
+        [[rules]]
+        kind = "id_regexp"
+        regexp = "^[a-z][a-z0-9-]*$"
+        [rules.tags]
+        form = "^form-[a-z-]+$"
+    "#;
+
+    #[test]
+    fn good_case() {
+        test_case(
+            "<div id=\"main\"></div><form id=\"form-login\"></form>",
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn bad_case() {
+        test_case(
+            r#"
+            <div id="Main_Content"></div>
+                     ------------
+                     id_regexp: id `Main_Content` must match the regexp `^[a-z][a-z0-9-]*$`
+            "#,
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn bad_case_tags() {
+        test_case(
+            r#"
+            <form id="login"></form>
+                      -----
+            "#,
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn good_case_template_expressions() {
+        test_case("<div id=\"item-{{Id}}\"></div>", CONFIG, &registry())
+    }
+}
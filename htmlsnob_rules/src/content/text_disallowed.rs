@@ -1,4 +1,4 @@
-use htmlsnob::ast::{Position, Area, Text};
+use htmlsnob::ast::{Area, Position, Text};
 use htmlsnob::dynamic_format::dynamic_format;
 use htmlsnob::parser::ParseState;
 use htmlsnob::rule_trait::RuleTrait;
@@ -32,7 +32,6 @@ impl RuleTrait for Rule {
         if self.tags.contains(parent_name) {
             let message = dynamic_format(&self.error_message, &[("tag", parent_name.clone())]);
 
-            
             return Some(Warning::from_areas(
                 &self.name,
                 &self.kind,
@@ -54,16 +53,20 @@ fn non_whitespace_areas(text: &Text) -> Vec<Area> {
         let end_column = start_column + line_text.len();
 
         let leading_whitespace = line_text.chars().take_while(|c| c.is_whitespace()).count();
-        let trailing_whitespace = line_text.chars().rev().take_while(|c| c.is_whitespace()).count();
+        let trailing_whitespace = line_text
+            .chars()
+            .rev()
+            .take_while(|c| c.is_whitespace())
+            .count();
 
         areas.push(Area {
             start: Position {
-                line: line,
-                column: start_column + leading_whitespace
+                line,
+                column: start_column + leading_whitespace,
             },
             end: Position {
-                line: line,
-                column: end_column - trailing_whitespace
+                line,
+                column: end_column - trailing_whitespace,
             },
         });
 
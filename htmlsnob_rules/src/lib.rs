@@ -42,28 +42,26 @@ pub mod attributes {
 }
 
 pub mod class_and_id {
+    /// Enforces that an element does not have any of the specified class names.
+    pub mod class_blacklist;
     /// Enforces that all class names match a specified casing style.
     pub mod class_name_casing_style;
+    /// Enforces that all class names match a specified regular expression.
+    pub mod class_name_regexp;
     /// Enforces that all class names are in a specified order.
     pub mod class_order;
+    /// Enforces that an element has one of the specified class names.
+    pub mod class_requirement;
+    /// Enforces that an element only has the specified class names.
+    pub mod class_whitelist;
     /// Enforces that no element has the same class name more than once.
     pub mod duplicate_classes_disallowed;
     /// Enforces that the value of all ids match a specified casing style.
     pub mod id_casing_style;
+    /// Enforces that all ids match a specified regular expression.
+    pub mod id_regexp;
     /// Enforces that all ids are unique within the document.
     pub mod id_unique;
-
-    // Potential new rules:
-    // /// Enforces that an element does not have any of the specified class names.
-    // pub mod class_blacklist;
-    // /// Enforces that all ids match a specified regular expression.
-    // pub mod id_regexp;
-    // /// Enforces that an element has one of the specified class names.
-    // pub mod class_requirement;
-    // /// Enforces that an element only has the specified class names.
-    // pub mod class_whitelist;
-    // /// Enforces that all class names match a specified regular expression.
-    // pub mod class_name_regexp;
 }
 
 pub mod content {
@@ -81,29 +79,34 @@ pub mod structure {
     pub mod ancestor_blacklist;
     /// Enforces that an element has one of the specified ancestor elements at some level.
     pub mod ancestor_requirement;
-    //
-    //    /// Enforces that an element does not have any of the specified direct child elements.
-    //    pub mod child_blacklist;
-    //    /// Enforces that an element has all of the specified direct child elements.
+    /// Enforces that an element only has the specified ancestor elements at any level.
+    pub mod ancestor_whitelist;
+    /// Enforces that an element does not have any of the specified direct child elements.
+    pub mod child_blacklist;
+    /// Enforces that an element has all of the specified direct child elements.
     pub mod child_requirement;
     /// Enforces that an element only has the specified direct child elements.
-    //    pub mod child_whitelist;
+    pub mod child_whitelist;
+    /// Enforces that elements only contain the content that the HTML specification permits.
+    pub mod content_model;
     //
+    /// Enforces that an element does not contain any of the specified descendant elements at any
+    /// depth.
+    pub mod descendant_blacklist;
     /// Enforces that an element contains all of the specified descendant elements at some depth.
     pub mod descendant_requirement;
+    /// Enforces that an element only contains the specified descendant elements at any depth.
+    pub mod descendant_whitelist;
     //
     /// Enforces that the specified elements appear only once in the entire document.
     pub mod duplicate_elements_blacklist;
     //
-    //    /// Enforces that an element has one of the specified direct parent elements.
-    //    pub mod parent_whitelist;
-
-    // Ideas for potential new rules:
-    //"ancestor_whitelist", # Enforces that an element only has the specified ancestors elements at any level.
-    //"descendant_blacklist" # Enforces that an element does not contain any of the specified descendant elements at any depth.
-    //"descendant_whitelist" # Enforces that an element only contains the specified descendant elements at any depth.
-    //"parent_blacklist" # Enforces that an element does not have any of the specified direct parent elements.
+    /// Enforces that an element does not have any of the specified direct parent elements.
+    pub mod parent_blacklist;
+    /// Enforces that an element has one of the specified direct parent elements.
+    pub mod parent_whitelist;
 
+    /// Enforces that elements are not nested deeper than a specified depth.
     pub mod maximum_nesting_depth;
 }
 
@@ -114,6 +117,8 @@ pub mod tags {
     pub mod missing_end_bracket_disallowed;
     /// Enforces that all closing tags have a matching opening tag.
     pub mod missing_open_tag_disallowed;
+    /// Enforces that optional close tags are either written out or omitted.
+    pub mod optional_tag_style;
     /// Enforces that self-closing tags are with a specified style.
     pub mod self_closing_tag_style;
     /// Enforces that the document does not use any of the specified tags.
@@ -124,6 +129,18 @@ pub mod tags {
     pub mod tag_name_regexp;
     /// Enforces that the document only use the specified tags.
     pub mod tag_name_whitelist;
+    /// Enforces that void elements, like `br` and `img`, don't have close tags.
+    pub mod void_element_close_tag_disallowed;
+}
+
+/// The default config of the CLI and the language server, `default_config.toml` followed by the
+/// `content_model` rule with data from the HTML spec
+pub fn default_config_toml() -> String {
+    [
+        include_str!("../../default_config/default_config.toml"),
+        include_str!("../../default_config/content_model.toml"),
+    ]
+    .join("\n")
 }
 
 pub fn registry() -> Registry {
@@ -159,6 +176,11 @@ pub fn registry() -> Registry {
         .register_rule::<class_and_id::id_casing_style::Rule>("id_casing_style")
         .register_rule::<class_and_id::class_order::Rule>("class_order")
         .register_rule::<class_and_id::id_unique::Rule>("id_unique")
+        .register_rule::<class_and_id::class_blacklist::Rule>("class_blacklist")
+        .register_rule::<class_and_id::class_whitelist::Rule>("class_whitelist")
+        .register_rule::<class_and_id::class_requirement::Rule>("class_requirement")
+        .register_rule::<class_and_id::class_name_regexp::Rule>("class_name_regexp")
+        .register_rule::<class_and_id::id_regexp::Rule>("id_regexp")
         // ---- Content ----------------------------------------------------------------------------
         .register_rule::<content::text_disallowed::Rule>("text_disallowed")
         .register_rule::<content::text_requirement::Rule>("text_requirement")
@@ -169,9 +191,17 @@ pub fn registry() -> Registry {
             "duplicate_elements_blacklist",
         )
         .register_rule::<structure::ancestor_requirement::Rule>("ancestor_requirement")
+        .register_rule::<structure::ancestor_whitelist::Rule>("ancestor_whitelist")
+        .register_rule::<structure::child_blacklist::Rule>("child_blacklist")
         .register_rule::<structure::child_requirement::Rule>("child_requirement")
+        .register_rule::<structure::child_whitelist::Rule>("child_whitelist")
+        .register_rule::<structure::content_model::Rule>("content_model")
+        .register_rule::<structure::descendant_blacklist::Rule>("descendant_blacklist")
         .register_rule::<structure::descendant_requirement::Rule>("descendant_requirement")
+        .register_rule::<structure::descendant_whitelist::Rule>("descendant_whitelist")
         .register_rule::<structure::maximum_nesting_depth::Rule>("maximum_nesting_depth")
+        .register_rule::<structure::parent_blacklist::Rule>("parent_blacklist")
+        .register_rule::<structure::parent_whitelist::Rule>("parent_whitelist")
         // ---- Tags  ------------------------------------------------------------------------------
         .register_rule::<tags::self_closing_tag_style::Rule>("self_closing_tag_style")
         .register_rule::<tags::tag_name_blacklist::Rule>("tag_name_blacklist")
@@ -183,4 +213,138 @@ pub fn registry() -> Registry {
         )
         .register_rule::<tags::missing_close_tag_disallowed::Rule>("missing_close_tag_disallowed")
         .register_rule::<tags::missing_open_tag_disallowed::Rule>("missing_open_tag_disallowed")
+        .register_rule::<tags::optional_tag_style::Rule>("optional_tag_style")
+        .register_rule::<tags::void_element_close_tag_disallowed::Rule>(
+            "void_element_close_tag_disallowed",
+        )
+}
+
+/// The module group and documentation of each rule kind, which is the doc comment of its module
+/// above
+const RULE_DOCUMENTATION: &[(&str, &str, &str)] = &[
+    ("ancestor_blacklist", "structure", "Enforces that an element does not have any of the specified ancestor elements at any level."),
+    ("ancestor_requirement", "structure", "Enforces that an element has one of the specified ancestor elements at some level."),
+    ("ancestor_whitelist", "structure", "Enforces that an element only has the specified ancestor elements at any level."),
+    ("attribute_name_blacklist", "attributes", "Enforces that an element does not have any of the specified attributes."),
+    ("attribute_name_casing_style", "attributes", "Enforces that all attribute names match a specified casing style."),
+    ("attribute_name_missing", "attributes", "Enforces that no attributes names are empty. (e.g. <div =\"foo\">)"),
+    ("attribute_name_regexp", "attributes", "Enforces that all attribute names match a specified regular expression."),
+    ("attribute_name_requirement", "attributes", "Enforces that an element has all of the specified attributes."),
+    ("attribute_name_whitelist", "attributes", "Enforces that an element only has the specified attributes."),
+    ("attribute_value_casing_style", "attributes", "Enforces that all attribute values match a specified casing style."),
+    ("attribute_value_quote_style", "attributes", "Enforces that all attribute values use the same quote style."),
+    ("attribute_value_regexp", "attributes", "Enforces that all attribute values match a specified regular expression."),
+    ("attribute_value_whitelist", "attributes", "Enforces that an elements specfied attribute has one of the specified values."),
+    ("attributes_order", "attributes", "Enforces that all attributes are in a specified order."),
+    ("boolean_attribute_style", "attributes", "Enforces that boolean attributes use a specified style."),
+    ("child_blacklist", "structure", "Enforces that an element does not have any of the specified direct child elements."),
+    ("child_requirement", "structure", "Enforces that an element has all of the specified direct child elements."),
+    ("child_whitelist", "structure", "Enforces that an element only has the specified direct child elements."),
+    ("class_blacklist", "class_and_id", "Enforces that an element does not have any of the specified class names."),
+    ("class_name_casing_style", "class_and_id", "Enforces that all class names match a specified casing style."),
+    ("class_name_regexp", "class_and_id", "Enforces that all class names match a specified regular expression."),
+    ("class_order", "class_and_id", "Enforces that all class names are in a specified order."),
+    ("class_requirement", "class_and_id", "Enforces that an element has one of the specified class names."),
+    ("class_whitelist", "class_and_id", "Enforces that an element only has the specified class names."),
+    ("content_model", "structure", "Enforces that elements only contain the content that the HTML specification permits."),
+    ("descendant_blacklist", "structure", "Enforces that an element does not contain any of the specified descendant elements at any depth."),
+    ("descendant_requirement", "structure", "Enforces that an element contains all of the specified descendant elements at some depth."),
+    ("descendant_whitelist", "structure", "Enforces that an element only contains the specified descendant elements at any depth."),
+    ("duplicate_attribute_names_disallowed", "attributes", "Enforces that no attributes appear more than once in the same element."),
+    ("duplicate_classes_disallowed", "class_and_id", "Enforces that no element has the same class name more than once."),
+    ("duplicate_elements_blacklist", "structure", "Enforces that the specified elements appear only once in the entire document."),
+    ("id_casing_style", "class_and_id", "Enforces that the value of all ids match a specified casing style."),
+    ("id_regexp", "class_and_id", "Enforces that all ids match a specified regular expression."),
+    ("id_unique", "class_and_id", "Enforces that all ids are unique within the document."),
+    ("maximum_nesting_depth", "structure", "Enforces that elements are not nested deeper than a specified depth."),
+    ("missing_close_tag_disallowed", "tags", "Enforces that all closing tags have a matching opening tag."),
+    ("missing_end_bracket_disallowed", "tags", "Enforces that all tags have their end brackets present."),
+    ("missing_open_tag_disallowed", "tags", "Enforces that all closing tags have a matching opening tag."),
+    ("optional_tag_style", "tags", "Enforces that optional close tags are either written out or omitted."),
+    ("parent_blacklist", "structure", "Enforces that an element does not have any of the specified direct parent elements."),
+    ("parent_whitelist", "structure", "Enforces that an element has one of the specified direct parent elements."),
+    ("self_closing_tag_style", "tags", "Enforces that self-closing tags are with a specified style."),
+    ("tag_name_blacklist", "tags", "Enforces that the document does not use any of the specified tags."),
+    ("tag_name_casing", "tags", "Enforces that the tag name matches a specified casing style."),
+    ("tag_name_regexp", "tags", "Enforces that the tag name matches a specified regular expression."),
+    ("tag_name_whitelist", "tags", "Enforces that the document only use the specified tags."),
+    ("text_disallowed", "content", "Enforces that an element does not have any text content."),
+    ("text_regexp", "content", "Enforces that an elements text content matches a specified regular expression."),
+    ("text_requirement", "content", "Enforces that an element has text content."),
+    ("void_element_close_tag_disallowed", "tags", "Enforces that void elements, like `br` and `img`, don't have close tags."),
+];
+
+/// The documentation of a rule kind
+pub fn documentation(kind: &str) -> Option<&'static str> {
+    RULE_DOCUMENTATION
+        .iter()
+        .find(|(rule_kind, _, _)| *rule_kind == kind)
+        .map(|(_, _, documentation)| *documentation)
+}
+
+/// The path of the source file of a rule kind in the repository, like
+/// `htmlsnob_rules/src/tags/tag_name_casing.rs`
+pub fn source_path(kind: &str) -> Option<String> {
+    RULE_DOCUMENTATION
+        .iter()
+        .find(|(rule_kind, _, _)| *rule_kind == kind)
+        .map(|(_, group, _)| format!("htmlsnob_rules/src/{group}/{kind}.rs"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn documentation_of_a_rule() {
+        assert_eq!(
+            documentation("class_order"),
+            Some("Enforces that all class names are in a specified order.")
+        );
+        assert_eq!(
+            documentation("maximum_nesting_depth"),
+            Some("Enforces that elements are not nested deeper than a specified depth.")
+        );
+        assert_eq!(documentation("unknown"), None);
+    }
+
+    #[test]
+    fn source_path_of_a_rule() {
+        assert_eq!(
+            source_path("class_order").as_deref(),
+            Some("htmlsnob_rules/src/class_and_id/class_order.rs")
+        );
+        assert_eq!(source_path("unknown"), None);
+    }
+
+    #[test]
+    fn documentation_matches_the_modules() {
+        let mut group = "";
+        let mut doc_lines = Vec::new();
+        let mut modules = Vec::new();
+        for line in include_str!("lib.rs").lines().map(str::trim) {
+            if let Some(name) = line
+                .strip_prefix("pub mod ")
+                .and_then(|l| l.strip_suffix(" {"))
+            {
+                group = name;
+            } else if let Some(doc_line) = line.strip_prefix("///") {
+                doc_lines.push(doc_line.trim());
+                continue;
+            } else if let Some(kind) = line
+                .strip_prefix("pub mod ")
+                .and_then(|l| l.strip_suffix(';'))
+            {
+                modules.push((kind, group, doc_lines.join(" ")));
+            }
+            doc_lines.clear();
+        }
+        modules.sort();
+
+        let table: Vec<_> = RULE_DOCUMENTATION
+            .iter()
+            .map(|(kind, group, documentation)| (*kind, *group, documentation.to_string()))
+            .collect();
+        assert_eq!(table, modules);
+    }
 }
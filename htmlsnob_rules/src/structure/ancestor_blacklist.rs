@@ -40,12 +40,10 @@ impl RuleTrait for Rule {
             ),
         };
 
-        let open_tag_names = parse_state.open_tag_names();
-
         for (tag_name, blacklist) in &self.tags {
             if open_tag.name == *tag_name {
-                for ancestor in &open_tag_names {
-                    if blacklist.contains(ancestor) {
+                for ancestor in parse_state.ancestors(open_tag) {
+                    if blacklist.contains(&ancestor.name) {
                         return Some(Warning::from_areas(
                             &self.name,
                             &self.kind,
@@ -54,7 +52,7 @@ impl RuleTrait for Rule {
                                 &self.error_message,
                                 &[
                                     ("tag", open_tag.name.clone()),
-                                    ("ancestor", (*ancestor).clone()),
+                                    ("ancestor", ancestor.name.clone()),
                                 ],
                             ),
                             self.severity.clone(),
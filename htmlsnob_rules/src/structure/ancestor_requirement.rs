@@ -34,13 +34,11 @@ impl RuleTrait for Rule {
     ) -> Option<Warning> {
         let open_tag = open_tag?;
 
-        let open_tag_names = parse_state.open_tag_names();
-
         for (tag_name, required_ancestors) in &self.tags {
             if open_tag.name == *tag_name {
-                if open_tag_names
-                    .iter()
-                    .any(|ancestor| required_ancestors.contains(ancestor))
+                if parse_state
+                    .ancestors(open_tag)
+                    .any(|ancestor| required_ancestors.contains(&ancestor.name))
                 {
                     continue;
                 }
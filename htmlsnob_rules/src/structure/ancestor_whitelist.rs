@@ -0,0 +1,102 @@
+use htmlsnob::ast::{CloseTag, OpenTag};
+use htmlsnob::dynamic_format::dynamic_format;
+use htmlsnob::parser::ParseState;
+use htmlsnob::rule_trait::RuleTrait;
+use htmlsnob::warning::Warning;
+use htmlsnob::WarningSeverity;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Enforces that a tag only has the specified ancestor tags at any level.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Rule {
+    #[serde(default)]
+    name: String,
+    kind: String,
+    /// A map of tag names to a list of allowed ancestor tag names.
+    tags: HashMap<String, Vec<String>>,
+    #[serde(default)]
+    severity: WarningSeverity,
+    #[serde(default = "default_error_message")]
+    pub error_message: String,
+}
+
+fn default_error_message() -> String {
+    "Tag `{tag}` is not allowed within `{ancestor}`, its ancestors must be one of: {whitelist}"
+        .to_string()
+}
+
+impl RuleTrait for Rule {
+    fn apply_element(
+        &self,
+        open_tag: &OpenTag,
+        close_tag: Option<&CloseTag>,
+        parse_state: &ParseState,
+    ) -> Option<Warning> {
+        let whitelist = self.tags.get(&open_tag.name)?;
+        let ancestor = parse_state
+            .ancestors(open_tag)
+            .find(|ancestor| !whitelist.contains(&ancestor.name))?;
+
+        Some(Warning::from_areas(
+            &self.name,
+            &self.kind,
+            &open_tag.element_areas(close_tag),
+            &dynamic_format(
+                &self.error_message,
+                &[
+                    ("tag", open_tag.name.clone()),
+                    ("ancestor", ancestor.name.clone()),
+                    ("whitelist", whitelist.join(", ")),
+                ],
+            ),
+            self.severity.clone(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::registry;
+    use htmlsnob::test_utils::tests::test_case;
+
+    const CONFIG: &str = r#"
+        template_language = "handlebars"
+
+        [[rules]]
+        kind = "ancestor_whitelist"
+        [rules.tags]
+        title = ["html", "head"]
+    "#;
+
+    #[test]
+    fn good_case() {
+        test_case(
+            "<html><head><title></title></head></html><title></title>",
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn bad_case() {
+        test_case(
+            r#"
+            <html><body><title> </title></body></html>
+                        ------- --------
+                        ancestor_whitelist: Tag `title` is not allowed within `body`, its ancestors must be one of: html, head
+            "#,
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn good_case_template_expressions() {
+        test_case(
+            "<head>{{#if title}}<title></title>{{/if}}</head>",
+            CONFIG,
+            &registry(),
+        )
+    }
+}
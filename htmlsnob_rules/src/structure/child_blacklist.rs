@@ -1,49 +1,62 @@
-use std::collections::HashMap;
-
-use htmlsnob::ast::Element;
+use htmlsnob::ast::{CloseTag, OpenTag};
+use htmlsnob::dynamic_format::dynamic_format;
+use htmlsnob::parser::ParseState;
+use htmlsnob::rule_trait::RuleTrait;
 use htmlsnob::warning::Warning;
 use htmlsnob::WarningSeverity;
 use serde::Deserialize;
+use std::collections::HashMap;
 
-/// Checks if an element is NOT a blacklisted child of its parent element.
+/// Enforces that a tag does not have any of the specified direct child tags.
 #[derive(Debug, Clone, Deserialize)]
 pub struct Rule {
-    /// A map of parent tag names to a list of forbidden child tag names
+    #[serde(default)]
+    name: String,
+    kind: String,
+    /// A map of parent tag names to a list of disallowed child tag names.
     tags: HashMap<String, Vec<String>>,
     #[serde(default)]
     severity: WarningSeverity,
+    #[serde(default = "default_error_message")]
+    pub error_message: String,
 }
 
-impl Rule {
-    pub fn check(&self, element: &mut Element, ancestor_tag_names: &[String]) -> Vec<Warning> {
-        if ancestor_tag_names.is_empty() {
-            return Vec::new();
-        }
-        let parent_name = ancestor_tag_names.last().unwrap();
-
-        let mut warnings = Vec::new();
+fn default_error_message() -> String {
+    "`{child}` is not allowed in `{parent}`".to_string()
+}
 
-        if let Some(blacklist) = self.tags.get(parent_name) {
-            if blacklist.contains(&element.name) {
-                warnings.push(Warning::from_element(
-                    element,
-                    &format!(
-                        "Invalid child element: {} is forbidden in {}, blacklisted elements are: {}",
-                        element.name,
-                        parent_name,
-                        blacklist.join(", ")
-                    ),
-                    self.severity.clone(),
-                ));
-            }
+impl RuleTrait for Rule {
+    fn apply_element(
+        &self,
+        open_tag: &OpenTag,
+        close_tag: Option<&CloseTag>,
+        parse_state: &ParseState,
+    ) -> Option<Warning> {
+        let parent = parse_state.ancestors(open_tag).next()?;
+        let blacklist = self.tags.get(&parent.name)?;
+        if !blacklist.contains(&open_tag.name) {
+            return None;
         }
 
-        warnings
+        Some(Warning::from_areas(
+            &self.name,
+            &self.kind,
+            &open_tag.element_areas(close_tag),
+            &dynamic_format(
+                &self.error_message,
+                &[
+                    ("child", open_tag.name.clone()),
+                    ("parent", parent.name.clone()),
+                ],
+            ),
+            self.severity.clone(),
+        ))
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::registry;
     use htmlsnob::test_utils::tests::test_case;
 
     const CONFIG: &str = r#"
@@ -54,44 +67,59 @@ mod tests {
     "#;
 
     #[test]
-    fn good_case_blacklist() {
-        test_case("<ul><li></li></ul>", CONFIG)
+    fn good_case() {
+        test_case("<ul><li><div></div></li></ul>", CONFIG, &registry())
     }
 
     #[test]
-    fn bad_case_blacklist() {
+    fn bad_case() {
         test_case(
             r#"
-        <ul><div> </div></ul>
-            ----- ------
-        "#,
+            <ul><div> </div></ul>
+                ----- ------
+                child_blacklist: `div` is not allowed in `ul`
+            "#,
             CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn bad_case_template_expressions() {
+        let config = format!("template_language = \"handlebars\"\n{CONFIG}");
+        test_case(
+            r#"
+            <ul>{{#if items}}<div> </div>{{/if}}</ul>
+                             ----- ------
+            "#,
+            &config,
+            &registry(),
         )
     }
 
     const CONFIG_EXPANSIONS: &str = r#"
         [expansions]
-        INLINE = ["span", "a"]
         BLOCK = ["div", "p"]
         [[rules]]
         kind = "child_blacklist"
         [rules.tags]
-        INLINE = ["BLOCK"]
+        span = ["BLOCK"]
     "#;
 
     #[test]
     fn good_case_expansions() {
-        test_case("<div><p><span><a></a></span></p></div>", CONFIG_EXPANSIONS)
+        test_case("<span><a></a></span>", CONFIG_EXPANSIONS, &registry())
     }
 
     #[test]
     fn bad_case_expansions() {
         test_case(
             r#"
-            <a><div><span><p> </p></span></div></a>
-               -----      --- ----       ------
-        "#,
+            <span><p> </p></span>
+                  --- ----
+            "#,
             CONFIG_EXPANSIONS,
+            &registry(),
         )
     }
 }
@@ -1,58 +1,63 @@
-use htmlsnob::ast::Element;
+use htmlsnob::ast::{CloseTag, OpenTag};
 use htmlsnob::dynamic_format::dynamic_format;
+use htmlsnob::parser::ParseState;
+use htmlsnob::rule_trait::RuleTrait;
 use htmlsnob::warning::Warning;
 use htmlsnob::WarningSeverity;
 use serde::Deserialize;
 use std::collections::HashMap;
 
-/// Checks if an element is a valid child of its parent element.
+/// Enforces that a tag only has the specified direct child tags.
 #[derive(Debug, Clone, Deserialize)]
 pub struct Rule {
-    /// A map of parent tag names to a list of allowed child tag names
+    #[serde(default)]
+    name: String,
+    kind: String,
+    /// A map of parent tag names to a list of allowed child tag names.
     tags: HashMap<String, Vec<String>>,
-    #[serde(default = "default_error_message")]
-    error_message: String,
     #[serde(default)]
     severity: WarningSeverity,
+    #[serde(default = "default_error_message")]
+    pub error_message: String,
 }
 
 fn default_error_message() -> String {
-    "{child} is not allowed in {parent}, must be one of: {whitelist}".to_string()
+    "`{child}` is not allowed in `{parent}`, must be one of: {whitelist}".to_string()
 }
 
-impl Rule {
-    pub fn check(&self, element: &mut Element, ancestor_tag_names: &[String]) -> Vec<Warning> {
-        if ancestor_tag_names.is_empty() {
-            return Vec::new();
-        }
-        let parent_name = ancestor_tag_names.last().unwrap();
-
-        let mut warnings = Vec::new();
-
-        // for category_name in categories_for(parent_name) {
-        if let Some(whitelist) = self.tags.get(parent_name) {
-            if !whitelist.contains(&element.name) {
-                warnings.push(Warning::from_element(
-                    element,
-                    &dynamic_format(
-                        &self.error_message,
-                        &[
-                            ("child", element.name.clone()),
-                            ("parent", parent_name.clone()),
-                            ("whitelist", whitelist.join(", ")),
-                        ],
-                    ),
-                    self.severity.clone(),
-                ));
-            }
+impl RuleTrait for Rule {
+    fn apply_element(
+        &self,
+        open_tag: &OpenTag,
+        close_tag: Option<&CloseTag>,
+        parse_state: &ParseState,
+    ) -> Option<Warning> {
+        let parent = parse_state.ancestors(open_tag).next()?;
+        let whitelist = self.tags.get(&parent.name)?;
+        if whitelist.contains(&open_tag.name) {
+            return None;
         }
 
-        warnings
+        Some(Warning::from_areas(
+            &self.name,
+            &self.kind,
+            &open_tag.element_areas(close_tag),
+            &dynamic_format(
+                &self.error_message,
+                &[
+                    ("child", open_tag.name.clone()),
+                    ("parent", parent.name.clone()),
+                    ("whitelist", whitelist.join(", ")),
+                ],
+            ),
+            self.severity.clone(),
+        ))
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::registry;
     use htmlsnob::test_utils::tests::test_case;
 
     const CONFIG: &str = r#"
@@ -64,17 +69,94 @@ mod tests {
 
     #[test]
     fn good_case() {
-        test_case("<ul><li></li></ul>", CONFIG)
+        test_case(
+            "<ul><li><div></div></li></ul><div></div>",
+            CONFIG,
+            &registry(),
+        )
     }
 
     #[test]
     fn bad_case() {
         test_case(
             r#"
-        <ul><div> </div></ul>
-            ----- ------
-        "#,
+            <ul><div> </div></ul>
+                ----- ------
+                child_whitelist: `div` is not allowed in `ul`, must be one of: li
+            "#,
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn bad_case_self_closed() {
+        test_case(
+            r#"
+            <ul><br/></ul>
+                -----
+            "#,
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn bad_case_void_element() {
+        test_case(
+            r#"
+            <ul><li><br></li><img src="a.png"></ul>
+                             -----------------
+            "#,
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn bad_case_unclosed() {
+        test_case(
+            r#"
+            <ul><div><span></ul>
+                -----
+                child_whitelist: `div` is not allowed in `ul`, must be one of: li
+            "#,
             CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn bad_case_disabled_by_directive() {
+        test_case(
+            r#"
+            <ul><!-- htmlsnob-disable child_whitelist --><div><!-- htmlsnob-enable --></div><p> </p></ul>
+                                                                                            --- ----
+            "#,
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn bad_case_disabled_before_close_tag() {
+        test_case(
+            r#"
+            <ul><div><!-- htmlsnob-disable child_whitelist --></div></ul>
+                -----                                         ------
+            "#,
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn good_case_template_expressions() {
+        let config = format!("template_language = \"handlebars\"\n{CONFIG}");
+        test_case(
+            "<ul>{{#if items}}<li></li>{{/if}}</ul>",
+            &config,
+            &registry(),
         )
     }
 
@@ -89,17 +171,22 @@ mod tests {
 
     #[test]
     fn good_case_expansions() {
-        test_case("<p><span><a></a></span></p>", CONFIG_EXPANSIONS)
+        test_case(
+            "<p><span><a></a></span></p>",
+            CONFIG_EXPANSIONS,
+            &registry(),
+        )
     }
 
     #[test]
     fn bad_case_expansions() {
         test_case(
             r#"
-            <p><div><span></span></div></p>
-               -----             ------
-        "#,
+            <p><b><span></span></b></p>
+               ---             ----
+            "#,
             CONFIG_EXPANSIONS,
+            &registry(),
         )
     }
 }
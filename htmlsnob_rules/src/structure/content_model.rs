@@ -0,0 +1,411 @@
+use htmlsnob::ast::{CloseTag, OpenTag, Text};
+use htmlsnob::dynamic_format::dynamic_format;
+use htmlsnob::parser::ParseState;
+use htmlsnob::rule_trait::RuleTrait;
+use htmlsnob::warning::Warning;
+use htmlsnob::WarningSeverity;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Enforces that tags only contain the tags and text that the content models of their ancestors
+/// permit. SVG and MathML elements are not checked. The default data is generated from the HTML
+/// specification by `cargo run --bin content_model_from_htmlspec`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Rule {
+    #[serde(default)]
+    name: String,
+    kind: String,
+    /// A map of tag names to their content categories, like `flow` and `phrasing`. Tags without
+    /// categories, like custom elements, are not checked.
+    categories: HashMap<String, Vec<String>>,
+    /// Categories that tags only belong to when they have an attribute, like `img` that is only
+    /// interactive with a `usemap`. A map of tag names to categories to attribute names.
+    #[serde(default)]
+    conditional_categories: HashMap<String, HashMap<String, String>>,
+    /// A map of tag names to the categories and tag names of their permitted children.
+    /// `transparent` permits the content that the parent permits. Tags without permitted content
+    /// may have any children.
+    permitted_content: HashMap<String, Vec<String>>,
+    /// Permitted content that depends on the parent, like `dt` and `dd` in a `div` within a `dl`.
+    /// A map of tag names to parent tag names to permitted content.
+    #[serde(default)]
+    conditional_permitted_content: HashMap<String, HashMap<String, Vec<String>>>,
+    /// A map of tag names to categories and tag names that are not allowed at any depth within
+    /// them, like interactive content within `button`.
+    #[serde(default)]
+    disallowed_descendants: HashMap<String, Vec<String>>,
+    #[serde(default)]
+    severity: WarningSeverity,
+    #[serde(default = "default_error_message")]
+    pub error_message: String,
+    #[serde(default = "default_text_error_message")]
+    pub text_error_message: String,
+    #[serde(default = "default_descendant_error_message")]
+    pub descendant_error_message: String,
+}
+
+fn default_error_message() -> String {
+    "`{tag}` is not allowed in `{parent}`, permitted content: {permitted}".to_string()
+}
+
+fn default_text_error_message() -> String {
+    "Text is not allowed in `{parent}`, permitted content: {permitted}".to_string()
+}
+
+fn default_descendant_error_message() -> String {
+    "`{tag}` is not allowed within `{ancestor}`".to_string()
+}
+
+impl Rule {
+    /// Whether the tag is one of the categories or tag names
+    fn is_one_of(&self, open_tag: &OpenTag, names: &[&String]) -> bool {
+        if names.contains(&&open_tag.name) {
+            return true;
+        }
+
+        let Some(categories) = self.categories.get(&open_tag.name) else {
+            return false;
+        };
+        let conditions = self.conditional_categories.get(&open_tag.name);
+
+        categories.iter().any(|category| {
+            let has_condition = match conditions.and_then(|conditions| conditions.get(category)) {
+                Some(attribute) => has_attribute(open_tag, attribute),
+                None => true,
+            };
+            has_condition && names.contains(&category)
+        })
+    }
+
+    /// The categories and tag names that the innermost ancestor permits, following transparent
+    /// content models up through the ancestors. `None` when anything is permitted.
+    fn permitted_content<'a, 'b>(
+        &'a self,
+        ancestors: impl Iterator<Item = &'b OpenTag>,
+    ) -> Option<Vec<&'a String>> {
+        let ancestors: Vec<&OpenTag> = ancestors.collect();
+        let mut permitted = Vec::new();
+        for (position, ancestor) in ancestors.iter().enumerate() {
+            let content = ancestors
+                .get(position + 1)
+                .and_then(|parent| {
+                    self.conditional_permitted_content
+                        .get(&ancestor.name)?
+                        .get(&parent.name)
+                })
+                .or_else(|| self.permitted_content.get(&ancestor.name))?;
+            permitted.extend(content.iter().filter(|name| *name != "transparent"));
+            if !content.iter().any(|name| name == "transparent") {
+                return Some(permitted);
+            }
+        }
+        // Transparent tags at the top level may be rendered into any parent
+        None
+    }
+
+    fn permitted_content_message(
+        &self,
+        open_tag: &OpenTag,
+        parse_state: &ParseState,
+    ) -> Option<String> {
+        let parent = parse_state.ancestors(open_tag).next()?;
+        let permitted = self.permitted_content(parse_state.ancestors(open_tag))?;
+        if self.is_one_of(open_tag, &permitted) {
+            return None;
+        }
+
+        Some(dynamic_format(
+            &self.error_message,
+            &[
+                ("tag", open_tag.name.clone()),
+                ("parent", parent.name.clone()),
+                ("permitted", permitted_list(&permitted)),
+            ],
+        ))
+    }
+
+    fn disallowed_descendant_message(
+        &self,
+        open_tag: &OpenTag,
+        parse_state: &ParseState,
+    ) -> Option<String> {
+        let ancestor = parse_state.ancestors(open_tag).find(|ancestor| {
+            self.disallowed_descendants
+                .get(&ancestor.name)
+                .is_some_and(|disallowed| {
+                    self.is_one_of(open_tag, &disallowed.iter().collect::<Vec<&String>>())
+                })
+        })?;
+
+        Some(dynamic_format(
+            &self.descendant_error_message,
+            &[
+                ("tag", open_tag.name.clone()),
+                ("ancestor", ancestor.name.clone()),
+            ],
+        ))
+    }
+}
+
+/// Text is permitted where flow or phrasing content is, and where only text is
+fn permits_text(permitted: &[&String]) -> bool {
+    permitted.is_empty()
+        || permitted
+            .iter()
+            .any(|name| *name == "flow" || *name == "phrasing")
+}
+
+fn permitted_list(permitted: &[&String]) -> String {
+    match permitted.is_empty() {
+        true => "text".to_string(),
+        false => permitted
+            .iter()
+            .map(|name| name.as_str())
+            .collect::<Vec<&str>>()
+            .join(", "),
+    }
+}
+
+fn has_attribute(open_tag: &OpenTag, name: &str) -> bool {
+    open_tag.attributes.iter().any(|attribute| {
+        attribute
+            .name
+            .left()
+            .is_some_and(|attribute_name| attribute_name.content.eq_ignore_ascii_case(name))
+    })
+}
+
+impl RuleTrait for Rule {
+    fn apply_element(
+        &self,
+        open_tag: &OpenTag,
+        close_tag: Option<&CloseTag>,
+        parse_state: &ParseState,
+    ) -> Option<Warning> {
+        // The content of SVG and MathML elements is per their own specs
+        if !self.categories.contains_key(&open_tag.name)
+            || parse_state.element_namespace(&open_tag.name).is_foreign()
+        {
+            return None;
+        }
+
+        let message = self
+            .permitted_content_message(open_tag, parse_state)
+            .or_else(|| self.disallowed_descendant_message(open_tag, parse_state))?;
+
+        Some(Warning::from_areas(
+            &self.name,
+            &self.kind,
+            &open_tag.element_areas(close_tag),
+            &message,
+            self.severity.clone(),
+        ))
+    }
+
+    fn apply_text(&self, text: &mut Text, parse_state: &ParseState) -> Option<Warning> {
+        if text.content.trim().is_empty() || parse_state.namespace().is_foreign() {
+            return None;
+        }
+
+        let parent = parse_state.open_tags().next()?;
+        let permitted = self.permitted_content(parse_state.open_tags())?;
+        if permits_text(&permitted) {
+            return None;
+        }
+
+        Some(Warning::from_area(
+            &self.name,
+            &self.kind,
+            text.area.clone(),
+            &dynamic_format(
+                &self.text_error_message,
+                &[
+                    ("parent", parent.name.clone()),
+                    ("permitted", permitted_list(&permitted)),
+                ],
+            ),
+            self.severity.clone(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::registry;
+    use htmlsnob::test_utils::tests::test_case;
+
+    const CONFIG: &str = r#"
+        template_language = "handlebars"
+
+        [[rules]]
+        kind = "content_model"
+        [rules.categories]
+        a = ["flow", "phrasing", "interactive"]
+        b = ["flow", "phrasing"]
+        button = ["flow", "phrasing", "interactive"]
+        div = ["flow"]
+        img = ["flow", "phrasing", "interactive"]
+        li = []
+        option = []
+        p = ["flow"]
+        select = ["flow", "phrasing", "interactive"]
+        ul = ["flow"]
+        [rules.conditional_categories]
+        a = { interactive = "href" }
+        img = { interactive = "usemap" }
+        [rules.permitted_content]
+        a = ["transparent"]
+        b = ["phrasing"]
+        button = ["phrasing"]
+        div = ["flow"]
+        li = ["flow"]
+        option = []
+        p = ["phrasing"]
+        select = ["option"]
+        ul = ["li"]
+        [rules.disallowed_descendants]
+        a = ["interactive", "a"]
+        button = ["interactive"]
+    "#;
+
+    #[test]
+    fn good_case() {
+        test_case(
+            r#"<div><p><b>Text</b><a href="/"><img/></a></p><ul><li><div></div></li></ul></div>
+            <button><b><img/></b></button><a><div></div></a><custom-element><div></div></custom-element>"#,
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn bad_case() {
+        test_case(
+            r#"
+            <b><div> </div></b>
+               ----- ------
+               content_model: `div` is not allowed in `b`, permitted content: phrasing
+            "#,
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn bad_case_text_only() {
+        test_case(
+            r#"
+            <select><option><b> </b></option></select>
+                            --- ----
+                            content_model: `b` is not allowed in `option`, permitted content: text
+            "#,
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn bad_case_text() {
+        test_case(
+            r#"
+            <div><p>Text</p><ul> Text <li>Text</li></ul></div>
+                                 -----
+                                 content_model: Text is not allowed in `ul`, permitted content: li
+            "#,
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn good_case_foreign_elements() {
+        test_case(
+            r##"<button><svg><a href="#"><rect></rect>Text</a></svg></button><ul><svg><text>Text</text></svg></ul>"##,
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn bad_case_transparent() {
+        test_case(
+            r#"
+            <p><a><div> </div></a></p>
+                  ----- ------
+                  content_model: `div` is not allowed in `a`, permitted content: phrasing
+            "#,
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn bad_case_interactive_descendant() {
+        test_case(
+            r#"
+            <button><b><a href="/"> </a></b></button>
+                       ------------ ----
+                       content_model: `a` is not allowed within `button`
+            "#,
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn bad_case_template_expressions() {
+        test_case(
+            r#"
+            <ul>{{#each items}}<p> </p>{{/each}}</ul>
+                               --- ----
+            "#,
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn default_data() {
+        let config = format!(
+            "template_language = \"handlebars\"\n{}",
+            include_str!("../../../default_config/content_model.toml")
+        );
+
+        test_case(
+            r#"
+            <html><head><title>Title</title></head><body><form><button><span><a href="/"> </a></span></button></form></body></html>
+                                                                             ------------ ----
+                                                                             content_model: `a` is not allowed within `button`
+            "#,
+            &config,
+            &registry(),
+        );
+        test_case(
+            r#"
+            <table><tbody><tr><td><ul><li><span><div> </div></span></li></ul></td></tr></tbody></table>
+                                                ----- ------
+            "#,
+            &config,
+            &registry(),
+        );
+        test_case(
+            "<dl><div><dt>Term</dt><dd>Definition</dd></div></dl>",
+            &config,
+            &registry(),
+        );
+        test_case(
+            r#"
+            <div><dt>Term</dt></div>
+                 ----    -----
+                 content_model: `dt` is not allowed in `div`, permitted content: flow
+            "#,
+            &config,
+            &registry(),
+        );
+        test_case(
+            r##"<button><svg><a href="#"><rect></rect></a></svg></button><script>let a = 1;</script>"##,
+            &config,
+            &registry(),
+        );
+    }
+}
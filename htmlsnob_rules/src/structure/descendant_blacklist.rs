@@ -0,0 +1,100 @@
+use htmlsnob::ast::{CloseTag, OpenTag};
+use htmlsnob::dynamic_format::dynamic_format;
+use htmlsnob::parser::ParseState;
+use htmlsnob::rule_trait::RuleTrait;
+use htmlsnob::warning::Warning;
+use htmlsnob::WarningSeverity;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Enforces that a tag does not contain any of the specified descendant tags at any depth.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Rule {
+    #[serde(default)]
+    name: String,
+    kind: String,
+    /// A map of tag names to a list of disallowed descendant tag names.
+    tags: HashMap<String, Vec<String>>,
+    #[serde(default)]
+    severity: WarningSeverity,
+    #[serde(default = "default_error_message")]
+    pub error_message: String,
+}
+
+fn default_error_message() -> String {
+    "Tag `{tag}` is not allowed within `{ancestor}`".to_string()
+}
+
+impl RuleTrait for Rule {
+    fn apply_element(
+        &self,
+        open_tag: &OpenTag,
+        close_tag: Option<&CloseTag>,
+        parse_state: &ParseState,
+    ) -> Option<Warning> {
+        let ancestor = parse_state.ancestors(open_tag).find(|ancestor| {
+            self.tags
+                .get(&ancestor.name)
+                .is_some_and(|blacklist| blacklist.contains(&open_tag.name))
+        })?;
+
+        Some(Warning::from_areas(
+            &self.name,
+            &self.kind,
+            &open_tag.element_areas(close_tag),
+            &dynamic_format(
+                &self.error_message,
+                &[
+                    ("tag", open_tag.name.clone()),
+                    ("ancestor", ancestor.name.clone()),
+                ],
+            ),
+            self.severity.clone(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::registry;
+    use htmlsnob::test_utils::tests::test_case;
+
+    const CONFIG: &str = r#"
+        template_language = "handlebars"
+
+        [[rules]]
+        kind = "descendant_blacklist"
+        [rules.tags]
+        form = ["form"]
+    "#;
+
+    #[test]
+    fn good_case() {
+        test_case("<form></form><div><form></form></div>", CONFIG, &registry())
+    }
+
+    #[test]
+    fn bad_case() {
+        test_case(
+            r#"
+            <form><div><p><form> </form></p></div></form>
+                          ------ -------
+                          descendant_blacklist: Tag `form` is not allowed within `form`
+            "#,
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn bad_case_template_expressions() {
+        test_case(
+            r#"
+            <form>{{#if search}}<div><form> </form></div>{{/if}}</form>
+                                     ------ -------
+            "#,
+            CONFIG,
+            &registry(),
+        )
+    }
+}
@@ -0,0 +1,107 @@
+use htmlsnob::ast::{CloseTag, OpenTag};
+use htmlsnob::dynamic_format::dynamic_format;
+use htmlsnob::parser::ParseState;
+use htmlsnob::rule_trait::RuleTrait;
+use htmlsnob::warning::Warning;
+use htmlsnob::WarningSeverity;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Enforces that a tag only contains the specified descendant tags at any depth.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Rule {
+    #[serde(default)]
+    name: String,
+    kind: String,
+    /// A map of tag names to a list of allowed descendant tag names.
+    tags: HashMap<String, Vec<String>>,
+    #[serde(default)]
+    severity: WarningSeverity,
+    #[serde(default = "default_error_message")]
+    pub error_message: String,
+}
+
+fn default_error_message() -> String {
+    "Tag `{tag}` is not allowed within `{ancestor}`, must be one of: {whitelist}".to_string()
+}
+
+impl RuleTrait for Rule {
+    fn apply_element(
+        &self,
+        open_tag: &OpenTag,
+        close_tag: Option<&CloseTag>,
+        parse_state: &ParseState,
+    ) -> Option<Warning> {
+        let (ancestor, whitelist) = parse_state
+            .ancestors(open_tag)
+            .filter_map(|ancestor| Some((ancestor, self.tags.get(&ancestor.name)?)))
+            .find(|(_, whitelist)| !whitelist.contains(&open_tag.name))?;
+
+        Some(Warning::from_areas(
+            &self.name,
+            &self.kind,
+            &open_tag.element_areas(close_tag),
+            &dynamic_format(
+                &self.error_message,
+                &[
+                    ("tag", open_tag.name.clone()),
+                    ("ancestor", ancestor.name.clone()),
+                    ("whitelist", whitelist.join(", ")),
+                ],
+            ),
+            self.severity.clone(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::registry;
+    use htmlsnob::test_utils::tests::test_case;
+
+    const CONFIG: &str = r#"
+        template_language = "handlebars"
+
+        [expansions]
+        PHRASING = ["span", "b", "i", "img"]
+
+        [[rules]]
+        kind = "descendant_whitelist"
+        [rules.tags]
+        button = ["PHRASING"]
+    "#;
+
+    #[test]
+    fn good_case() {
+        test_case(
+            "<div><p></p></div><button><span><b></b></span><img/></button>",
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn bad_case() {
+        test_case(
+            r#"
+            <button><span><div> </div></span></button>
+                          ----- ------
+                          descendant_whitelist: Tag `div` is not allowed within `button`, must be one of: span, b, i, img
+            "#,
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn bad_case_template_expressions() {
+        test_case(
+            r#"
+            <button>{{#if icon}}<p> </p>{{/if}}</button>
+                                --- ----
+            "#,
+            CONFIG,
+            &registry(),
+        )
+    }
+}
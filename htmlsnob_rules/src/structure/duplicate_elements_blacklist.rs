@@ -91,12 +91,25 @@ mod tests {
     fn bad_case() {
         test_case(
             r#"
-        <title> </title> <title> <title> </title>
-                         ------- ------- --------
+        <title> </title> <title> </title>
+                         ------- --------
                          duplicate_elements_blacklist: `title` is not allowed to occur more than once
         "#,
             CONFIG,
             &registry(),
         )
     }
+
+    #[test]
+    fn bad_case_unclosed() {
+        test_case(
+            r#"
+        <main> </main> <main> <main> </main>
+                       ------ ------ -------
+                       duplicate_elements_blacklist: `main` is not allowed to occur more than once
+        "#,
+            CONFIG,
+            &registry(),
+        )
+    }
 }
@@ -0,0 +1,100 @@
+use htmlsnob::ast::{CloseTag, OpenTag};
+use htmlsnob::dynamic_format::dynamic_format;
+use htmlsnob::parser::ParseState;
+use htmlsnob::rule_trait::RuleTrait;
+use htmlsnob::warning::Warning;
+use htmlsnob::WarningSeverity;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Enforces that a tag does not have any of the specified tags as its direct parent.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Rule {
+    #[serde(default)]
+    name: String,
+    kind: String,
+    /// A map of tag names to a list of disallowed parent tag names.
+    tags: HashMap<String, Vec<String>>,
+    #[serde(default)]
+    severity: WarningSeverity,
+    #[serde(default = "default_error_message")]
+    pub error_message: String,
+}
+
+fn default_error_message() -> String {
+    "`{tag}` is not allowed in `{parent}`".to_string()
+}
+
+impl RuleTrait for Rule {
+    fn apply_element(
+        &self,
+        open_tag: &OpenTag,
+        close_tag: Option<&CloseTag>,
+        parse_state: &ParseState,
+    ) -> Option<Warning> {
+        let blacklist = self.tags.get(&open_tag.name)?;
+        let parent = parse_state.ancestors(open_tag).next()?;
+        if !blacklist.contains(&parent.name) {
+            return None;
+        }
+
+        Some(Warning::from_areas(
+            &self.name,
+            &self.kind,
+            &open_tag.element_areas(close_tag),
+            &dynamic_format(
+                &self.error_message,
+                &[
+                    ("tag", open_tag.name.clone()),
+                    ("parent", parent.name.clone()),
+                ],
+            ),
+            self.severity.clone(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::registry;
+    use htmlsnob::test_utils::tests::test_case;
+
+    const CONFIG: &str = r#"
+        template_language = "handlebars"
+
+        [[rules]]
+        kind = "parent_blacklist"
+        [rules.tags]
+        div = ["p", "span"]
+    "#;
+
+    #[test]
+    fn good_case() {
+        test_case("<p><b><div></div></b></p><div></div>", CONFIG, &registry())
+    }
+
+    #[test]
+    fn bad_case() {
+        test_case(
+            r#"
+            <span><div> </div></span>
+                  ----- ------
+                  parent_blacklist: `div` is not allowed in `span`
+            "#,
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn bad_case_template_expressions() {
+        test_case(
+            r#"
+            <span>{{#each items}}<div> </div>{{/each}}</span>
+                                 ----- ------
+            "#,
+            CONFIG,
+            &registry(),
+        )
+    }
+}
@@ -0,0 +1,114 @@
+use htmlsnob::ast::{CloseTag, OpenTag};
+use htmlsnob::dynamic_format::dynamic_format;
+use htmlsnob::parser::ParseState;
+use htmlsnob::rule_trait::RuleTrait;
+use htmlsnob::warning::Warning;
+use htmlsnob::WarningSeverity;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Enforces that a tag has one of the specified tags as its direct parent. Tags at the top level
+/// of a document are allowed, as templates are often partials that are rendered inside a parent.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Rule {
+    #[serde(default)]
+    name: String,
+    kind: String,
+    /// A map of tag names to a list of allowed parent tag names.
+    tags: HashMap<String, Vec<String>>,
+    #[serde(default)]
+    severity: WarningSeverity,
+    #[serde(default = "default_error_message")]
+    pub error_message: String,
+}
+
+fn default_error_message() -> String {
+    "`{tag}` is not allowed in `{parent}`, its parent must be one of: {whitelist}".to_string()
+}
+
+impl RuleTrait for Rule {
+    fn apply_element(
+        &self,
+        open_tag: &OpenTag,
+        close_tag: Option<&CloseTag>,
+        parse_state: &ParseState,
+    ) -> Option<Warning> {
+        let whitelist = self.tags.get(&open_tag.name)?;
+        let parent = parse_state.ancestors(open_tag).next()?;
+        if whitelist.contains(&parent.name) {
+            return None;
+        }
+
+        Some(Warning::from_areas(
+            &self.name,
+            &self.kind,
+            &open_tag.element_areas(close_tag),
+            &dynamic_format(
+                &self.error_message,
+                &[
+                    ("tag", open_tag.name.clone()),
+                    ("parent", parent.name.clone()),
+                    ("whitelist", whitelist.join(", ")),
+                ],
+            ),
+            self.severity.clone(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::registry;
+    use htmlsnob::test_utils::tests::test_case;
+
+    const CONFIG: &str = r#"
+        [[rules]]
+        kind = "parent_whitelist"
+        [rules.tags]
+        li = ["ul", "ol", "menu"]
+    "#;
+
+    #[test]
+    fn good_case() {
+        test_case(
+            "<ul><li></li></ul><ol><li></li></ol><li></li>",
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn bad_case() {
+        test_case(
+            r#"
+            <ul><div><li> </li></div></ul>
+                     ---- -----
+                     parent_whitelist: `li` is not allowed in `div`, its parent must be one of: ul, ol, menu
+            "#,
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn bad_case_unclosed() {
+        test_case(
+            r#"
+            <div><li>
+                 ----
+            "#,
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn good_case_template_expressions() {
+        let config = format!("template_language = \"handlebars\"\n{CONFIG}");
+        test_case(
+            "<ul>{{#each items}}<li></li>{{/each}}</ul>",
+            &config,
+            &registry(),
+        )
+    }
+}
@@ -6,7 +6,8 @@ use htmlsnob::warning::Warning;
 use htmlsnob::WarningSeverity;
 use serde::Deserialize;
 
-/// Enforces that open tags have a corresponding close tag.
+/// Enforces that open tags have a corresponding close tag. Elements whose close tags are optional,
+/// like `li` and `p`, may omit them where the HTML spec allows it.
 #[derive(Debug, Clone, Deserialize)]
 pub struct Rule {
     #[serde(default)]
@@ -41,7 +42,9 @@ impl RuleTrait for Rule {
             return None;
         }
 
-        if close_tag.is_some() {
+        // Implied close tags are only allowed where the close tag is optional, so `</p>` is
+        // required within `a`
+        if close_tag.is_some_and(|close_tag| !close_tag.is_implied || close_tag.is_optional) {
             return None;
         }
 
@@ -75,13 +78,81 @@ mod tests {
         test_case("<p></p>", CONFIG, &registry())
     }
 
+    #[test]
+    fn good_case_optional_close_tags() {
+        test_case(
+            r#"<html><head><title>Title</title><body><ul><li>One<li>Two</ul><p>Text<div></div>
+            <dl><dt>Term<dd>Definition</dl><table><tr><td>One<td>Two<tr><th>Three</table><p>End"#,
+            CONFIG,
+            &registry(),
+        )
+    }
+
     #[test]
     fn bad_case() {
         test_case(
             r#"
-            <p><br>
-            ---
-            missing_close_tag_disallowed: Open tag `p` is missing close tag
+            <div><p><br>
+            -----
+            missing_close_tag_disallowed: Open tag `div` is missing close tag
+            "#,
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn bad_case_closed_by_outer_close_tag() {
+        test_case(
+            r#"
+            <ul><div> <span></ul>
+                ----- ------
+            "#,
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn good_case_disabled_by_directive() {
+        test_case(
+            "<!-- htmlsnob-disable missing_close_tag_disallowed --><div>",
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn bad_case_disabled_after_open_tag() {
+        test_case(
+            r#"
+            <div><!-- htmlsnob-disable missing_close_tag_disallowed --><span>
+            -----
+            "#,
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn bad_case_p_in_a() {
+        test_case(
+            r#"
+            <a><p>Text</a>
+               ---
+               missing_close_tag_disallowed: Open tag `p` is missing close tag
+            "#,
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn bad_case_p_in_custom_element() {
+        test_case(
+            r#"
+            <my-element><p>Text</my-element>
+                        ---
             "#,
             CONFIG,
             &registry(),
@@ -89,7 +89,7 @@ impl RuleTrait for Rule {
         self.apply(
             &mut comment.is_missing_end_bracket,
             &comment.area,
-            "Comment",
+            comment.kind.name(),
         )
     }
 }
@@ -141,6 +141,24 @@ mod tests {
         )
     }
 
+    #[test]
+    fn bad_case_close_tag_followed_by_newline() {
+        test_case_autofix(
+            r#"
+            <div><p></p
+                    ---
+                    missing_end_bracket_disallowed: Close tag `p` is missing end bracket
+            </div>
+            "#,
+            r#"
+            <div><p></p>
+            </div>
+            "#,
+            CONFIG,
+            &registry(),
+        )
+    }
+
     #[test]
     fn bad_case_template_expression_not_autofixed() {
         test_case_autofix(
@@ -182,4 +200,18 @@ mod tests {
             &registry(),
         )
     }
+
+    #[test]
+    fn bad_case_cdata() {
+        test_case_autofix(
+            r#"
+            <svg><![CDATA[ x < y
+                 ---------------
+                 missing_end_bracket_disallowed: CDATA section is missing end bracket
+            "#,
+            r#"<svg><![CDATA[ x < y]]>"#,
+            CONFIG,
+            &registry(),
+        )
+    }
 }
@@ -1,6 +1,6 @@
 use htmlsnob::ast::CloseTag;
 use htmlsnob::dynamic_format::dynamic_format;
-use htmlsnob::parser::ParseState;
+use htmlsnob::parser::{is_void_element, ParseState};
 use htmlsnob::rule_trait::RuleTrait;
 use htmlsnob::warning::Warning;
 use htmlsnob::WarningSeverity;
@@ -28,7 +28,9 @@ impl RuleTrait for Rule {
         close_tag: &mut CloseTag,
         _parse_state: &ParseState,
     ) -> Option<Warning> {
-        if close_tag.open_tag_index.is_some() {
+        // Void elements are never open, their close tags are flagged by
+        // void_element_close_tag_disallowed
+        if close_tag.open_tag_index.is_some() || is_void_element(&close_tag.name) {
             return None;
         }
 
@@ -61,6 +63,11 @@ mod tests {
         test_case("<p></p>", CONFIG, &registry())
     }
 
+    #[test]
+    fn good_case_void_close_tag() {
+        test_case("<br></br>", CONFIG, &registry())
+    }
+
     #[test]
     fn bad_case() {
         test_case(
@@ -0,0 +1,166 @@
+use htmlsnob::ast::{CloseTag, Node};
+use htmlsnob::dynamic_format::dynamic_format;
+use htmlsnob::parser::ParseState;
+use htmlsnob::rule_trait::RuleTrait;
+use htmlsnob::warning::Warning;
+use htmlsnob::WarningSeverity;
+use serde::Deserialize;
+
+/// Enforces that optional close tags, like the `</li>` before another `<li>`, are either written
+/// out or omitted.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Rule {
+    #[serde(default)]
+    name: String,
+    kind: String,
+    style: OptionalTagStyle,
+    /// The tags to check, all tags with optional close tags when empty.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default = "default_error_message")]
+    pub error_message: String,
+    #[serde(default)]
+    severity: WarningSeverity,
+    #[serde(default)]
+    pub autofix: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OptionalTagStyle {
+    Explicit,
+    Omitted,
+}
+
+impl std::fmt::Display for OptionalTagStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OptionalTagStyle::Explicit => write!(f, "explicit"),
+            OptionalTagStyle::Omitted => write!(f, "omitted"),
+        }
+    }
+}
+
+fn default_error_message() -> String {
+    "Close tag `</{name}>` must be {style}".to_string()
+}
+
+impl RuleTrait for Rule {
+    fn apply_close_tag(
+        &self,
+        close_tag: &mut CloseTag,
+        parse_state: &ParseState,
+    ) -> Option<Warning> {
+        if !close_tag.is_optional {
+            return None;
+        }
+
+        if !self.tags.is_empty() && !self.tags.contains(&close_tag.name.to_ascii_lowercase()) {
+            return None;
+        }
+
+        let area = match self.style {
+            // Implied close tags aren't in the source, so the open tag is marked instead
+            OptionalTagStyle::Explicit if close_tag.is_implied => {
+                match &parse_state.ast[close_tag.open_tag_index?] {
+                    Node::OpenTag(open_tag) => open_tag.area.clone(),
+                    _ => panic!(
+                        "Expected OpenTag at index {}",
+                        close_tag.open_tag_index.unwrap()
+                    ),
+                }
+            }
+            OptionalTagStyle::Omitted if !close_tag.is_implied => close_tag.area.clone(),
+            _ => return None,
+        };
+
+        if self.autofix {
+            close_tag.is_implied = matches!(self.style, OptionalTagStyle::Omitted);
+        }
+
+        let message = dynamic_format(
+            &self.error_message,
+            &[
+                ("name", close_tag.name.clone()),
+                ("style", self.style.to_string()),
+            ],
+        );
+
+        Some(Warning::from_area(
+            &self.name,
+            &self.kind,
+            area,
+            &message,
+            self.severity.clone(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::registry;
+    use htmlsnob::test_utils::tests::test_case;
+    use htmlsnob::test_utils::tests::test_case_autofix;
+
+    const CONFIG_EXPLICIT: &str = r#"
+        [[rules]]
+        kind = "optional_tag_style"
+        style = "explicit"
+        autofix = true
+    "#;
+
+    const CONFIG_OMITTED: &str = r#"
+        [[rules]]
+        kind = "optional_tag_style"
+        style = "omitted"
+        tags = ["li", "p"]
+        autofix = true
+    "#;
+
+    #[test]
+    fn good_case_explicit() {
+        test_case(
+            "<ul><li>One</li><li>Two</li></ul><p>Three</p>",
+            CONFIG_EXPLICIT,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn bad_case_explicit() {
+        test_case_autofix(
+            r#"
+            <ul><li>One <li>Two</ul><p>Three
+                ----    ----        ---
+                optional_tag_style: Close tag `</li>` must be explicit
+            "#,
+            "<ul><li>One</li><li>Two</li></ul><p>Three</p>",
+            CONFIG_EXPLICIT,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn good_case_omitted() {
+        test_case(
+            r#"<ul><li>One<li>Two</ul><p>Three</p>Four<a><p>Five</p></a>
+            <table><tr><td>Six</td></tr></table>"#,
+            CONFIG_OMITTED,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn bad_case_omitted() {
+        test_case_autofix(
+            r#"
+            <ul><li>One</li> <li>Two</li></ul><p>Three</p><div></div>
+                       -----        -----             ----
+                       optional_tag_style: Close tag `</li>` must be omitted
+            "#,
+            "<ul><li>One<li>Two</ul><p>Three<div></div>",
+            CONFIG_OMITTED,
+            &registry(),
+        )
+    }
+}
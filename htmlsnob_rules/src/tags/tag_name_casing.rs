@@ -33,7 +33,8 @@ impl fmt::Display for TagNameCaseStyle {
     }
 }
 
-/// Enforces that all tag names match a specified casing style.
+/// Enforces that all tag names match a specified casing style. SVG and MathML elements, like
+/// `foreignObject`, must match their case-sensitive names instead.
 #[derive(Debug, Clone, Deserialize)]
 pub struct Rule {
     #[serde(default)]
@@ -55,9 +56,23 @@ fn default_error_message() -> String {
     "Tag name \"{name}\" should be in {style}, change to \"{converted_name}\"".to_string()
 }
 
+impl Rule {
+    /// The name in the casing style, or the case-sensitive name of an SVG or MathML element,
+    /// along with the style for the message. Unknown SVG and MathML elements keep their name.
+    fn convert(&self, name: &str, parse_state: &ParseState) -> (String, String) {
+        let namespace = parse_state.element_namespace(name);
+        if !namespace.is_foreign() {
+            return (self.style.convert(name), self.style.to_string());
+        }
+
+        let converted_name = namespace.element_name(name).unwrap_or(name);
+        (converted_name.to_string(), format!("{namespace} case"))
+    }
+}
+
 impl RuleTrait for Rule {
-    fn apply_open_tag(&self, open_tag: &mut OpenTag, _parse_state: &ParseState) -> Option<Warning> {
-        let converted_name = self.style.convert(&open_tag.name);
+    fn apply_open_tag(&self, open_tag: &mut OpenTag, parse_state: &ParseState) -> Option<Warning> {
+        let (converted_name, style) = self.convert(&open_tag.name, parse_state);
 
         if open_tag.name == converted_name {
             return None;
@@ -67,7 +82,7 @@ impl RuleTrait for Rule {
             &self.error_message,
             &[
                 ("name", open_tag.name.clone()),
-                ("style", self.style.to_string()),
+                ("style", style),
                 ("converted_name", converted_name.clone()),
             ],
         );
@@ -90,11 +105,12 @@ impl RuleTrait for Rule {
     fn apply_close_tag(
         &self,
         close_tag: &mut CloseTag,
-        _parse_state: &ParseState,
+        parse_state: &ParseState,
     ) -> Option<Warning> {
-        let converted_name = self.style.convert(&close_tag.name);
+        let (converted_name, style) = self.convert(&close_tag.name, parse_state);
 
-        if close_tag.name == converted_name {
+        // Implied close tags aren't in the source, and have the name of their open tag
+        if close_tag.is_implied || close_tag.name == converted_name {
             return None;
         }
 
@@ -102,7 +118,7 @@ impl RuleTrait for Rule {
             &self.error_message,
             &[
                 ("name", close_tag.name.clone()),
-                ("style", self.style.to_string()),
+                ("style", style),
                 ("converted_name", converted_name.clone()),
             ],
         );
@@ -203,4 +219,28 @@ mod tests {
             &registry(),
         )
     }
+
+    #[test]
+    fn good_case_foreign_elements() {
+        test_case(
+            r#"<svg><foreignObject><p>Hello</p></foreignObject><linearGradient></linearGradient>
+            <my-Element></my-Element></svg><math><mi>x</mi></math>"#,
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn bad_case_foreign_elements() {
+        test_case_autofix(
+            r#"
+            <svg><lineargradient> </lineargradient> <foreignobject> <P> </P> </foreignobject></svg>
+                 ---------------- ----------------- --------------- --- ---- ----------------
+                 tag_name_casing: Tag name "lineargradient" should be in SVG case, change to "linearGradient"
+            "#,
+            "<svg><linearGradient></linearGradient><foreignObject><p></p></foreignObject></svg>",
+            CONFIG,
+            &registry(),
+        )
+    }
 }
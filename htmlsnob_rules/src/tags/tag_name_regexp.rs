@@ -57,7 +57,8 @@ impl RuleTrait for Rule {
     ) -> Option<Warning> {
         let re = &self.regexp;
 
-        if re.is_match(&close_tag.name) {
+        // Implied close tags aren't in the source, and have the name of their open tag
+        if close_tag.is_implied || re.is_match(&close_tag.name) {
             return None;
         }
 
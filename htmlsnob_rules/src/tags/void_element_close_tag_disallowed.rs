@@ -0,0 +1,89 @@
+use htmlsnob::ast::CloseTag;
+use htmlsnob::dynamic_format::dynamic_format;
+use htmlsnob::parser::{is_void_element, ParseState};
+use htmlsnob::rule_trait::RuleTrait;
+use htmlsnob::warning::Warning;
+use htmlsnob::WarningSeverity;
+use serde::Deserialize;
+
+/// Enforces that void elements, like `br` and `img`, don't have close tags.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Rule {
+    #[serde(default)]
+    name: String,
+    kind: String,
+    #[serde(default = "default_error_message")]
+    pub error_message: String,
+    #[serde(default)]
+    severity: WarningSeverity,
+    #[serde(default)]
+    pub autofix: bool,
+}
+
+fn default_error_message() -> String {
+    "Void element `{name}` must not have a close tag".to_string()
+}
+
+impl RuleTrait for Rule {
+    fn apply_close_tag(
+        &self,
+        close_tag: &mut CloseTag,
+        _parse_state: &ParseState,
+    ) -> Option<Warning> {
+        if close_tag.is_implied || !is_void_element(&close_tag.name) {
+            return None;
+        }
+
+        let message = dynamic_format(&self.error_message, &[("name", close_tag.name.clone())]);
+        let warning = Warning::from_area(
+            &self.name,
+            &self.kind,
+            close_tag.area.clone(),
+            &message,
+            self.severity.clone(),
+        );
+
+        // Omitted close tags aren't printed
+        if self.autofix {
+            close_tag.is_implied = true;
+        }
+
+        Some(warning)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::registry;
+    use htmlsnob::test_utils::tests::test_case;
+    use htmlsnob::test_utils::tests::test_case_autofix;
+
+    const CONFIG: &str = r#"
+        [[rules]]
+        kind = "void_element_close_tag_disallowed"
+        autofix = true
+    "#;
+
+    #[test]
+    fn good_case() {
+        test_case(
+            "<p>One<br>Two<br/><img src=\"a.png\"></p>",
+            CONFIG,
+            &registry(),
+        )
+    }
+
+    #[test]
+    fn bad_case() {
+        test_case_autofix(
+            r#"
+            <p>One<br></br> <img src="a.png"></IMG></p>
+                      -----                  ------
+                      void_element_close_tag_disallowed: Void element `br` must not have a close tag
+            "#,
+            r#"<p>One<br><img src="a.png"></p>"#,
+            CONFIG,
+            &registry(),
+        )
+    }
+}
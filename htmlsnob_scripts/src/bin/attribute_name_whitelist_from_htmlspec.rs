@@ -5,6 +5,7 @@ use std::collections::{BTreeMap, HashMap};
 use std::fs;
 
 #[derive(Debug, Deserialize)]
+#[allow(dead_code)]
 struct HtmlElement {
     attributes: Vec<String>,
     #[serde(default)]
@@ -16,6 +17,7 @@ struct HtmlElement {
 }
 
 #[derive(Debug, Deserialize)]
+#[allow(dead_code)]
 struct SourceData {
     #[serde(rename = "__META__")]
     meta: serde_json::Value,
@@ -0,0 +1,173 @@
+// Run with `cargo run --bin content_model_from_htmlspec`
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct HtmlElement {
+    attributes: Vec<String>,
+    #[serde(default)]
+    categories: Vec<String>,
+    #[serde(default)]
+    children: Vec<String>,
+    #[serde(default)]
+    desc: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct SourceData {
+    #[serde(rename = "__META__")]
+    meta: serde_json::Value,
+    #[serde(flatten)]
+    tags: HashMap<String, HtmlElement>,
+}
+
+#[derive(Debug, Serialize)]
+struct Rules {
+    rules: Vec<RuleEntry>,
+}
+
+#[derive(Debug, Serialize)]
+struct RuleEntry {
+    kind: String,
+    categories: BTreeMap<String, Vec<String>>,
+    conditional_categories: BTreeMap<String, BTreeMap<String, String>>,
+    permitted_content: BTreeMap<String, Vec<String>>,
+    conditional_permitted_content: BTreeMap<String, BTreeMap<String, Vec<String>>>,
+    disallowed_descendants: BTreeMap<String, Vec<String>>,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // 1. Load the JSON file
+    let json_content = fs::read_to_string("htmlsnob_scripts/data/elements.json")?;
+    let data: SourceData = serde_json::from_str(&json_content)?;
+
+    // 2. Categories that depend on attributes, which the data doesn't have
+    let conditional_categories = [
+        ("a", "interactive", "href"),
+        ("audio", "interactive", "controls"),
+        ("img", "interactive", "usemap"),
+        ("object", "interactive", "usemap"),
+        ("video", "interactive", "controls"),
+    ];
+
+    // 3. Restrictions on descendants from the content models in the spec, which the data doesn't
+    // have either
+    let disallowed_descendants = [
+        ("a", vec!["interactive", "a"]),
+        (
+            "address",
+            vec!["heading", "sectioning", "header", "footer", "address"],
+        ),
+        ("audio", vec!["audio", "video"]),
+        ("button", vec!["interactive"]),
+        ("caption", vec!["table"]),
+        ("dfn", vec!["dfn"]),
+        ("dt", vec!["heading", "sectioning", "header", "footer"]),
+        ("footer", vec!["header", "footer"]),
+        ("form", vec!["form"]),
+        ("header", vec!["header", "footer"]),
+        ("label", vec!["label"]),
+        ("meter", vec!["meter"]),
+        ("progress", vec!["progress"]),
+        ("th", vec!["heading", "sectioning", "header", "footer"]),
+        ("video", vec!["audio", "video"]),
+    ];
+
+    // 4. Content models that depend on the parent, which the data doesn't have either
+    let conditional_permitted_content = [("div", "dl", vec!["dd", "dt", "script-supporting"])];
+
+    // 5. Transform data
+    let mut categories: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut permitted_content: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+    for (tag_name, element) in data.tags {
+        let mut tag_categories: Vec<String> = element
+            .categories
+            .into_iter()
+            // `th` was interactive in an older version of the spec
+            .filter(|category| {
+                category != "none" && !(tag_name == "th" && category == "interactive")
+            })
+            .collect();
+        tag_categories.sort();
+        categories.insert(tag_name.clone(), tag_categories);
+
+        // Elements without children are void or have content the data doesn't describe, like
+        // `template`, and the content of foreign elements is per their own specs
+        if element.children.is_empty()
+            || element
+                .children
+                .iter()
+                .any(|child| child == "varies" || child.starts_with("per ["))
+        {
+            continue;
+        }
+
+        let mut children: Vec<String> = element
+            .children
+            .iter()
+            .filter_map(|child| match child.as_str() {
+                // Only text, no elements. The spec describes the text of `script` in prose.
+                "text" | "script, data, or script documentation" => None,
+                "heading content" => Some("heading".to_string()),
+                "metadata content" => Some("metadata".to_string()),
+                "one img" => Some("img".to_string()),
+                "script-supporting elements" => Some("script-supporting".to_string()),
+                child => Some(child.to_string()),
+            })
+            .collect();
+        children.sort();
+        permitted_content.insert(tag_name, children);
+    }
+
+    let output = Rules {
+        rules: vec![RuleEntry {
+            kind: "content_model".to_string(),
+            categories,
+            conditional_categories: conditional_categories.into_iter().fold(
+                BTreeMap::new(),
+                |mut map, (tag_name, category, attribute)| {
+                    map.entry(tag_name.to_string())
+                        .or_insert_with(BTreeMap::new)
+                        .insert(category.to_string(), attribute.to_string());
+                    map
+                },
+            ),
+            permitted_content,
+            conditional_permitted_content: conditional_permitted_content.into_iter().fold(
+                BTreeMap::new(),
+                |mut map, (tag_name, parent, permitted)| {
+                    map.entry(tag_name.to_string())
+                        .or_insert_with(BTreeMap::new)
+                        .insert(
+                            parent.to_string(),
+                            permitted.into_iter().map(String::from).collect(),
+                        );
+                    map
+                },
+            ),
+            disallowed_descendants: disallowed_descendants
+                .into_iter()
+                .map(|(tag_name, disallowed)| {
+                    (
+                        tag_name.to_string(),
+                        disallowed.into_iter().map(String::from).collect(),
+                    )
+                })
+                .collect(),
+        }],
+    };
+
+    // 6. Serialize to TOML string
+    let toml_string = toml::to_string_pretty(&output)?;
+    println!("{}", toml_string);
+
+    // 7. Write to the default config, which the CLI and the language server include
+    fs::write("default_config/content_model.toml", toml_string)?;
+
+    Ok(())
+}
@@ -0,0 +1,56 @@
+// Run with `cargo run --bin foreign_names_from_specs`
+
+use serde::Deserialize;
+use std::fmt::Write;
+use std::fs;
+
+#[derive(Debug, Deserialize)]
+struct Names {
+    elements: Vec<String>,
+    attributes: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct SourceData {
+    #[serde(rename = "__META__")]
+    meta: serde_json::Value,
+    svg: Names,
+    math: Names,
+}
+
+/// A constant with the names, one per line, that rustfmt leaves as it is
+fn name_table(output: &mut String, name: &str, names: &[String]) -> std::fmt::Result {
+    writeln!(output, "#[rustfmt::skip]")?;
+    writeln!(output, "pub const {name}: [&str; {}] = [", names.len())?;
+    for name in names {
+        writeln!(output, "    {name:?},")?;
+    }
+    writeln!(output, "];")?;
+    writeln!(output)
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // 1. Load the JSON file
+    let json_content = fs::read_to_string("htmlsnob_scripts/data/foreign_elements.json")?;
+    let data: SourceData = serde_json::from_str(&json_content)?;
+
+    // 2. Generate the case-sensitive name tables
+    let mut output = String::new();
+    writeln!(
+        output,
+        "// Generated from `htmlsnob_scripts/data/foreign_elements.json` by\n\
+         // `cargo run --bin foreign_names_from_specs`, do not edit.\n"
+    )?;
+    name_table(&mut output, "SVG_ELEMENTS", &data.svg.elements)?;
+    name_table(&mut output, "SVG_ATTRIBUTES", &data.svg.attributes)?;
+    name_table(&mut output, "MATHML_ELEMENTS", &data.math.elements)?;
+    name_table(&mut output, "MATHML_ATTRIBUTES", &data.math.attributes)?;
+    let output = output.trim_end().to_string() + "\n";
+    println!("{}", output);
+
+    // 3. Write to the core crate, where the namespaces are tracked
+    fs::write("htmlsnob/src/foreign_names.rs", output)?;
+
+    Ok(())
+}
@@ -0,0 +1,34 @@
+// Run with `cargo run --bin void_elements_from_specs`
+
+use std::fmt::Write;
+use std::fs;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // 1. Load the JSON file, which has the items of an array without the brackets
+    let json_content = fs::read_to_string("htmlsnob_scripts/data/void_elements.json")?;
+    let names: Vec<String> = serde_json::from_str(&format!("[{json_content}]"))?;
+
+    // 2. Generate the name table
+    let mut output = String::new();
+    writeln!(
+        output,
+        "// Generated from `htmlsnob_scripts/data/void_elements.json` by\n\
+         // `cargo run --bin void_elements_from_specs`, do not edit.\n"
+    )?;
+    writeln!(output, "#[rustfmt::skip]")?;
+    writeln!(
+        output,
+        "pub const VOID_ELEMENTS: [&str; {}] = [",
+        names.len()
+    )?;
+    for name in &names {
+        writeln!(output, "    {name:?},")?;
+    }
+    writeln!(output, "];")?;
+    println!("{}", output);
+
+    // 3. Write to the core crate, where the parser uses them
+    fs::write("htmlsnob/src/void_elements.rs", output)?;
+
+    Ok(())
+}